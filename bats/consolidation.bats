@@ -0,0 +1,66 @@
+#!/usr/bin/env bats
+
+load "helpers"
+
+setup_file() {
+  restart_bitcoin_stack
+  reset_pg
+  bitcoind_init
+  start_daemon
+  bria_init
+}
+
+teardown_file() {
+  stop_daemon
+}
+
+@test "consolidation: Fund the wallet with several small utxos" {
+  for i in {1..3}; do
+    bria_address=$(bria_cmd new-address -w default | jq -r '.address')
+    bitcoin_cli -regtest sendtoaddress ${bria_address} 0.01
+  done
+  bitcoin_cli -generate 10
+
+  for i in {1..30}; do
+    cache_wallet_balance
+    [[ $(cached_current_settled) == 3000000 ]] && break
+    sleep 1
+  done
+  [[ $(cached_current_settled) == 3000000 ]] || exit 1
+}
+
+@test "consolidation: Queue without payouts batches the wallet's utxos" {
+  wallet_id=$(bria_cmd list-wallets | jq -r '.wallets[] | select(.name == "default") | .id')
+  bria_cmd create-payout-queue --name consolidate --interval-trigger 5 \
+    --consolidation-below-fee-rate 1000 \
+    --consolidation-wallet-id "${wallet_id}" \
+    --consolidation-max-utxos 10
+
+  for i in {1..30}; do
+    batch_id=$(bria_cmd list-batches -q consolidate | jq -r '.batches[0].id')
+    [[ "${batch_id}" != "null" ]] && break
+    sleep 1
+  done
+  [[ "${batch_id}" != "null" ]] || exit 1
+
+  n_payouts=$(bria_cmd list-batches -q consolidate | jq -r '.batches[0].nPayouts')
+  [[ "${n_payouts}" == "0" ]] || exit 1
+
+  batch=$(bria_cmd get-batch -b "${batch_id}")
+  summary_wallet_id=$(jq -r '.walletSummaries[0].walletId' <<< "${batch}")
+  fee_sats=$(jq -r '.walletSummaries[0].feeSats' <<< "${batch}")
+  [[ "${summary_wallet_id}" == "${wallet_id}" ]] || exit 1
+  [[ "${fee_sats}" != "0" ]] || exit 1
+
+  n_inputs=$(bitcoin_cli decodepsbt "$(jq -r '.unsignedPsbt' <<< "${batch}")" | jq '.tx.vin | length')
+  [[ "${n_inputs}" == "3" ]] || exit 1
+
+  # batch_wallet_accounting books the batch fees as pending for the wallet
+  for i in {1..30}; do
+    cache_wallet_balance
+    [[ $(cached_pending_fees) == "${fee_sats}" ]] && break
+    sleep 1
+  done
+  [[ $(cached_pending_fees) == "${fee_sats}" ]] || exit 1
+  [[ $(cached_encumbered_outgoing) == 0 ]] || exit 1
+}
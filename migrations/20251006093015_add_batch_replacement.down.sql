@@ -0,0 +1,2 @@
+ALTER TABLE bria_batch_wallet_summaries DROP COLUMN batch_replaced_ledger_tx_id;
+ALTER TABLE bria_batches DROP COLUMN replaces_batch_id;
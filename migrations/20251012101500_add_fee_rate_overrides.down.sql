@@ -0,0 +1 @@
+DROP TABLE bria_fee_rate_overrides;
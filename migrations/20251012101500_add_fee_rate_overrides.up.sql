@@ -0,0 +1,7 @@
+CREATE TABLE bria_fee_rate_overrides (
+  id UUID PRIMARY KEY NOT NULL,
+  sat_per_vb DOUBLE PRECISION NOT NULL,
+  expires_at TIMESTAMPTZ NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX idx_bria_fee_rate_overrides_expires_at ON bria_fee_rate_overrides (expires_at);
@@ -0,0 +1,6 @@
+DROP INDEX idx_bria_payouts_batch_id;
+DROP INDEX idx_bria_payouts_profile_id;
+DROP INDEX idx_bria_payouts_payout_queue_id;
+DROP INDEX idx_bria_payouts_account_id_destination_address;
+DROP INDEX idx_bria_payouts_account_id_created_at;
+ALTER TABLE bria_payouts DROP COLUMN destination_address;
@@ -0,0 +1,15 @@
+ALTER TABLE bria_payouts ADD COLUMN destination_address VARCHAR;
+UPDATE bria_payouts p
+SET destination_address = COALESCE(
+  e.event->'destination'->>'value',
+  e.event->'destination'->>'address'
+)
+FROM bria_payout_events e
+WHERE e.id = p.id AND e.event_type = 'initialized';
+ALTER TABLE bria_payouts ALTER COLUMN destination_address SET NOT NULL;
+
+CREATE INDEX idx_bria_payouts_account_id_created_at ON bria_payouts (account_id, created_at);
+CREATE INDEX idx_bria_payouts_account_id_destination_address ON bria_payouts (account_id, destination_address);
+CREATE INDEX idx_bria_payouts_payout_queue_id ON bria_payouts (payout_queue_id);
+CREATE INDEX idx_bria_payouts_profile_id ON bria_payouts (profile_id);
+CREATE INDEX idx_bria_payouts_batch_id ON bria_payouts (batch_id);
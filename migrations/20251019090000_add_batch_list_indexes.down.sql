@@ -0,0 +1,4 @@
+DROP INDEX idx_bria_utxos_spending_batch_id;
+DROP INDEX idx_bria_batches_bitcoin_tx_id;
+DROP INDEX idx_bria_batches_payout_queue_id;
+DROP INDEX idx_bria_batches_account_id_created_at;
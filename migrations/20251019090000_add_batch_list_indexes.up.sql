@@ -0,0 +1,4 @@
+CREATE INDEX idx_bria_batches_account_id_created_at ON bria_batches (account_id, created_at);
+CREATE INDEX idx_bria_batches_payout_queue_id ON bria_batches (payout_queue_id);
+CREATE INDEX idx_bria_batches_bitcoin_tx_id ON bria_batches (bitcoin_tx_id);
+CREATE INDEX idx_bria_utxos_spending_batch_id ON bria_utxos (spending_batch_id);
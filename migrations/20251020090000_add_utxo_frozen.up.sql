@@ -0,0 +1 @@
+ALTER TABLE bria_utxos ADD COLUMN frozen BOOLEAN NOT NULL DEFAULT FALSE;
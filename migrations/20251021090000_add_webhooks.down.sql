@@ -0,0 +1,5 @@
+DROP TABLE bria_webhook_deliveries;
+DROP TYPE WebhookDeliveryStatus;
+DROP TABLE bria_webhook_cursors;
+DROP TABLE bria_webhook_endpoint_events;
+DROP TABLE bria_webhook_endpoints;
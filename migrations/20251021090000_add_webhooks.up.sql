@@ -0,0 +1,40 @@
+CREATE TABLE bria_webhook_endpoints (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  account_id UUID REFERENCES bria_accounts(id) NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX idx_bria_webhook_endpoints_account_id ON bria_webhook_endpoints (account_id);
+
+CREATE TABLE bria_webhook_endpoint_events (
+  id UUID REFERENCES bria_webhook_endpoints(id) NOT NULL,
+  sequence INT NOT NULL,
+  event_type VARCHAR NOT NULL,
+  event JSONB NOT NULL,
+  context JSONB DEFAULT NULL,
+  recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE(id, sequence)
+);
+
+CREATE TABLE bria_webhook_cursors (
+  webhook_endpoint_id UUID PRIMARY KEY REFERENCES bria_webhook_endpoints(id),
+  account_id UUID REFERENCES bria_accounts(id) NOT NULL,
+  sequence BIGINT NOT NULL,
+  modified_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TYPE WebhookDeliveryStatus AS ENUM ('pending', 'delivered', 'dead_letter');
+
+CREATE TABLE bria_webhook_deliveries (
+  webhook_endpoint_id UUID REFERENCES bria_webhook_endpoints(id) NOT NULL,
+  account_id UUID REFERENCES bria_accounts(id) NOT NULL,
+  sequence BIGINT NOT NULL,
+  status WebhookDeliveryStatus NOT NULL DEFAULT 'pending',
+  attempts INT NOT NULL DEFAULT 0,
+  last_error VARCHAR,
+  event JSONB NOT NULL,
+  delivered_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  modified_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY(webhook_endpoint_id, sequence)
+);
+CREATE INDEX idx_bria_webhook_deliveries_status ON bria_webhook_deliveries (webhook_endpoint_id, status, sequence);
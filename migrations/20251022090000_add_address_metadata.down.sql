@@ -0,0 +1,2 @@
+DROP INDEX idx_bria_addresses_metadata;
+ALTER TABLE bria_addresses DROP COLUMN metadata;
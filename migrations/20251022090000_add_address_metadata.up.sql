@@ -0,0 +1,12 @@
+ALTER TABLE bria_addresses ADD COLUMN metadata JSONB;
+UPDATE bria_addresses a
+SET metadata = latest.metadata
+FROM (
+  SELECT DISTINCT ON (id) id, event->'metadata' AS metadata
+  FROM bria_address_events
+  WHERE event_type = 'metadata_updated'
+  ORDER BY id, sequence DESC
+) latest
+WHERE latest.id = a.id;
+
+CREATE INDEX idx_bria_addresses_metadata ON bria_addresses USING GIN (metadata jsonb_path_ops);
@@ -0,0 +1 @@
+DROP INDEX idx_bria_payout_events_id_event_type;
@@ -0,0 +1 @@
+CREATE INDEX idx_bria_payout_events_id_event_type ON bria_payout_events (id, event_type);
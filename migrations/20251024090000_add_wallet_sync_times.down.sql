@@ -0,0 +1 @@
+DROP TABLE bria_wallet_sync_times;
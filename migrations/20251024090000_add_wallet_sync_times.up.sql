@@ -0,0 +1,4 @@
+CREATE TABLE bria_wallet_sync_times (
+  wallet_id UUID PRIMARY KEY REFERENCES bria_wallets(id),
+  last_synced_at TIMESTAMPTZ NOT NULL
+);
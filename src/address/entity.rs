@@ -84,7 +84,7 @@ pub struct NewAddress {
     #[builder(setter(into))]
     pub(super) external_id: String,
     pub(super) kind: KeychainKind,
-    metadata: Option<serde_json::Value>,
+    pub(super) metadata: Option<serde_json::Value>,
 }
 
 impl NewAddress {
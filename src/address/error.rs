@@ -8,6 +8,8 @@ pub enum AddressError {
     Sqlx(sqlx::Error),
     #[error("ProfileError - EsEntityError: {0}")]
     EsEntityError(es_entity::EsEntityError),
+    #[error("AddressError - InvalidCursor: '{0}'")]
+    InvalidCursor(String),
     #[error("ProfileError - CursorDestructureError: {0}")]
     CursorDestructureError(#[from] es_entity::CursorDestructureError),
 }
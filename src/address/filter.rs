@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+
+use std::{fmt, str::FromStr};
+
+use super::error::AddressError;
+use crate::primitives::*;
+
+#[derive(Debug, Default, Clone)]
+pub struct AddressFilter {
+    pub external_id_prefix: Option<String>,
+    pub profile_id: Option<ProfileId>,
+    /// Path segments into the address metadata and the value expected there
+    pub metadata: Option<(Vec<String>, serde_json::Value)>,
+    pub include_change: bool,
+}
+
+/// Position of the last address returned in a page, ordered by (created_at, id).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: uuid::Uuid,
+}
+
+impl fmt::Display for AddressCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}_{}", self.created_at.timestamp_micros(), self.id)
+    }
+}
+
+impl FromStr for AddressCursor {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AddressError::InvalidCursor(s.to_string());
+        let (micros, id) = s.split_once('_').ok_or_else(invalid)?;
+        let created_at = micros
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::<Utc>::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let id = id.parse().map_err(|_| invalid())?;
+        Ok(Self { created_at, id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_roundtrip() {
+        let cursor = AddressCursor {
+            created_at: DateTime::<Utc>::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            id: uuid::Uuid::new_v4(),
+        };
+        let parsed = cursor.to_string().parse::<AddressCursor>().unwrap();
+        assert_eq!(cursor, parsed);
+        assert!("1700000000_not-a-uuid".parse::<AddressCursor>().is_err());
+    }
+}
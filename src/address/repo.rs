@@ -2,7 +2,7 @@ use es_entity::*;
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
-use super::{entity::*, error::AddressError};
+use super::{entity::*, error::AddressError, filter::*};
 
 use crate::primitives::{bitcoin::*, *};
 
@@ -25,7 +25,11 @@ use crate::primitives::{bitcoin::*, *};
             create(accessor = "kind.into()"),
             update(persist = false)
         ),
-        external_id(ty = "String")
+        external_id(ty = "String"),
+        metadata(
+            ty = "Option<serde_json::Value>",
+            update(accessor = "metadata().cloned()")
+        )
     ),
 )]
 pub struct Addresses {
@@ -44,8 +48,8 @@ impl Addresses {
     ) -> Result<(), AddressError> {
         let res = sqlx::query!(
             r#"INSERT INTO bria_addresses
-               (id, account_id, wallet_id, keychain_id, profile_id, address, kind, external_id)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT DO NOTHING"#,
+               (id, account_id, wallet_id, keychain_id, profile_id, address, kind, external_id, metadata)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) ON CONFLICT DO NOTHING"#,
             address.id,
             address.account_id as AccountId,
             address.wallet_id as WalletId,
@@ -54,6 +58,7 @@ impl Addresses {
             address.address.to_string(),
             pg::PgKeychainKind::from(address.kind) as pg::PgKeychainKind,
             address.external_id,
+            address.metadata,
         )
         .execute(op.as_executor())
         .await?;
@@ -65,51 +70,66 @@ impl Addresses {
         Ok(())
     }
 
-    pub async fn list_external_by_wallet_id(
+    pub async fn list_by_wallet_id(
         &self,
         account_id: AccountId,
         wallet_id: WalletId,
-    ) -> Result<Vec<WalletAddress>, AddressError> {
-        let mut wallet_addresses = Vec::new();
-        let mut query = es_entity::PaginatedQueryArgs::<
-            wallet_address_cursor::WalletAddressesByCreatedAtCursor,
-        > {
-            first: Default::default(),
-            after: None,
+        filter: &AddressFilter,
+        after: Option<AddressCursor>,
+        page_size: Option<usize>,
+    ) -> Result<(Vec<WalletAddress>, Option<AddressCursor>), AddressError> {
+        let (id, created_at) = if let Some(after) = after {
+            (Some(after.id), Some(after.created_at))
+        } else {
+            (None, None)
+        };
+        let (metadata_contains, metadata_path, metadata_value) = match filter.metadata.clone() {
+            Some((path, value)) => (
+                Some(metadata_containing(&path, value.clone())),
+                Some(path),
+                Some(value),
+            ),
+            None => (None, None, None),
         };
-        loop {
-            let (id, created_at) = if let Some(after) = query.after {
-                (Some(after.id), Some(after.created_at))
-            } else {
-                (None, None)
-            };
-
-            let (entities, has_next_page) = es_entity::es_query!(
-                entity = WalletAddress,
-                r#"
-                SELECT *
-                FROM bria_addresses
-                WHERE account_id = $1 AND wallet_id = $2 AND kind = 'external'
-                AND (COALESCE((created_at, id) > ($4, $3), $3 IS NULL))
-                ORDER BY created_at, id"#,
-                account_id as AccountId,
-                wallet_id as WalletId,
-                id,
-                created_at
-            )
-            .fetch_n(self.pool(), query.first)
-            .await?;
 
-            wallet_addresses.extend(entities);
+        let (entities, has_next_page) = es_entity::es_query!(
+            entity = WalletAddress,
+            r#"
+            SELECT *
+            FROM bria_addresses
+            WHERE account_id = $1 AND wallet_id = $2
+            AND (kind = 'external' OR $3)
+            AND ($4::VARCHAR IS NULL OR starts_with(external_id, $4))
+            AND ($5::UUID IS NULL OR profile_id = $5)
+            AND ($6::JSONB IS NULL OR (metadata @> $6 AND metadata #> $7 = $8::JSONB))
+            AND (COALESCE((created_at, id) > ($10, $9), $9 IS NULL))
+            ORDER BY created_at, id"#,
+            account_id as AccountId,
+            wallet_id as WalletId,
+            filter.include_change,
+            filter.external_id_prefix,
+            filter.profile_id as Option<ProfileId>,
+            metadata_contains,
+            metadata_path.as_deref(),
+            metadata_value,
+            id,
+            created_at
+        )
+        .fetch_n(self.pool(), page_size.unwrap_or(usize::MAX))
+        .await?;
 
-            if !has_next_page {
-                break;
-            }
-            query.after = wallet_addresses
+        let next_cursor = if has_next_page {
+            entities
                 .last()
-                .map(wallet_address_cursor::WalletAddressesByCreatedAtCursor::from);
-        }
-        Ok(wallet_addresses)
+                .map(wallet_address_cursor::WalletAddressesByCreatedAtCursor::from)
+                .map(|cursor| AddressCursor {
+                    created_at: cursor.created_at,
+                    id: cursor.id,
+                })
+        } else {
+            None
+        };
+        Ok((entities, next_cursor))
     }
 
     pub async fn find_by_account_id_and_address(
@@ -150,3 +170,12 @@ impl Addresses {
         Ok(wallet_address)
     }
 }
+
+/// Wraps `value` in objects keyed by `path` so the filter can use the GIN index via `@>`
+fn metadata_containing(path: &[String], value: serde_json::Value) -> serde_json::Value {
+    path.iter().rev().fold(value, |inner, key| {
+        let mut map = serde_json::Map::new();
+        map.insert(key.clone(), inner);
+        serde_json::Value::Object(map)
+    })
+}
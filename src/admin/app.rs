@@ -2,7 +2,13 @@ use tracing::instrument;
 
 use super::{error::*, keys::*};
 use crate::{
-    account::*, dev_constants, job_svc::JobSvc, ledger::Ledger, primitives::bitcoin, profile::*,
+    account::*,
+    dev_constants,
+    fees::{FeeRateOverride, FeeRateOverrides},
+    job_svc::JobSvc,
+    ledger::Ledger,
+    primitives::bitcoin,
+    profile::*,
 };
 
 const BOOTSTRAP_KEY_NAME: &str = "admin_bootstrap_key";
@@ -12,6 +18,7 @@ pub struct AdminApp {
     accounts: Accounts,
     profiles: Profiles,
     ledger: Ledger,
+    fee_rate_overrides: FeeRateOverrides,
     network: bitcoin::Network,
     job_svc: JobSvc,
 }
@@ -23,6 +30,7 @@ impl AdminApp {
             accounts: Accounts::new(&pool),
             profiles: Profiles::new(&pool),
             ledger: Ledger::new(&pool),
+            fee_rate_overrides: FeeRateOverrides::new(&pool),
             network,
             job_svc,
         }
@@ -111,4 +119,28 @@ impl AdminApp {
     pub async fn list_accounts(&self) -> Result<Vec<Account>, AdminApiError> {
         Ok(self.accounts.list().await?)
     }
+
+    #[instrument(name = "admin_app.set_fee_rate_override", skip(self), err)]
+    pub async fn set_fee_rate_override(
+        &self,
+        sat_per_vb: f64,
+        expires_in: std::time::Duration,
+    ) -> Result<FeeRateOverride, AdminApiError> {
+        if !sat_per_vb.is_finite() || sat_per_vb < 1.0 {
+            return Err(AdminApiError::InvalidFeeRate(sat_per_vb));
+        }
+        let expires_at = chrono::Utc::now() + expires_in;
+        Ok(self
+            .fee_rate_overrides
+            .set(
+                bitcoin::FeeRate::from_sat_per_vb(sat_per_vb as f32),
+                expires_at,
+            )
+            .await?)
+    }
+
+    #[instrument(name = "admin_app.clear_fee_rate_override", skip(self), err)]
+    pub async fn clear_fee_rate_override(&self) -> Result<(), AdminApiError> {
+        Ok(self.fee_rate_overrides.clear().await?)
+    }
 }
@@ -1,8 +1,8 @@
 use thiserror::Error;
 
 use crate::{
-    account::error::AccountError, app::error::ApplicationError, job_svc::JobSvcError,
-    ledger::error::LedgerError, profile::error::ProfileError,
+    account::error::AccountError, app::error::ApplicationError, fees::error::FeeEstimationError,
+    job_svc::JobSvcError, ledger::error::LedgerError, profile::error::ProfileError,
 };
 
 #[allow(clippy::large_enum_variant)]
@@ -16,6 +16,8 @@ pub enum AdminApiError {
     BriaError(ApplicationError),
     #[error("AdminApiError - BadNetworkForDev")]
     BadNetworkForDev,
+    #[error("AdminApiError - InvalidFeeRate: {0}")]
+    InvalidFeeRate(f64),
     #[error("{0}")]
     AccountError(#[from] AccountError),
     #[error("{0}")]
@@ -24,6 +26,8 @@ pub enum AdminApiError {
     LedgerError(#[from] LedgerError),
     #[error("{0}")]
     JobSvcError(#[from] JobSvcError),
+    #[error("{0}")]
+    FeeEstimationError(#[from] FeeEstimationError),
     #[error("AdminApiError - DevBootstrapError: {0}")]
     DevBootstrapError(#[from] anyhow::Error),
 }
@@ -74,6 +74,38 @@ impl AdminService for Admin {
             accounts: response_accounts,
         }))
     }
+
+    #[instrument(skip_all, err)]
+    async fn set_fee_rate_override(
+        &self,
+        request: Request<SetFeeRateOverrideRequest>,
+    ) -> Result<Response<SetFeeRateOverrideResponse>, Status> {
+        let admin_api_key = extract_api_token(&request)?;
+        self.app.authenticate(admin_api_key).await?;
+        let SetFeeRateOverrideRequest {
+            sat_per_vb,
+            expires_in_secs,
+        } = request.into_inner();
+        let fee_rate_override = self
+            .app
+            .set_fee_rate_override(sat_per_vb, std::time::Duration::from_secs(expires_in_secs))
+            .await?;
+        Ok(Response::new(SetFeeRateOverrideResponse {
+            sat_per_vb: fee_rate_override.fee_rate.as_sat_per_vb() as f64,
+            expires_at: fee_rate_override.expires_at.timestamp() as u32,
+        }))
+    }
+
+    #[instrument(skip_all, err)]
+    async fn clear_fee_rate_override(
+        &self,
+        request: Request<ClearFeeRateOverrideRequest>,
+    ) -> Result<Response<ClearFeeRateOverrideResponse>, Status> {
+        let admin_api_key = extract_api_token(&request)?;
+        self.app.authenticate(admin_api_key).await?;
+        self.app.clear_fee_rate_override().await?;
+        Ok(Response::new(ClearFeeRateOverrideResponse {}))
+    }
 }
 
 pub(crate) async fn start(
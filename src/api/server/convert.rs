@@ -7,6 +7,7 @@ use crate::{
     app::error::*,
     batch::*,
     batch_inclusion::PayoutWithInclusionEstimate,
+    fees::{FeeRateQuote, FeeRateSource},
     outbox::*,
     payout::*,
     payout_queue::*,
@@ -17,6 +18,7 @@ use crate::{
     utxo::*,
     wallet::balance::WalletBalanceSummary,
     wallet::*,
+    webhook::*,
     xpub::*,
 };
 
@@ -39,10 +41,56 @@ impl From<SpendingPolicy> for proto::SpendingPolicy {
                 .map(|addr| addr.to_string())
                 .collect(),
             max_payout_sats: sp.max_payout.map(u64::from),
+            limits: sp
+                .limits
+                .into_iter()
+                .map(proto::SpendingLimit::from)
+                .collect(),
+            approval: sp.approval.map(|approval| proto::ApprovalPolicy {
+                threshold_sats: u64::from(approval.threshold),
+                required_approvals: approval.required_approvals,
+                approver_profile_ids: approval
+                    .approvers
+                    .into_iter()
+                    .map(|id| id.to_string())
+                    .collect(),
+            }),
         }
     }
 }
 
+impl From<SpendingLimit> for proto::SpendingLimit {
+    fn from(limit: SpendingLimit) -> Self {
+        Self {
+            window_secs: limit.window_secs,
+            max_amount_sats: limit.max_amount.map(u64::from),
+            max_payouts: limit.max_payouts,
+        }
+    }
+}
+
+impl TryFrom<proto::SpendingLimit> for SpendingLimit {
+    type Error = tonic::Status;
+
+    fn try_from(limit: proto::SpendingLimit) -> Result<Self, Self::Error> {
+        if limit.window_secs == 0 {
+            return Err(tonic::Status::invalid_argument(
+                "spending limit window must be greater than 0 seconds",
+            ));
+        }
+        if limit.max_amount_sats.is_none() && limit.max_payouts.is_none() {
+            return Err(tonic::Status::invalid_argument(
+                "spending limit must set max_amount_sats or max_payouts",
+            ));
+        }
+        Ok(Self {
+            window_secs: limit.window_secs,
+            max_amount: limit.max_amount_sats.map(Satoshis::from),
+            max_payouts: limit.max_payouts,
+        })
+    }
+}
+
 impl TryFrom<(proto::SpendingPolicy, bitcoin::Network)> for SpendingPolicy {
     type Error = tonic::Status;
 
@@ -58,6 +106,35 @@ impl TryFrom<(proto::SpendingPolicy, bitcoin::Network)> for SpendingPolicy {
         Ok(Self {
             allowed_payout_addresses,
             max_payout: sp.max_payout_sats.map(Satoshis::from),
+            limits: sp
+                .limits
+                .into_iter()
+                .map(SpendingLimit::try_from)
+                .collect::<Result<_, _>>()?,
+            approval: sp.approval.map(ApprovalPolicy::try_from).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<proto::ApprovalPolicy> for ApprovalPolicy {
+    type Error = tonic::Status;
+
+    fn try_from(approval: proto::ApprovalPolicy) -> Result<Self, Self::Error> {
+        let approvers = approval
+            .approver_profile_ids
+            .iter()
+            .map(|id| id.parse::<ProfileId>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| tonic::Status::invalid_argument(err.to_string()))?;
+        if approvers.len() < approval.required_approvals as usize {
+            return Err(tonic::Status::invalid_argument(
+                "approval policy must list at least required_approvals approver profiles",
+            ));
+        }
+        Ok(Self {
+            threshold: Satoshis::from(approval.threshold_sats),
+            required_approvals: approval.required_approvals,
+            approvers,
         })
     }
 }
@@ -151,6 +228,118 @@ impl From<WalletUtxo> for proto::Utxo {
             address: utxo.address.map(|a| a.to_string()),
             change_output: utxo.kind == KeychainKind::Internal,
             block_height: utxo.block_height,
+            frozen: utxo.frozen,
+        }
+    }
+}
+
+impl From<WebhookEndpoint> for proto::WebhookEndpoint {
+    fn from(endpoint: WebhookEndpoint) -> Self {
+        Self {
+            id: endpoint.id.to_string(),
+            url: endpoint.url,
+            enabled: endpoint.enabled,
+        }
+    }
+}
+
+impl From<WebhookDeliveryStatus> for proto::WebhookDeliveryStatus {
+    fn from(status: WebhookDeliveryStatus) -> Self {
+        match status {
+            WebhookDeliveryStatus::Pending => proto::WebhookDeliveryStatus::Pending,
+            WebhookDeliveryStatus::Delivered => proto::WebhookDeliveryStatus::Delivered,
+            WebhookDeliveryStatus::DeadLetter => proto::WebhookDeliveryStatus::DeadLetter,
+        }
+    }
+}
+
+impl From<proto::WebhookDeliveryStatus> for WebhookDeliveryStatus {
+    fn from(status: proto::WebhookDeliveryStatus) -> Self {
+        match status {
+            proto::WebhookDeliveryStatus::Pending => WebhookDeliveryStatus::Pending,
+            proto::WebhookDeliveryStatus::Delivered => WebhookDeliveryStatus::Delivered,
+            proto::WebhookDeliveryStatus::DeadLetter => WebhookDeliveryStatus::DeadLetter,
+        }
+    }
+}
+
+impl From<WebhookDelivery> for proto::WebhookDelivery {
+    fn from(delivery: WebhookDelivery) -> Self {
+        let status: proto::WebhookDeliveryStatus = delivery.status.into();
+        Self {
+            sequence: u64::from(delivery.sequence),
+            status: status as i32,
+            attempts: delivery.attempts,
+            last_error: delivery.last_error,
+            delivered_at: delivery.delivered_at.map(|at| at.timestamp() as u32),
+            created_at: delivery.created_at.timestamp() as u32,
+        }
+    }
+}
+
+impl From<proto::PayoutStatus> for PayoutStatus {
+    fn from(status: proto::PayoutStatus) -> Self {
+        match status {
+            proto::PayoutStatus::Queued => PayoutStatus::Queued,
+            proto::PayoutStatus::Committed => PayoutStatus::Committed,
+            proto::PayoutStatus::Broadcast => PayoutStatus::Broadcast,
+            proto::PayoutStatus::Settled => PayoutStatus::Settled,
+            proto::PayoutStatus::Cancelled => PayoutStatus::Cancelled,
+        }
+    }
+}
+
+impl From<proto::BatchStatus> for BatchStatus {
+    fn from(status: proto::BatchStatus) -> Self {
+        match status {
+            proto::BatchStatus::Pending => BatchStatus::Pending,
+            proto::BatchStatus::Broadcast => BatchStatus::Broadcast,
+            proto::BatchStatus::Confirmed => BatchStatus::Confirmed,
+        }
+    }
+}
+
+impl From<BatchStatus> for proto::BatchStatus {
+    fn from(status: BatchStatus) -> Self {
+        match status {
+            BatchStatus::Pending => proto::BatchStatus::Pending,
+            BatchStatus::Broadcast => proto::BatchStatus::Broadcast,
+            BatchStatus::Confirmed => proto::BatchStatus::Confirmed,
+        }
+    }
+}
+
+impl From<(BatchListing, Option<BatchSigningSession>)> for proto::BatchListing {
+    fn from((batch, signing_session): (BatchListing, Option<BatchSigningSession>)) -> Self {
+        proto::BatchListing {
+            id: batch.id.to_string(),
+            payout_queue_id: batch.payout_queue_id.to_string(),
+            tx_id: batch.bitcoin_tx_id.to_string(),
+            total_fee_sats: u64::from(batch.total_fee_sats),
+            cpfp_fee_sats: u64::from(batch.cpfp_fee_sats),
+            n_payouts: batch.n_payouts,
+            status: proto::BatchStatus::from(batch.status) as i32,
+            signing_state: signing_session.map(|session| format!("{:?}", session.state())),
+            replaces_batch_id: batch.replaces_batch_id.map(|id| id.to_string()),
+            replaced_by_batch_id: batch.replaced_by_batch_id.map(|id| id.to_string()),
+            created_at: batch.created_at.timestamp() as u32,
+        }
+    }
+}
+
+impl TryFrom<proto::UtxoStatus> for UtxoStatus {
+    type Error = tonic::Status;
+
+    fn try_from(status: proto::UtxoStatus) -> Result<Self, Self::Error> {
+        match status {
+            proto::UtxoStatus::Unspecified => {
+                Err(tonic::Status::invalid_argument("status must be specified"))
+            }
+            proto::UtxoStatus::Pending => Ok(UtxoStatus::Pending),
+            proto::UtxoStatus::Settled => Ok(UtxoStatus::Settled),
+            proto::UtxoStatus::Reserved => Ok(UtxoStatus::Reserved),
+            proto::UtxoStatus::Spent => Ok(UtxoStatus::Spent),
+            proto::UtxoStatus::Frozen => Ok(UtxoStatus::Frozen),
         }
     }
 }
@@ -172,6 +361,7 @@ impl From<PayoutWithInclusionEstimate> for proto::Payout {
         }: PayoutWithInclusionEstimate,
     ) -> Self {
         let cancelled = payout.is_cancelled();
+        let pending_approval = payout.is_pending_approval();
         let destination = match payout.destination {
             PayoutDestination::OnchainAddress { value } => {
                 proto::payout::Destination::OnchainAddress(value.to_string())
@@ -205,6 +395,13 @@ impl From<PayoutWithInclusionEstimate> for proto::Payout {
             batch_inclusion_estimated_at,
             tx_id,
             vout,
+            pending_approval,
+            not_before: payout.not_before.map(|time| time.timestamp() as u32),
+            spend_outpoints: payout
+                .spend_outpoints
+                .iter()
+                .map(|out| out.to_string())
+                .collect(),
         }
     }
 }
@@ -250,6 +447,20 @@ impl From<PayoutQueue> for proto::PayoutQueue {
             cpfp_payouts_after_mins: payout_queue.config.cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks: payout_queue.config.cpfp_payouts_after_blocks,
             force_min_change_sats: payout_queue.config.force_min_change_sats.map(u64::from),
+            min_fee_rate: payout_queue.config.min_fee_rate,
+            max_fee_rate: payout_queue.config.max_fee_rate,
+            consolidation: payout_queue.config.consolidation.map(|consolidation| {
+                proto::ConsolidationConfig {
+                    wallet_ids: consolidation
+                        .wallet_ids
+                        .iter()
+                        .map(|id| id.to_string())
+                        .collect(),
+                    fee_rate_threshold: consolidation.fee_rate_threshold,
+                    max_utxos: consolidation.max_utxos,
+                    max_utxo_sats: consolidation.max_utxo_sats.map(u64::from),
+                }
+            }),
         });
         proto::PayoutQueue {
             id,
@@ -260,6 +471,24 @@ impl From<PayoutQueue> for proto::PayoutQueue {
     }
 }
 
+impl From<FeeRateQuote> for proto::FeeRateQuote {
+    fn from(quote: FeeRateQuote) -> Self {
+        let source = match quote.source {
+            FeeRateSource::Override => proto::FeeRateSource::Override,
+            FeeRateSource::MempoolSpace => proto::FeeRateSource::MempoolSpace,
+            FeeRateSource::Blockstream => proto::FeeRateSource::Blockstream,
+            FeeRateSource::Bitcoind => proto::FeeRateSource::Bitcoind,
+        };
+        proto::FeeRateQuote {
+            sat_per_vb: quote.fee_rate.as_sat_per_vb() as f64,
+            source: source as i32,
+            fetched_at: quote.fetched_at.timestamp() as u32,
+            age_secs: quote.age().as_secs(),
+            stale: quote.stale,
+        }
+    }
+}
+
 impl From<TxPriority> for proto::TxPriority {
     fn from(priority: TxPriority) -> Self {
         match priority {
@@ -278,12 +507,46 @@ impl From<SigningSession> for proto::SigningSession {
             xpub_id: session.xpub_fingerprint.to_string(),
             failure_reason: session.failure_reason().map(|r| r.to_string()),
             state: format!("{:?}", session.state()),
+            attempts: session
+                .attempts()
+                .into_iter()
+                .map(|attempt| proto::SigningAttempt {
+                    recorded_at: attempt.recorded_at.timestamp() as u32,
+                    failure_reason: attempt.failure_reason.map(|r| r.to_string()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::ConsolidationConfig> for ConsolidationConfig {
+    type Error = tonic::Status;
+
+    fn try_from(consolidation: proto::ConsolidationConfig) -> Result<Self, Self::Error> {
+        if consolidation.max_utxos < 2 {
+            return Err(tonic::Status::invalid_argument(
+                "consolidation must merge at least 2 utxos",
+            ));
         }
+        let wallet_ids = consolidation
+            .wallet_ids
+            .iter()
+            .map(|id| id.parse())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| tonic::Status::invalid_argument("invalid consolidation wallet_id"))?;
+        Ok(Self {
+            wallet_ids,
+            fee_rate_threshold: consolidation.fee_rate_threshold,
+            max_utxos: consolidation.max_utxos,
+            max_utxo_sats: consolidation.max_utxo_sats.map(Satoshis::from),
+        })
     }
 }
 
-impl From<proto::PayoutQueueConfig> for PayoutQueueConfig {
-    fn from(proto_config: proto::PayoutQueueConfig) -> Self {
+impl TryFrom<proto::PayoutQueueConfig> for PayoutQueueConfig {
+    type Error = tonic::Status;
+
+    fn try_from(proto_config: proto::PayoutQueueConfig) -> Result<Self, Self::Error> {
         let tx_priority =
             proto::TxPriority::try_from(proto_config.tx_priority).map(TxPriority::from);
 
@@ -304,6 +567,12 @@ impl From<proto::PayoutQueueConfig> for PayoutQueueConfig {
             cpfp_payouts_after_mins: proto_config.cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks: proto_config.cpfp_payouts_after_blocks,
             force_min_change_sats: proto_config.force_min_change_sats.map(Satoshis::from),
+            min_fee_rate: proto_config.min_fee_rate,
+            max_fee_rate: proto_config.max_fee_rate,
+            consolidation: proto_config
+                .consolidation
+                .map(ConsolidationConfig::try_from)
+                .transpose()?,
             ..Self::default()
         };
 
@@ -313,7 +582,7 @@ impl From<proto::PayoutQueueConfig> for PayoutQueueConfig {
         if let Ok(tx_priority) = tx_priority {
             ret.tx_priority = tx_priority;
         }
-        ret
+        Ok(ret)
     }
 }
 
@@ -529,6 +798,92 @@ impl From<OutboxEvent<Augmentation>> for proto::BriaEvent {
                     }
                 }),
             }),
+            OutboxEventPayload::PayoutApproved {
+                id,
+                wallet_id,
+                payout_queue_id,
+                satoshis,
+                destination,
+                approved_by,
+                n_approvals,
+                required_approvals,
+                ..
+            } => proto::bria_event::Payload::PayoutApproved(proto::PayoutApproved {
+                id: id.to_string(),
+                wallet_id: wallet_id.to_string(),
+                payout_queue_id: payout_queue_id.to_string(),
+                satoshis: u64::from(satoshis),
+                destination: Some(match destination {
+                    PayoutDestination::OnchainAddress { value: destination } => {
+                        proto::payout_approved::Destination::OnchainAddress(destination.to_string())
+                    }
+                    PayoutDestination::Wallet { id, address } => {
+                        proto::payout_approved::Destination::Wallet(proto::BriaWalletDestination {
+                            wallet_id: id.to_string(),
+                            address: address.to_string(),
+                        })
+                    }
+                }),
+                approved_by_profile_id: approved_by.to_string(),
+                n_approvals,
+                required_approvals,
+            }),
+            OutboxEventPayload::PayoutRejected {
+                id,
+                wallet_id,
+                payout_queue_id,
+                satoshis,
+                destination,
+                rejected_by,
+                reason,
+                ..
+            } => proto::bria_event::Payload::PayoutRejected(proto::PayoutRejected {
+                id: id.to_string(),
+                wallet_id: wallet_id.to_string(),
+                payout_queue_id: payout_queue_id.to_string(),
+                satoshis: u64::from(satoshis),
+                destination: Some(match destination {
+                    PayoutDestination::OnchainAddress { value: destination } => {
+                        proto::payout_rejected::Destination::OnchainAddress(destination.to_string())
+                    }
+                    PayoutDestination::Wallet { id, address } => {
+                        proto::payout_rejected::Destination::Wallet(proto::BriaWalletDestination {
+                            wallet_id: id.to_string(),
+                            address: address.to_string(),
+                        })
+                    }
+                }),
+                rejected_by_profile_id: rejected_by.to_string(),
+                reason,
+            }),
+            OutboxEventPayload::WalletUpdated {
+                wallet_id,
+                name,
+                settle_income_after_n_confs,
+                settle_change_after_n_confs,
+            } => proto::bria_event::Payload::WalletUpdated(proto::WalletUpdated {
+                wallet_id: wallet_id.to_string(),
+                name,
+                config: Some(proto::WalletConfig {
+                    settle_income_after_n_confs,
+                    settle_change_after_n_confs,
+                }),
+            }),
+            OutboxEventPayload::PayoutQueueBatchDeferred {
+                payout_queue_id,
+                fee_rate_sat_per_vb,
+                max_fee_rate,
+                n_unbatched_payouts,
+                deferred_at,
+            } => proto::bria_event::Payload::PayoutQueueBatchDeferred(
+                proto::PayoutQueueBatchDeferred {
+                    payout_queue_id: payout_queue_id.to_string(),
+                    fee_rate_sat_per_vb: fee_rate_sat_per_vb as f64,
+                    max_fee_rate,
+                    n_unbatched_payouts: n_unbatched_payouts as u32,
+                    deferred_at: deferred_at.timestamp() as u32,
+                },
+            ),
             OutboxEventPayload::PayoutCommitted {
                 id,
                 tx_id,
@@ -652,7 +1007,10 @@ impl From<AddressAugmentation> for proto::WalletAddress {
 
 impl From<ApplicationError> for tonic::Status {
     fn from(err: ApplicationError) -> Self {
-        use crate::{address::error::*, payout::error::*, profile::error::*, wallet::error::*};
+        use crate::{
+            address::error::*, payout::error::*, profile::error::*, signing_session::error::*,
+            utxo::error::*, wallet::error::*, webhook::error::*,
+        };
 
         match err {
             ApplicationError::ProfileError(ProfileError::ProfileKeyNotFound) => {
@@ -694,18 +1052,36 @@ impl From<ApplicationError> for tonic::Status {
             ApplicationError::PayoutExceedsMaximum(_) => {
                 tonic::Status::permission_denied(err.to_string())
             }
+            ApplicationError::SpendingLimitExceeded(_) => {
+                tonic::Status::permission_denied(err.to_string())
+            }
             ApplicationError::SigningSessionNotFoundForBatchId(_) => {
                 tonic::Status::not_found(err.to_string())
             }
             ApplicationError::SigningSessionNotFoundForXPubFingerprint(_) => {
                 tonic::Status::not_found(err.to_string())
             }
+            ApplicationError::WalletError(WalletError::NameAlreadyExists) => {
+                tonic::Status::already_exists(err.to_string())
+            }
             ApplicationError::WalletError(WalletError::PsbtDoesNotHaveValidSignatures) => {
                 tonic::Status::invalid_argument(err.to_string())
             }
             ApplicationError::WalletError(WalletError::UnsignedTxnMismatch) => {
                 tonic::Status::invalid_argument(err.to_string())
             }
+            ApplicationError::WalletError(WalletError::KeychainNetworkMismatch(_))
+            | ApplicationError::WalletError(WalletError::KeychainAlreadyActive) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::SigningSessionError(
+                SigningSessionError::SignedPsbtInputsMismatch
+                | SigningSessionError::SignedPsbtOutputsMismatch
+                | SigningSessionError::SignedPsbtTransactionMismatch
+                | SigningSessionError::SignedPsbtInputUtxoMismatch(_)
+                | SigningSessionError::SignedPsbtFeeMismatch { .. }
+                | SigningSessionError::SignedPsbtSighashNotAll(_),
+            ) => tonic::Status::invalid_argument(err.to_string()),
             ApplicationError::CouldNotParseIncomingPsbt(_) => {
                 tonic::Status::invalid_argument(err.to_string())
             }
@@ -715,9 +1091,56 @@ impl From<ApplicationError> for tonic::Status {
             ApplicationError::PayoutError(PayoutError::PayoutAlreadyCancelled) => {
                 tonic::Status::failed_precondition(err.to_string())
             }
+            ApplicationError::PayoutError(PayoutError::PayoutNotPendingApproval)
+            | ApplicationError::PayoutError(PayoutError::PayoutAlreadyApprovedByProfile(_)) => {
+                tonic::Status::failed_precondition(err.to_string())
+            }
+            ApplicationError::PayoutError(PayoutError::SubmitterCannotDecideApproval)
+            | ApplicationError::PayoutError(PayoutError::ProfileNotAnApprover(_)) => {
+                tonic::Status::permission_denied(err.to_string())
+            }
             ApplicationError::CouldNotParseAddress(_) => {
                 tonic::Status::invalid_argument(err.to_string())
             }
+            ApplicationError::BatchAlreadyReplaced(_)
+            | ApplicationError::BatchAlreadySigned(_)
+            | ApplicationError::BatchSigningInProgress(_)
+            | ApplicationError::BatchAlreadyConfirmed(_)
+            | ApplicationError::BatchNotReadyForReplacement(_)
+            | ApplicationError::BatchChangeAlreadySpent(_)
+            | ApplicationError::CouldNotBuildReplacementBatch(_) => {
+                tonic::Status::failed_precondition(err.to_string())
+            }
+            ApplicationError::FeeBumpTooLow(_) | ApplicationError::FeeRateMustBePositive => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::AddressError(AddressError::InvalidCursor(_))
+            | ApplicationError::UtxoError(UtxoError::InvalidCursor(_)) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::UtxoError(UtxoError::UtxoNotInWallet(_)) => {
+                tonic::Status::not_found(err.to_string())
+            }
+            ApplicationError::UtxoError(UtxoError::UtxoFrozen(_))
+            | ApplicationError::UtxoError(UtxoError::UtxoAlreadyReserved(_))
+            | ApplicationError::UtxoError(UtxoError::UtxoNotSettled(_)) => {
+                tonic::Status::failed_precondition(err.to_string())
+            }
+            ApplicationError::InvalidFeeRateBounds => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::WebhookError(err) if err.was_not_found() => {
+                tonic::Status::not_found(err.to_string())
+            }
+            ApplicationError::WebhookError(WebhookError::DeliveryNotFound(_, _)) => {
+                tonic::Status::not_found(err.to_string())
+            }
+            ApplicationError::WebhookError(WebhookError::InvalidUrl(_)) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::WebhookError(WebhookError::DeliveryPending(_, _)) => {
+                tonic::Status::failed_precondition(err.to_string())
+            }
             _ => tonic::Status::internal(err.to_string()),
         }
     }
@@ -17,13 +17,21 @@ use proto::{bria_service_server::BriaService, *};
 
 use super::config::*;
 use crate::{
+    address::{AddressCursor, AddressFilter},
     app::{error::ApplicationError, *},
+    batch::{BatchFilter, BatchStatus},
+    outbox::{OutboxEventFilter, OUTBOX_EVENT_TYPES},
+    payout::{PayoutFilter, PayoutStatus},
     payout_queue,
     primitives::*,
     profile,
+    utxo::{UtxoCursor, UtxoFilter, UtxoStatus},
+    webhook,
 };
 
 pub const PROFILE_API_KEY_HEADER: &str = "x-bria-api-key";
+const MAX_ADDRESSES_PAGE_SIZE: usize = 1000;
+const MAX_UTXOS_PAGE_SIZE: usize = 1000;
 
 pub struct Bria {
     app: App,
@@ -200,6 +208,34 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.get_unsigned_psbt", skip_all, fields(error, error.level, error.message), err)]
+    async fn get_unsigned_psbt(
+        &self,
+        request: Request<GetUnsignedPsbtRequest>,
+    ) -> Result<Response<GetUnsignedPsbtResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let GetUnsignedPsbtRequest { batch_id, xpub_ref } = request.into_inner();
+            let session = self
+                .app
+                .get_unsigned_psbt(
+                    &profile,
+                    batch_id
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    xpub_ref,
+                )
+                .await?;
+            Ok(Response::new(GetUnsignedPsbtResponse {
+                signing_session_id: session.id.to_string(),
+                unsigned_psbt: session.unsigned_psbt.to_string(),
+            }))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.submit_signed_psbt", skip_all, fields(error, error.level, error.message), err)]
     async fn submit_signed_psbt(
         &self,
@@ -232,6 +268,58 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.list_signing_sessions", skip_all, fields(error, error.level, error.message), err)]
+    async fn list_signing_sessions(
+        &self,
+        request: Request<ListSigningSessionsRequest>,
+    ) -> Result<Response<ListSigningSessionsResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let ListSigningSessionsRequest { batch_id } = request.into_inner();
+            let sessions = self
+                .app
+                .list_signing_sessions(
+                    &profile,
+                    batch_id
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                )
+                .await?;
+            Ok(Response::new(ListSigningSessionsResponse {
+                signing_sessions: sessions
+                    .into_iter()
+                    .map(proto::SigningSession::from)
+                    .collect(),
+            }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.retry_signing", skip_all, fields(error, error.level, error.message), err)]
+    async fn retry_signing(
+        &self,
+        request: Request<RetrySigningRequest>,
+    ) -> Result<Response<RetrySigningResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let RetrySigningRequest { batch_id } = request.into_inner();
+            self.app
+                .retry_signing(
+                    &profile,
+                    batch_id
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                )
+                .await?;
+            Ok(Response::new(RetrySigningResponse {}))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.create_wallet", skip_all, fields(error, error.level, error.message), err)]
     async fn create_wallet(
         &self,
@@ -258,6 +346,17 @@ impl BriaService for Bria {
                         .create_wpkh_wallet(&profile, name, xpub, derivation_path)
                         .await?
                 }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Tr(keychain_config::Tr {
+                            xpub,
+                            derivation_path,
+                        })),
+                }) => {
+                    self.app
+                        .create_tr_wallet(&profile, name, xpub, derivation_path)
+                        .await?
+                }
                 Some(KeychainConfig {
                     config:
                         Some(keychain_config::Config::Descriptors(keychain_config::Descriptors {
@@ -411,11 +510,47 @@ impl BriaService for Bria {
 
             let key = extract_api_token(&request)?;
             let profile = self.app.authenticate(key).await?;
-            let wallet_name = request.into_inner().wallet_name;
+            let ListAddressesRequest {
+                wallet_name,
+                external_id_prefix,
+                profile_id,
+                metadata,
+                include_change,
+                after,
+                page_size,
+            } = request.into_inner();
+            let filter = AddressFilter {
+                external_id_prefix,
+                profile_id: profile_id
+                    .map(|id| id.parse())
+                    .transpose()
+                    .map_err(|_| Status::invalid_argument("invalid profile_id"))?,
+                metadata: metadata
+                    .map(|MetadataFilter { path, value }| {
+                        serde_json::from_str(&value)
+                            .map(|value| (path.split('.').map(String::from).collect(), value))
+                    })
+                    .transpose()
+                    .map_err(ApplicationError::CouldNotParseIncomingMetadata)?,
+                include_change: include_change.unwrap_or(false),
+            };
+            let after = after
+                .map(|cursor| cursor.parse::<AddressCursor>())
+                .transpose()
+                .map_err(ApplicationError::from)?;
+            if page_size == Some(0) {
+                return Err(Status::invalid_argument("page_size must be greater than 0"));
+            }
+            // Without paging arguments every address of the wallet is returned
+            let page_size = match (page_size, &after) {
+                (Some(size), _) => Some((size as usize).min(MAX_ADDRESSES_PAGE_SIZE)),
+                (None, Some(_)) => Some(MAX_ADDRESSES_PAGE_SIZE),
+                (None, None) => None,
+            };
 
-            let (wallet_id, addresses) = self
+            let (wallet_id, addresses, next_cursor) = self
                 .app
-                .list_external_addresses(&profile, wallet_name)
+                .list_addresses(&profile, wallet_name, filter, after, page_size)
                 .await?;
             let proto_addresses: Vec<proto::WalletAddress> = addresses
                 .into_iter()
@@ -424,6 +559,7 @@ impl BriaService for Bria {
             Ok(Response::new(ListAddressesResponse {
                 wallet_id: wallet_id.to_string(),
                 addresses: proto_addresses,
+                next_cursor: next_cursor.map(|cursor| cursor.to_string()),
             }))
         })
         .await
@@ -470,9 +606,51 @@ impl BriaService for Bria {
 
             let key = extract_api_token(&request)?;
             let profile = self.app.authenticate(key).await?;
-            let request = request.into_inner();
-            let (wallet_id, keychain_utxos) =
-                self.app.list_utxos(&profile, request.wallet_name).await?;
+            let ListUtxosRequest {
+                wallet_name,
+                keychain_id,
+                status,
+                min_value,
+                max_value,
+                min_detected_height,
+                max_detected_height,
+                after,
+                page_size,
+            } = request.into_inner();
+            let filter = UtxoFilter {
+                keychain_id: keychain_id
+                    .map(|id| id.parse())
+                    .transpose()
+                    .map_err(|_| Status::invalid_argument("invalid keychain_id"))?,
+                status: status
+                    .map(|status| {
+                        proto::UtxoStatus::try_from(status)
+                            .map_err(|_| Status::invalid_argument("invalid status"))
+                            .and_then(UtxoStatus::try_from)
+                    })
+                    .transpose()?,
+                min_value: min_value.map(Satoshis::from),
+                max_value: max_value.map(Satoshis::from),
+                min_detected_height,
+                max_detected_height,
+            };
+            let after = after
+                .map(|cursor| cursor.parse::<UtxoCursor>())
+                .transpose()
+                .map_err(ApplicationError::from)?;
+            if page_size == Some(0) {
+                return Err(Status::invalid_argument("page_size must be greater than 0"));
+            }
+            // Without paging arguments every utxo of the wallet is returned
+            let page_size = match (page_size, &after) {
+                (Some(size), _) => Some((size as usize).min(MAX_UTXOS_PAGE_SIZE)),
+                (None, Some(_)) => Some(MAX_UTXOS_PAGE_SIZE),
+                (None, None) => None,
+            };
+            let (wallet_id, keychain_utxos, next_cursor) = self
+                .app
+                .list_utxos(&profile, wallet_name, filter, after, page_size)
+                .await?;
 
             let proto_keychains: Vec<proto::KeychainUtxos> = keychain_utxos
                 .into_iter()
@@ -482,11 +660,60 @@ impl BriaService for Bria {
             Ok(Response::new(ListUtxosResponse {
                 wallet_id: wallet_id.to_string(),
                 keychains: proto_keychains,
+                next_cursor: next_cursor.map(|cursor| cursor.to_string()),
             }))
         })
         .await
     }
 
+    #[instrument(name = "bria.freeze_utxo", skip_all, fields(error, error.level, error.message), err)]
+    async fn freeze_utxo(
+        &self,
+        request: Request<FreezeUtxoRequest>,
+    ) -> Result<Response<FreezeUtxoResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let FreezeUtxoRequest {
+                wallet_name,
+                outpoint,
+            } = request.into_inner();
+            let outpoint = outpoint
+                .parse()
+                .map_err(|_| Status::invalid_argument("invalid outpoint"))?;
+            self.app
+                .freeze_utxo(&profile, wallet_name, outpoint)
+                .await?;
+            Ok(Response::new(FreezeUtxoResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.unfreeze_utxo", skip_all, fields(error, error.level, error.message), err)]
+    async fn unfreeze_utxo(
+        &self,
+        request: Request<UnfreezeUtxoRequest>,
+    ) -> Result<Response<UnfreezeUtxoResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let UnfreezeUtxoRequest {
+                wallet_name,
+                outpoint,
+            } = request.into_inner();
+            let outpoint = outpoint
+                .parse()
+                .map_err(|_| Status::invalid_argument("invalid outpoint"))?;
+            self.app
+                .unfreeze_utxo(&profile, wallet_name, outpoint)
+                .await?;
+            Ok(Response::new(UnfreezeUtxoResponse {}))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.create_payout_queue", skip_all, fields(error, error.level, error.message), err)]
     async fn create_payout_queue(
         &self,
@@ -504,7 +731,10 @@ impl BriaService for Bria {
                     &profile,
                     request.name,
                     request.description,
-                    request.config.map(payout_queue::PayoutQueueConfig::from),
+                    request
+                        .config
+                        .map(payout_queue::PayoutQueueConfig::try_from)
+                        .transpose()?,
                 )
                 .await?;
             Ok(Response::new(CreatePayoutQueueResponse {
@@ -531,6 +761,28 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.get_fee_rate", skip_all, fields(error, error.level, error.message), err)]
+    async fn get_fee_rate(
+        &self,
+        request: Request<GetFeeRateRequest>,
+    ) -> Result<Response<GetFeeRateResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let GetFeeRateRequest { tx_priority } = request.into_inner();
+            let tx_priority = proto::TxPriority::try_from(tx_priority)
+                .map(crate::primitives::TxPriority::from)
+                .map_err(|_| Status::invalid_argument("invalid tx_priority"))?;
+            let quote = self.app.get_fee_rate(&profile, tx_priority).await?;
+            Ok(Response::new(GetFeeRateResponse {
+                fee_rate_quote: Some(proto::FeeRateQuote::from(quote)),
+            }))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.estimate_payout_fee", skip_all, fields(error, error.level, error.message), err)]
     async fn estimate_payout_fee(
         &self,
@@ -549,7 +801,7 @@ impl BriaService for Bria {
                 satoshis,
             } = request;
 
-            let sats = match destination {
+            let (sats, quote) = match destination {
                 Some(proto::estimate_payout_fee_request::Destination::OnchainAddress(address)) => {
                     self.app
                         .estimate_payout_fee_to_address(
@@ -583,6 +835,7 @@ impl BriaService for Bria {
             };
             Ok(Response::new(EstimatePayoutFeeResponse {
                 satoshis: u64::from(sats),
+                fee_rate_quote: Some(proto::FeeRateQuote::from(quote)),
             }))
         })
         .await
@@ -606,7 +859,15 @@ impl BriaService for Bria {
                 satoshis,
                 external_id,
                 metadata,
+                not_before,
+                spend_outpoints,
             } = request;
+            let not_before = timestamp_arg(not_before, "not_before")?;
+            let spend_outpoints = spend_outpoints
+                .iter()
+                .map(|out| out.parse())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| Status::invalid_argument("invalid spend_outpoints"))?;
 
             let (id, estimated_time) = match destination {
                 Some(proto::submit_payout_request::Destination::OnchainAddress(address)) => {
@@ -622,6 +883,8 @@ impl BriaService for Bria {
                                 .map(serde_json::to_value)
                                 .transpose()
                                 .map_err(ApplicationError::CouldNotParseIncomingMetadata)?,
+                            not_before,
+                            spend_outpoints,
                         )
                         .await?
                 }
@@ -638,6 +901,8 @@ impl BriaService for Bria {
                                 .map(serde_json::to_value)
                                 .transpose()
                                 .map_err(ApplicationError::CouldNotParseIncomingMetadata)?,
+                            not_before,
+                            spend_outpoints,
                         )
                         .await?
                 }
@@ -672,12 +937,44 @@ impl BriaService for Bria {
                 wallet_name,
                 page,
                 page_size,
+                status,
+                payout_queue_name,
+                profile_id,
+                created_after,
+                created_before,
+                external_id,
+                destination_address,
             } = request;
             let page = page.unwrap_or(1);
             let page_size = page_size.unwrap_or(100);
+            let filter = PayoutFilter {
+                profile_id: profile_id
+                    .map(|id| id.parse())
+                    .transpose()
+                    .map_err(|_| Status::invalid_argument("invalid profile_id"))?,
+                status: status
+                    .map(|status| {
+                        proto::PayoutStatus::try_from(status)
+                            .map(PayoutStatus::from)
+                            .map_err(|_| Status::invalid_argument("invalid status"))
+                    })
+                    .transpose()?,
+                created_after: timestamp_arg(created_after, "created_after")?,
+                created_before: timestamp_arg(created_before, "created_before")?,
+                external_id,
+                destination_address,
+                ..Default::default()
+            };
             let payouts = self
                 .app
-                .list_payouts(&profile, wallet_name, page, page_size)
+                .list_payouts(
+                    &profile,
+                    wallet_name,
+                    payout_queue_name,
+                    filter,
+                    page,
+                    page_size,
+                )
                 .await?;
 
             let payout_messages: Vec<proto::Payout> =
@@ -751,6 +1048,53 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.approve_payout", skip_all, fields(error, error.level, error.message), err)]
+    async fn approve_payout(
+        &self,
+        request: Request<ApprovePayoutRequest>,
+    ) -> Result<Response<ApprovePayoutResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let request = request.into_inner();
+            let ApprovePayoutRequest { id } = request;
+            self.app
+                .approve_payout(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                )
+                .await?;
+            Ok(Response::new(ApprovePayoutResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.reject_payout", skip_all, fields(error, error.level, error.message), err)]
+    async fn reject_payout(
+        &self,
+        request: Request<RejectPayoutRequest>,
+    ) -> Result<Response<RejectPayoutResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let request = request.into_inner();
+            let RejectPayoutRequest { id, reason } = request;
+            self.app
+                .reject_payout(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    reason,
+                )
+                .await?;
+            Ok(Response::new(RejectPayoutResponse {}))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.list_wallets", skip_all, fields(error, error.level, error.message), err)]
     async fn list_wallets(
         &self,
@@ -772,6 +1116,111 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.update_wallet", skip_all, fields(error, error.level, error.message), err)]
+    async fn update_wallet(
+        &self,
+        request: Request<UpdateWalletRequest>,
+    ) -> Result<Response<UpdateWalletResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let UpdateWalletRequest {
+                wallet_name,
+                new_name,
+                settle_income_after_n_confs,
+                settle_change_after_n_confs,
+            } = request.into_inner();
+            let wallet = self
+                .app
+                .update_wallet(
+                    &profile,
+                    wallet_name,
+                    new_name,
+                    settle_income_after_n_confs,
+                    settle_change_after_n_confs,
+                )
+                .await?;
+            Ok(Response::new(UpdateWalletResponse {
+                wallet: Some(proto::Wallet::from(wallet)),
+            }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.rotate_wallet_keychain", skip_all, fields(error, error.level, error.message), err)]
+    async fn rotate_wallet_keychain(
+        &self,
+        request: Request<RotateWalletKeychainRequest>,
+    ) -> Result<Response<RotateWalletKeychainResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let RotateWalletKeychainRequest {
+                wallet_name,
+                keychain_config,
+            } = request.into_inner();
+            let (keychain_id, xpub_fingerprints) = match keychain_config {
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Wpkh(keychain_config::Wpkh {
+                            xpub,
+                            derivation_path,
+                        })),
+                }) => {
+                    self.app
+                        .rotate_wpkh_wallet_keychain(&profile, wallet_name, xpub, derivation_path)
+                        .await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Tr(keychain_config::Tr {
+                            xpub,
+                            derivation_path,
+                        })),
+                }) => {
+                    self.app
+                        .rotate_tr_wallet_keychain(&profile, wallet_name, xpub, derivation_path)
+                        .await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Descriptors(keychain_config::Descriptors {
+                            external,
+                            internal,
+                        })),
+                }) => {
+                    self.app
+                        .rotate_descriptors_wallet_keychain(&profile, wallet_name, external, internal)
+                        .await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::SortedMultisig(
+                            keychain_config::SortedMultisig {
+                                xpubs,
+                                threshold,
+                            })),
+                }) => {
+                    self.app
+                        .rotate_sorted_multisig_wallet_keychain(&profile, wallet_name, xpubs, threshold)
+                        .await?
+                }
+                _ => {
+                    return Err(Status::invalid_argument("invalid keychain config"));
+                }
+            };
+            Ok(Response::new(RotateWalletKeychainResponse {
+                keychain_id: keychain_id.to_string(),
+                xpub_ids: xpub_fingerprints.into_iter().map(|id| id.to_string()).collect(),
+            }))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.list_payout_queues", skip_all, fields(error, error.level, error.message), err)]
     async fn list_payout_queues(
         &self,
@@ -818,7 +1267,9 @@ impl BriaService for Bria {
                     id.parse()
                         .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
                     new_description,
-                    new_config.map(payout_queue::PayoutQueueConfig::from),
+                    new_config
+                        .map(payout_queue::PayoutQueueConfig::try_from)
+                        .transpose()?,
                 )
                 .await?;
             Ok(Response::new(UpdatePayoutQueueResponse {}))
@@ -872,11 +1323,274 @@ impl BriaService for Bria {
                             .collect()
                     })
                     .unwrap_or_default(),
+                replaces_batch_id: batch.replaces_batch_id.map(|id| id.to_string()),
+                replaced_by_batch_id: batch.replaced_by_batch_id.map(|id| id.to_string()),
             }))
         })
         .await
     }
 
+    #[instrument(name = "bria.list_batches", skip_all, fields(error, error.level, error.message), err)]
+    async fn list_batches(
+        &self,
+        request: Request<ListBatchesRequest>,
+    ) -> Result<Response<ListBatchesResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let ListBatchesRequest {
+                page,
+                page_size,
+                payout_queue_name,
+                wallet_name,
+                tx_id,
+                created_after,
+                created_before,
+                status,
+            } = request.into_inner();
+            let page = page.unwrap_or(1);
+            let page_size = page_size.unwrap_or(100);
+            let filter = BatchFilter {
+                tx_id: tx_id
+                    .map(|tx_id| tx_id.parse())
+                    .transpose()
+                    .map_err(|_| Status::invalid_argument("invalid tx_id"))?,
+                created_after: timestamp_arg(created_after, "created_after")?,
+                created_before: timestamp_arg(created_before, "created_before")?,
+                status: status
+                    .map(|status| {
+                        proto::BatchStatus::try_from(status)
+                            .map(BatchStatus::from)
+                            .map_err(|_| Status::invalid_argument("invalid status"))
+                    })
+                    .transpose()?,
+                ..Default::default()
+            };
+            let batches = self
+                .app
+                .list_batches(
+                    &profile,
+                    wallet_name,
+                    payout_queue_name,
+                    filter,
+                    page,
+                    page_size,
+                )
+                .await?;
+
+            Ok(Response::new(ListBatchesResponse {
+                batches: batches.into_iter().map(proto::BatchListing::from).collect(),
+            }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.bump_batch_fee", skip_all, fields(error, error.level, error.message), err)]
+    async fn bump_batch_fee(
+        &self,
+        request: Request<BumpBatchFeeRequest>,
+    ) -> Result<Response<BumpBatchFeeResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let BumpBatchFeeRequest { id, target } = request.into_inner();
+            let (tx_priority, fee_rate) = match target {
+                Some(proto::bump_batch_fee_request::Target::TxPriority(priority)) => (
+                    proto::TxPriority::try_from(priority)
+                        .ok()
+                        .map(crate::primitives::TxPriority::from),
+                    None,
+                ),
+                Some(proto::bump_batch_fee_request::Target::FeeRateSatsPerVbyte(rate)) => {
+                    (None, Some(rate))
+                }
+                None => (None, None),
+            };
+            let id = self
+                .app
+                .bump_batch_fee(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    tx_priority,
+                    fee_rate,
+                )
+                .await?;
+            Ok(Response::new(BumpBatchFeeResponse { id: id.to_string() }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.create_webhook_endpoint", skip_all, fields(error, error.level, error.message), err)]
+    async fn create_webhook_endpoint(
+        &self,
+        request: Request<CreateWebhookEndpointRequest>,
+    ) -> Result<Response<CreateWebhookEndpointResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let CreateWebhookEndpointRequest { url } = request.into_inner();
+            let (id, secret) = self.app.create_webhook_endpoint(&profile, url).await?;
+            Ok(Response::new(CreateWebhookEndpointResponse {
+                id: id.to_string(),
+                secret,
+            }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.list_webhook_endpoints", skip_all, fields(error, error.level, error.message), err)]
+    async fn list_webhook_endpoints(
+        &self,
+        request: Request<ListWebhookEndpointsRequest>,
+    ) -> Result<Response<ListWebhookEndpointsResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let endpoints = self.app.list_webhook_endpoints(&profile).await?;
+            Ok(Response::new(ListWebhookEndpointsResponse {
+                endpoints: endpoints
+                    .into_iter()
+                    .map(proto::WebhookEndpoint::from)
+                    .collect(),
+            }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.update_webhook_endpoint", skip_all, fields(error, error.level, error.message), err)]
+    async fn update_webhook_endpoint(
+        &self,
+        request: Request<UpdateWebhookEndpointRequest>,
+    ) -> Result<Response<UpdateWebhookEndpointResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let UpdateWebhookEndpointRequest {
+                id,
+                url,
+                enabled,
+                rotate_secret,
+            } = request.into_inner();
+            let secret = self
+                .app
+                .update_webhook_endpoint(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    url,
+                    enabled,
+                    rotate_secret,
+                )
+                .await?;
+            Ok(Response::new(UpdateWebhookEndpointResponse { secret }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.delete_webhook_endpoint", skip_all, fields(error, error.level, error.message), err)]
+    async fn delete_webhook_endpoint(
+        &self,
+        request: Request<DeleteWebhookEndpointRequest>,
+    ) -> Result<Response<DeleteWebhookEndpointResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let DeleteWebhookEndpointRequest { id } = request.into_inner();
+            self.app
+                .delete_webhook_endpoint(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                )
+                .await?;
+            Ok(Response::new(DeleteWebhookEndpointResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.list_webhook_deliveries", skip_all, fields(error, error.level, error.message), err)]
+    async fn list_webhook_deliveries(
+        &self,
+        request: Request<ListWebhookDeliveriesRequest>,
+    ) -> Result<Response<ListWebhookDeliveriesResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let ListWebhookDeliveriesRequest {
+                webhook_endpoint_id,
+                status,
+                limit,
+            } = request.into_inner();
+            let deliveries = self
+                .app
+                .list_webhook_deliveries(
+                    &profile,
+                    webhook_endpoint_id
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    status
+                        .map(|status| {
+                            proto::WebhookDeliveryStatus::try_from(status)
+                                .map(webhook::WebhookDeliveryStatus::from)
+                                .map_err(|_| Status::invalid_argument("invalid status"))
+                        })
+                        .transpose()?,
+                    limit.map(|limit| limit as usize),
+                )
+                .await?;
+            Ok(Response::new(ListWebhookDeliveriesResponse {
+                deliveries: deliveries
+                    .into_iter()
+                    .map(proto::WebhookDelivery::from)
+                    .collect(),
+            }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.redeliver_webhook", skip_all, fields(error, error.level, error.message), err)]
+    async fn redeliver_webhook(
+        &self,
+        request: Request<RedeliverWebhookRequest>,
+    ) -> Result<Response<RedeliverWebhookResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let key = extract_api_token(&request)?;
+            let profile = self.app.authenticate(key).await?;
+            let RedeliverWebhookRequest {
+                webhook_endpoint_id,
+                sequence,
+            } = request.into_inner();
+            self.app
+                .redeliver_webhook(
+                    &profile,
+                    webhook_endpoint_id
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    sequence,
+                )
+                .await?;
+            Ok(Response::new(RedeliverWebhookResponse {}))
+        })
+        .await
+    }
+
     type SubscribeAllStream = std::pin::Pin<
         Box<dyn futures::Stream<Item = Result<BriaEvent, Status>> + Send + Sync + 'static>,
     >;
@@ -893,11 +1607,44 @@ impl BriaService for Bria {
         let SubscribeAllRequest {
             after_sequence,
             augment,
+            event_types,
+            wallet_ids,
+            payout_queue_ids,
+            profile_ids,
         } = request.into_inner();
+        let filter = OutboxEventFilter {
+            event_types: event_types
+                .into_iter()
+                .map(|event_type| {
+                    if OUTBOX_EVENT_TYPES.contains(&event_type.as_str()) {
+                        Ok(event_type)
+                    } else {
+                        Err(Status::invalid_argument(format!(
+                            "unknown event type '{event_type}'"
+                        )))
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+            wallet_ids: wallet_ids
+                .into_iter()
+                .map(|id| id.parse())
+                .collect::<Result<_, _>>()
+                .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+            payout_queue_ids: payout_queue_ids
+                .into_iter()
+                .map(|id| id.parse())
+                .collect::<Result<_, _>>()
+                .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+            profile_ids: profile_ids
+                .into_iter()
+                .map(|id| id.parse())
+                .collect::<Result<_, _>>()
+                .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+        };
 
         let outbox_listener = self
             .app
-            .subscribe_all(&profile, after_sequence, augment.unwrap_or(false))
+            .subscribe_all(&profile, after_sequence, augment.unwrap_or(false), filter)
             .await?;
         Ok(Response::new(Box::pin(
             outbox_listener
@@ -939,6 +1686,18 @@ pub fn extract_api_token<T>(request: &Request<T>) -> Result<&str, Status> {
     }
 }
 
+#[allow(clippy::result_large_err)]
+fn timestamp_arg(
+    secs: Option<u32>,
+    name: &str,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, Status> {
+    secs.map(|secs| {
+        chrono::DateTime::from_timestamp(secs as i64, 0)
+            .ok_or_else(|| Status::invalid_argument(format!("invalid {name}")))
+    })
+    .transpose()
+}
+
 pub fn extract_tracing<T>(request: &Request<T>) {
     let propagator = TraceContextPropagator::new();
     let parent_cx = propagator.extract(&RequestContextExtractor(request));
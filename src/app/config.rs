@@ -33,15 +33,80 @@ pub struct AppConfig {
 pub struct BlockchainConfig {
     #[serde(default = "default_network", deserialize_with = "deserialize_network")]
     pub network: Network,
+    #[serde(default)]
+    pub backend: BlockchainBackend,
     #[serde(default = "default_electrum_url")]
     pub electrum_url: String,
+    #[serde(default)]
+    pub bitcoind_rpc: BitcoindRpcConfig,
+    #[serde(default)]
+    pub esplora: EsploraConfig,
 }
 
 impl Default for BlockchainConfig {
     fn default() -> Self {
         Self {
             network: default_network(),
+            backend: BlockchainBackend::default(),
             electrum_url: default_electrum_url(),
+            bitcoind_rpc: BitcoindRpcConfig::default(),
+            esplora: EsploraConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockchainBackend {
+    #[default]
+    Electrum,
+    BitcoindRpc,
+    Esplora,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoindRpcConfig {
+    #[serde(default = "default_bitcoind_rpc_url")]
+    pub url: String,
+    #[serde(default)]
+    pub rpc_user: String,
+    #[serde(default)]
+    pub rpc_password: String,
+    /// Unix timestamp from which bitcoind rescans when a keychain is first imported.
+    /// Defaults to the creation time of the wallet being synced.
+    #[serde(default)]
+    pub sync_start_time: Option<u64>,
+}
+
+impl Default for BitcoindRpcConfig {
+    fn default() -> Self {
+        Self {
+            url: default_bitcoind_rpc_url(),
+            rpc_user: String::new(),
+            rpc_password: String::new(),
+            sync_start_time: None,
+        }
+    }
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsploraConfig {
+    #[serde(default = "default_esplora_url")]
+    pub url: String,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_esplora_timeout")]
+    pub timeout: std::time::Duration,
+    #[serde(default = "default_esplora_stop_gap")]
+    pub stop_gap: usize,
+}
+
+impl Default for EsploraConfig {
+    fn default() -> Self {
+        Self {
+            url: default_esplora_url(),
+            timeout: default_esplora_timeout(),
+            stop_gap: default_esplora_stop_gap(),
         }
     }
 }
@@ -54,6 +119,22 @@ fn default_electrum_url() -> String {
     "127.0.0.1:50001".to_string()
 }
 
+fn default_bitcoind_rpc_url() -> String {
+    "http://127.0.0.1:18443".to_string()
+}
+
+fn default_esplora_url() -> String {
+    "http://127.0.0.1:3002".to_string()
+}
+
+fn default_esplora_timeout() -> std::time::Duration {
+    std::time::Duration::from_secs(60)
+}
+
+fn default_esplora_stop_gap() -> usize {
+    20
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SecurityConfig {
     blocked_addresses: HashSet<Address>,
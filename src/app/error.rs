@@ -19,6 +19,7 @@ use crate::{
     signing_session::error::SigningSessionError,
     utxo::error::UtxoError,
     wallet::error::WalletError,
+    webhook::error::WebhookError,
     xpub::error::XPubError,
 };
 
@@ -59,6 +60,8 @@ pub enum ApplicationError {
     #[error("{0}")]
     DescriptorError(#[from] DescriptorError),
     #[error("{0}")]
+    WebhookError(#[from] WebhookError),
+    #[error("{0}")]
     Sqlx(#[from] sqlx::Error),
     #[error("UnsupportedPubKeyType")]
     UnsupportedPubKeyType,
@@ -72,6 +75,12 @@ pub enum ApplicationError {
     DestinationNotAllowed(PayoutDestination),
     #[error("PayoutExceedsMaximum - profile is not allowed to send '{0}' satoshis")]
     PayoutExceedsMaximum(Satoshis),
+    #[error(
+        "SpendingLimitExceeded - profile has reached its spending limit for the last {0} seconds"
+    )]
+    SpendingLimitExceeded(u64),
+    #[error("InvalidFeeRateBounds - min_fee_rate must not exceed max_fee_rate")]
+    InvalidFeeRateBounds,
     #[error("Signing Session not found for batch id: {0}")]
     SigningSessionNotFoundForBatchId(crate::primitives::BatchId),
     #[error("Signing Session not found for xpub fingerprint: {0}")]
@@ -82,6 +91,24 @@ pub enum ApplicationError {
     HexDecodeError(#[from] hex::FromHexError),
     #[error("Could not decrypt the encrypted key: {0}")]
     CouldNotDecryptKey(chacha20poly1305::Error),
+    #[error("Batch {0} has already been replaced")]
+    BatchAlreadyReplaced(crate::primitives::BatchId),
+    #[error("Batch {0} has already been signed")]
+    BatchAlreadySigned(crate::primitives::BatchId),
+    #[error("Batch {0} is being signed right now")]
+    BatchSigningInProgress(crate::primitives::BatchId),
+    #[error("Batch {0} has already been confirmed")]
+    BatchAlreadyConfirmed(crate::primitives::BatchId),
+    #[error("Batch {0} can not be replaced yet - accounting is not complete")]
+    BatchNotReadyForReplacement(crate::primitives::BatchId),
+    #[error("Batch {0} can not be replaced - its change output is already being spent")]
+    BatchChangeAlreadySpent(crate::primitives::BatchId),
+    #[error("FeeBumpTooLow - replacement fee must be at least '{0}' satoshis")]
+    FeeBumpTooLow(Satoshis),
+    #[error("FeeRateMustBePositive - fee rate must be at least 1 sat/vbyte")]
+    FeeRateMustBePositive,
+    #[error("Could not build replacement for batch {0}")]
+    CouldNotBuildReplacementBatch(crate::primitives::BatchId),
     #[error("AddressError - Could not parse the address: {0}")]
     CouldNotParseAddress(#[from] bitcoin::AddressError),
 }
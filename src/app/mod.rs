@@ -27,9 +27,13 @@ use crate::{
     signing_session::*,
     utxo::*,
     wallet::{balance::*, *},
+    webhook::*,
     xpub::*,
 };
 
+/// Bitcoin Core's default `-incrementalrelayfee`
+const INCREMENTAL_RELAY_FEE_SATS_PER_VBYTE: u64 = 1;
+
 #[allow(dead_code)]
 pub struct App {
     _runner: JobRunnerHandle,
@@ -48,6 +52,8 @@ pub struct App {
     addresses: Addresses,
     fees_client: FeesClient,
     batch_inclusion: BatchInclusion,
+    webhook_endpoints: WebhookEndpoints,
+    webhook_deliveries: WebhookDeliveries,
     pool: sqlx::PgPool,
     config: AppConfig,
 }
@@ -69,7 +75,9 @@ impl App {
             Augmenter::new(&addresses, &payouts, &batch_inclusion),
         )
         .await?;
-        let fees_client = FeesClient::new(config.fees.clone());
+        let fees_client = FeesClient::new(config.fees.clone(), &pool);
+        let webhook_endpoints = WebhookEndpoints::new(&pool);
+        let webhook_deliveries = WebhookDeliveries::new(&pool);
 
         let job_svc = JobSvc::init(pool.clone(), outbox.clone(), ledger.clone()).await?;
 
@@ -85,6 +93,8 @@ impl App {
             ledger.clone(),
             utxos.clone(),
             addresses.clone(),
+            webhook_endpoints.clone(),
+            webhook_deliveries.clone(),
             config.jobs.clone(),
             config.blockchain.clone(),
             config.signer_encryption.clone(),
@@ -97,6 +107,7 @@ impl App {
             config.jobs.process_all_payout_queues_delay,
         )
         .await?;
+        Self::spawn_dispatch_webhooks(pool.clone(), config.jobs.dispatch_webhooks_delay).await?;
 
         let app = Self {
             job_svc,
@@ -115,6 +126,8 @@ impl App {
             addresses,
             fees_client,
             batch_inclusion,
+            webhook_endpoints,
+            webhook_deliveries,
             config,
             _runner: runner,
         };
@@ -273,10 +286,45 @@ impl App {
                 self.xpubs.update_signer_config(&mut op, xpub).await?;
             }
         }
+        for mut endpoint in self.webhook_endpoints.list_all().await? {
+            if endpoint.reencrypt_secret(&deprecated_key, &self.config.signer_encryption.key) {
+                self.webhook_endpoints
+                    .update_in_op(&mut op, &mut endpoint)
+                    .await?;
+            }
+        }
         op.commit().await?;
         Ok(())
     }
 
+    #[instrument(name = "app.get_unsigned_psbt", skip(self), err)]
+    pub async fn get_unsigned_psbt(
+        &self,
+        profile: &Profile,
+        batch_id: BatchId,
+        xpub_ref: String,
+    ) -> Result<SigningSession, ApplicationError> {
+        let xpub_fingerprint = self
+            .xpubs
+            .find_from_ref(
+                profile.account_id,
+                xpub_ref
+                    .parse::<XPubRef>()
+                    .expect("ref should always parse"),
+            )
+            .await?
+            .fingerprint();
+        let mut sessions = self
+            .signing_sessions
+            .list_for_batch(profile.account_id, batch_id)
+            .await?
+            .ok_or(ApplicationError::SigningSessionNotFoundForBatchId(batch_id))?
+            .xpub_sessions;
+        sessions.remove(&xpub_fingerprint).ok_or(
+            ApplicationError::SigningSessionNotFoundForXPubFingerprint(xpub_fingerprint),
+        )
+    }
+
     #[instrument(name = "app.submit_signed_psbt", skip(self), err)]
     pub async fn submit_signed_psbt(
         &self,
@@ -296,12 +344,16 @@ impl App {
             .await?;
         let xpub_fingerprint = xpub.fingerprint();
         let xpub = xpub.value;
-        let unsigned_psbt = self
+        let batch = self
             .batches
             .find_by_id(profile.account_id, batch_id)
-            .await?
-            .unsigned_psbt;
-        psbt_validator::validate_psbt(&signed_psbt, xpub, &unsigned_psbt)?;
+            .await?;
+        psbt_validator::validate_signed_psbt_matches_batch(
+            &signed_psbt,
+            &batch.unsigned_psbt,
+            batch.total_fee_sats,
+        )?;
+        psbt_validator::validate_psbt(&signed_psbt, xpub, &batch.unsigned_psbt)?;
         let mut sessions = self
             .signing_sessions
             .list_for_batch(profile.account_id, batch_id)
@@ -330,23 +382,42 @@ impl App {
         xpub: String,
         derivation: Option<String>,
     ) -> Result<(WalletId, Vec<XPubFingerprint>), ApplicationError> {
-        let keychain = if let Ok(xpub) = XPub::try_from((&xpub, derivation)) {
-            KeychainConfig::wpkh(xpub)
-        } else {
-            KeychainConfig::wpkh(
-                self.xpubs
-                    .find_from_ref(
-                        profile.account_id,
-                        xpub.parse::<XPubRef>()
-                            .expect("xpub_ref should always parse"),
-                    )
-                    .await?
-                    .value,
-            )
-        };
+        let keychain = KeychainConfig::wpkh(self.xpub_or_ref(profile, xpub, derivation).await?);
+        self.create_wallet(profile, wallet_name, keychain).await
+    }
+
+    #[instrument(name = "app.create_tr_wallet", skip(self), err)]
+    pub async fn create_tr_wallet(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        xpub: String,
+        derivation: Option<String>,
+    ) -> Result<(WalletId, Vec<XPubFingerprint>), ApplicationError> {
+        let keychain = KeychainConfig::tr(self.xpub_or_ref(profile, xpub, derivation).await?);
         self.create_wallet(profile, wallet_name, keychain).await
     }
 
+    async fn xpub_or_ref(
+        &self,
+        profile: &Profile,
+        xpub: String,
+        derivation: Option<String>,
+    ) -> Result<XPub, ApplicationError> {
+        if let Ok(xpub) = XPub::try_from((&xpub, derivation)) {
+            return Ok(xpub);
+        }
+        Ok(self
+            .xpubs
+            .find_from_ref(
+                profile.account_id,
+                xpub.parse::<XPubRef>()
+                    .expect("xpub_ref should always parse"),
+            )
+            .await?
+            .value)
+    }
+
     #[instrument(name = "app.create_descriptors_wallet", skip(self), err)]
     pub async fn create_descriptors_wallet(
         &self,
@@ -367,7 +438,17 @@ impl App {
         xpubs: Vec<String>,
         threshold: u32,
     ) -> Result<(WalletId, Vec<XPubFingerprint>), ApplicationError> {
-        let xpub_values: Vec<XPub> = futures::future::try_join_all(
+        let xpub_values = self.find_xpubs_from_refs(profile, xpubs).await?;
+        let keychain = KeychainConfig::sorted_multisig(xpub_values, threshold);
+        self.create_wallet(profile, wallet_name, keychain).await
+    }
+
+    async fn find_xpubs_from_refs(
+        &self,
+        profile: &Profile,
+        xpubs: Vec<String>,
+    ) -> Result<Vec<XPub>, ApplicationError> {
+        Ok(futures::future::try_join_all(
             xpubs
                 .iter()
                 .map(|xpub| {
@@ -379,10 +460,7 @@ impl App {
         .await?
         .into_iter()
         .map(|xpub| xpub.value)
-        .collect();
-
-        let keychain = KeychainConfig::sorted_multisig(xpub_values, threshold);
-        self.create_wallet(profile, wallet_name, keychain).await
+        .collect())
     }
 
     async fn create_wallet(
@@ -392,9 +470,164 @@ impl App {
         keychain: KeychainConfig,
     ) -> Result<(WalletId, Vec<XPubFingerprint>), ApplicationError> {
         let mut op = self.wallets.begin_op().await?;
-        let xpubs = keychain.xpubs();
+        let xpub_fingerprints = self
+            .import_keychain_xpubs(&mut op, profile, &wallet_name, &keychain)
+            .await?;
+        let wallet_id = WalletId::new();
+        let wallet_ledger_accounts = self
+            .ledger
+            .create_ledger_accounts_for_wallet(op.tx_mut(), wallet_id)
+            .await?;
+        let new_wallet = NewWallet::builder()
+            .id(wallet_id)
+            .network(self.config.blockchain.network)
+            .account_id(profile.account_id)
+            .journal_id(profile.account_id)
+            .name(wallet_name)
+            .keychain(keychain.clone())
+            .ledger_account_ids(wallet_ledger_accounts)
+            .build()
+            .expect("Couldn't build NewWallet");
+        let wallet = self.wallets.create_in_op(&mut op, new_wallet).await?;
+        self.persist_keychain_descriptors(&mut op, profile, wallet.id, &keychain)
+            .await?;
+        op.commit().await?;
+        Ok((wallet.id, xpub_fingerprints))
+    }
+
+    #[instrument(name = "app.update_wallet", skip(self), err)]
+    pub async fn update_wallet(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        new_name: Option<String>,
+        settle_income_after_n_confs: Option<u32>,
+        settle_change_after_n_confs: Option<u32>,
+    ) -> Result<Wallet, ApplicationError> {
+        let mut wallet = self
+            .wallets
+            .find_by_account_id_and_name(profile.account_id, wallet_name)
+            .await?;
+        let original_name = wallet.name.clone();
+        let original_config = wallet.config.clone();
+
+        if let Some(name) = new_name {
+            wallet.update_name(name);
+        }
+        let mut config = wallet.config.clone();
+        if let Some(n_confs) = settle_income_after_n_confs {
+            config.settle_income_after_n_confs = n_confs;
+        }
+        if let Some(n_confs) = settle_change_after_n_confs {
+            config.settle_change_after_n_confs = n_confs;
+        }
+        wallet.update_config(config);
+
+        if wallet.name == original_name && wallet.config == original_config {
+            return Ok(wallet);
+        }
+        let mut op = self.wallets.begin_op().await?;
+        self.wallets.update_in_op(&mut op, &mut wallet).await?;
+        self.outbox
+            .publish_in_op(
+                &mut op,
+                profile.account_id,
+                OutboxEventPayload::WalletUpdated {
+                    wallet_id: wallet.id,
+                    name: wallet.name.clone(),
+                    settle_income_after_n_confs: wallet.config.settle_income_after_n_confs,
+                    settle_change_after_n_confs: wallet.config.settle_change_after_n_confs,
+                },
+            )
+            .await?;
+        op.commit().await?;
+        Ok(wallet)
+    }
+
+    #[instrument(name = "app.rotate_wpkh_wallet_keychain", skip(self), err)]
+    pub async fn rotate_wpkh_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        xpub: String,
+        derivation: Option<String>,
+    ) -> Result<(KeychainId, Vec<XPubFingerprint>), ApplicationError> {
+        let keychain = KeychainConfig::wpkh(self.xpub_or_ref(profile, xpub, derivation).await?);
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    #[instrument(name = "app.rotate_tr_wallet_keychain", skip(self), err)]
+    pub async fn rotate_tr_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        xpub: String,
+        derivation: Option<String>,
+    ) -> Result<(KeychainId, Vec<XPubFingerprint>), ApplicationError> {
+        let keychain = KeychainConfig::tr(self.xpub_or_ref(profile, xpub, derivation).await?);
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    #[instrument(name = "app.rotate_descriptors_wallet_keychain", skip(self), err)]
+    pub async fn rotate_descriptors_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        external: String,
+        internal: String,
+    ) -> Result<(KeychainId, Vec<XPubFingerprint>), ApplicationError> {
+        let keychain = KeychainConfig::try_from((external.as_ref(), internal.as_ref()))?;
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    #[instrument(name = "app.rotate_sorted_multisig_wallet_keychain", skip(self), err)]
+    pub async fn rotate_sorted_multisig_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        xpubs: Vec<String>,
+        threshold: u32,
+    ) -> Result<(KeychainId, Vec<XPubFingerprint>), ApplicationError> {
+        let xpub_values = self.find_xpubs_from_refs(profile, xpubs).await?;
+        let keychain = KeychainConfig::sorted_multisig(xpub_values, threshold);
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    async fn rotate_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        keychain: KeychainConfig,
+    ) -> Result<(KeychainId, Vec<XPubFingerprint>), ApplicationError> {
+        let mut wallet = self
+            .wallets
+            .find_by_account_id_and_name(profile.account_id, wallet_name.clone())
+            .await?;
+        let keychain_id = wallet.rotate_keychain(keychain.clone())?;
+        let mut op = self.wallets.begin_op().await?;
+        let xpub_fingerprints = self
+            .import_keychain_xpubs(&mut op, profile, &wallet_name, &keychain)
+            .await?;
+        self.wallets.update_in_op(&mut op, &mut wallet).await?;
+        self.persist_keychain_descriptors(&mut op, profile, wallet.id, &keychain)
+            .await?;
+        op.commit().await?;
+        Ok((keychain_id, xpub_fingerprints))
+    }
+
+    async fn import_keychain_xpubs(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        profile: &Profile,
+        wallet_name: &str,
+        keychain: &KeychainConfig,
+    ) -> Result<Vec<XPubFingerprint>, ApplicationError> {
         let mut xpub_fingerprints = Vec::new();
-        for xpub in xpubs {
+        for xpub in keychain.xpubs() {
             match self
                 .xpubs
                 .find_from_ref(profile.account_id, xpub.fingerprint())
@@ -412,27 +645,20 @@ impl App {
                         .value(xpub)
                         .build()
                         .expect("Couldn't build xpub");
-                    xpub_fingerprints
-                        .push(self.xpubs.create_in_op(&mut op, xpub).await?.fingerprint());
+                    xpub_fingerprints.push(self.xpubs.create_in_op(op, xpub).await?.fingerprint());
                 }
             }
         }
-        let wallet_id = WalletId::new();
-        let wallet_ledger_accounts = self
-            .ledger
-            .create_ledger_accounts_for_wallet(op.tx_mut(), wallet_id)
-            .await?;
-        let new_wallet = NewWallet::builder()
-            .id(wallet_id)
-            .network(self.config.blockchain.network)
-            .account_id(profile.account_id)
-            .journal_id(profile.account_id)
-            .name(wallet_name)
-            .keychain(keychain.clone())
-            .ledger_account_ids(wallet_ledger_accounts)
-            .build()
-            .expect("Couldn't build NewWallet");
-        let wallet = self.wallets.create_in_op(&mut op, new_wallet).await?;
+        Ok(xpub_fingerprints)
+    }
+
+    async fn persist_keychain_descriptors(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        profile: &Profile,
+        wallet_id: WalletId,
+        keychain: &KeychainConfig,
+    ) -> Result<(), ApplicationError> {
         let descriptors = vec![
             NewDescriptor::builder()
                 .account_id(profile.account_id)
@@ -449,11 +675,8 @@ impl App {
                 .build()
                 .expect("Could not build descriptor"),
         ];
-        self.descriptors
-            .persist_all_in_op(&mut op, descriptors)
-            .await?;
-        op.commit().await?;
-        Ok((wallet.id, xpub_fingerprints))
+        self.descriptors.persist_all_in_op(op, descriptors).await?;
+        Ok(())
     }
 
     #[instrument(name = "app.get_wallet_balance_summary", skip(self), err)]
@@ -545,21 +768,24 @@ impl App {
     }
 
     #[instrument(name = "app.list_addresses", skip(self), err)]
-    pub async fn list_external_addresses(
+    pub async fn list_addresses(
         &self,
         profile: &Profile,
         wallet_name: String,
-    ) -> Result<(WalletId, Vec<WalletAddress>), ApplicationError> {
+        filter: AddressFilter,
+        after: Option<AddressCursor>,
+        page_size: Option<usize>,
+    ) -> Result<(WalletId, Vec<WalletAddress>, Option<AddressCursor>), ApplicationError> {
         let wallet = self
             .wallets
             .find_by_account_id_and_name(profile.account_id, wallet_name)
             .await?;
-        let addresses = self
+        let (addresses, next_cursor) = self
             .addresses
-            .list_external_by_wallet_id(profile.account_id, wallet.id)
+            .list_by_wallet_id(profile.account_id, wallet.id, &filter, after, page_size)
             .await?;
 
-        Ok((wallet.id, addresses))
+        Ok((wallet.id, addresses, next_cursor))
     }
 
     #[instrument(name = "app.get_address_by_external_id", skip(self), err)]
@@ -602,20 +828,57 @@ impl App {
         &self,
         profile: &Profile,
         wallet_name: String,
-    ) -> Result<(WalletId, Vec<KeychainUtxos>), ApplicationError> {
+        filter: UtxoFilter,
+        after: Option<UtxoCursor>,
+        page_size: Option<usize>,
+    ) -> Result<(WalletId, Vec<KeychainUtxos>, Option<UtxoCursor>), ApplicationError> {
         let wallet = self
             .wallets
             .find_by_account_id_and_name(profile.account_id, wallet_name)
             .await?;
-        let mut utxos = self
+        let (mut utxos, next_cursor) = self
             .utxos
-            .find_keychain_utxos(wallet.keychain_ids())
+            .list_keychain_utxos(wallet.keychain_ids(), &filter, after, page_size)
             .await?;
         let ordered_utxos = wallet
             .keychain_ids()
             .filter_map(|keychain_id| utxos.remove(&keychain_id))
             .collect();
-        Ok((wallet.id, ordered_utxos))
+        Ok((wallet.id, ordered_utxos, next_cursor))
+    }
+
+    #[instrument(name = "app.freeze_utxo", skip(self), err)]
+    pub async fn freeze_utxo(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        outpoint: bitcoin::OutPoint,
+    ) -> Result<(), ApplicationError> {
+        let wallet = self
+            .wallets
+            .find_by_account_id_and_name(profile.account_id, wallet_name)
+            .await?;
+        Ok(self
+            .utxos
+            .freeze_utxo(wallet.keychain_ids(), outpoint)
+            .await?)
+    }
+
+    #[instrument(name = "app.unfreeze_utxo", skip(self), err)]
+    pub async fn unfreeze_utxo(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        outpoint: bitcoin::OutPoint,
+    ) -> Result<(), ApplicationError> {
+        let wallet = self
+            .wallets
+            .find_by_account_id_and_name(profile.account_id, wallet_name)
+            .await?;
+        Ok(self
+            .utxos
+            .unfreeze_utxo(wallet.keychain_ids(), outpoint)
+            .await?)
     }
 
     #[instrument(name = "app.create_payout_queue", skip(self), err)]
@@ -632,6 +895,10 @@ impl App {
             .name(payout_queue_name)
             .description(description);
         if let Some(config) = config {
+            if !config.has_valid_fee_rate_bounds() {
+                return Err(ApplicationError::InvalidFeeRateBounds);
+            }
+            self.check_consolidation_wallets(profile, &config).await?;
             builder.config(config);
         }
         let payout_queue = builder.build().expect("Couldn't build NewPayoutQueue");
@@ -662,7 +929,7 @@ impl App {
         queue_name: String,
         destination_wallet_name: String,
         sats: Satoshis,
-    ) -> Result<Satoshis, ApplicationError> {
+    ) -> Result<(Satoshis, FeeRateQuote), ApplicationError> {
         let destination_wallet = self
             .wallets
             .find_by_account_id_and_name(profile.account_id, destination_wallet_name)
@@ -689,7 +956,7 @@ impl App {
         queue_name: String,
         destination: String,
         sats: Satoshis,
-    ) -> Result<Satoshis, ApplicationError> {
+    ) -> Result<(Satoshis, FeeRateQuote), ApplicationError> {
         let wallet = self
             .wallets
             .find_by_account_id_and_name(profile.account_id, wallet_name)
@@ -710,7 +977,8 @@ impl App {
 
         let queue_id = payout_queue.id;
         let tx_priority = payout_queue.config.tx_priority;
-        let fee_rate = self.fees_client.fee_rate(tx_priority).await?;
+        let quote = self.fees_client.fee_rate_quote(tx_priority).await?;
+        let fee_rate = payout_queue.config.apply_min_fee_rate(quote.fee_rate);
 
         let psbt = {
             job::process_payout_queue::construct_psbt(
@@ -727,7 +995,7 @@ impl App {
         };
 
         if let Some(fee) = psbt.proportional_fee(&wallet.id, sats) {
-            return Ok(fee);
+            return Ok((fee, quote));
         }
 
         // No utxos were available to simulate the batch
@@ -736,7 +1004,7 @@ impl App {
             .payouts
             .average_payout_per_batch(wallet.id, queue_id)
             .await?;
-        Ok(fees::estimate_proportional_fee(
+        let fee = fees::estimate_proportional_fee(
             avg_utxo_size,
             wallet
                 .current_keychain_wallet(&self.pool)
@@ -746,7 +1014,17 @@ impl App {
             payout_size,
             destination,
             sats,
-        ))
+        );
+        Ok((fee, quote))
+    }
+
+    #[instrument(name = "app.get_fee_rate", skip(self), err)]
+    pub async fn get_fee_rate(
+        &self,
+        _profile: &Profile,
+        tx_priority: TxPriority,
+    ) -> Result<FeeRateQuote, ApplicationError> {
+        Ok(self.fees_client.fee_rate_quote(tx_priority).await?)
     }
 
     #[instrument(name = "app.submit_payout_to_address", skip(self), err)]
@@ -760,6 +1038,8 @@ impl App {
         sats: Satoshis,
         external_id: Option<String>,
         metadata: Option<serde_json::Value>,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        spend_outpoints: Vec<bitcoin::OutPoint>,
     ) -> Result<(PayoutId, Option<chrono::DateTime<chrono::Utc>>), ApplicationError> {
         let wallet = self
             .wallets
@@ -779,6 +1059,8 @@ impl App {
             sats,
             external_id,
             metadata,
+            not_before,
+            spend_outpoints,
         )
         .await
     }
@@ -794,6 +1076,8 @@ impl App {
         sats: Satoshis,
         external_id: Option<String>,
         metadata: Option<serde_json::Value>,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        spend_outpoints: Vec<bitcoin::OutPoint>,
     ) -> Result<(PayoutId, Option<chrono::DateTime<chrono::Utc>>), ApplicationError> {
         let wallet = self
             .wallets
@@ -824,6 +1108,8 @@ impl App {
             sats,
             external_id,
             metadata,
+            not_before,
+            spend_outpoints,
         )
         .await
     }
@@ -839,6 +1125,8 @@ impl App {
         sats: Satoshis,
         external_id: Option<String>,
         metadata: Option<serde_json::Value>,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        spend_outpoints: Vec<bitcoin::OutPoint>,
     ) -> Result<(PayoutId, Option<chrono::DateTime<chrono::Utc>>), ApplicationError> {
         if self.config.security.is_blocked(&destination) {
             return Err(ApplicationError::DestinationBlocked(destination));
@@ -849,6 +1137,26 @@ impl App {
         if !profile.is_amount_allowed(sats) {
             return Err(ApplicationError::PayoutExceedsMaximum(sats));
         }
+        self.utxos
+            .check_outpoints_spendable(wallet.keychain_ids(), &spend_outpoints)
+            .await?;
+
+        let mut op = self.payouts.begin_op().await?;
+        if !profile.spending_limits().is_empty() {
+            self.profiles
+                .lock_for_spending_in_op(&mut op, profile.id)
+                .await?;
+            let now = chrono::Utc::now();
+            for limit in profile.spending_limits() {
+                let (n_submitted, submitted_sats) = self
+                    .payouts
+                    .submitted_by_profile_since(&mut op, profile.id, limit.window_start(now))
+                    .await?;
+                if limit.is_exceeded_by(n_submitted, submitted_sats, sats) {
+                    return Err(ApplicationError::SpendingLimitExceeded(limit.window_secs));
+                }
+            }
+        }
 
         let mut builder = NewPayout::builder(id);
         builder
@@ -858,12 +1166,18 @@ impl App {
             .payout_queue_id(payout_queue.id)
             .destination(destination.clone())
             .satoshis(sats)
-            .metadata(metadata.clone());
+            .metadata(metadata.clone())
+            .not_before(not_before)
+            .spend_outpoints(spend_outpoints);
         if let Some(external_id) = external_id.as_ref() {
             builder.external_id(external_id);
         }
+        if let Some(approval) = profile.approval_required(sats) {
+            builder
+                .required_approvals(approval.required_approvals)
+                .approvers(approval.approvers.clone());
+        }
         let new_payout = builder.build().expect("Couldn't build NewPayout");
-        let mut op = self.payouts.begin_op().await?;
         let id = self.payouts.create_in_op(&mut op, new_payout).await?.id;
         self.ledger
             .payout_submitted(
@@ -888,7 +1202,7 @@ impl App {
 
         let estimation = self
             .batch_inclusion
-            .estimate_next_queue_trigger(payout_queue)
+            .estimate_queue_trigger_not_before(payout_queue, not_before)
             .await?;
         Ok((id, estimation))
     }
@@ -901,7 +1215,7 @@ impl App {
         let mut op = self.payouts.begin_op().await?;
         let mut payout = self
             .payouts
-            .find_by_id_for_cancellation(&mut op, profile.account_id, id)
+            .find_by_id_for_update(&mut op, profile.account_id, id)
             .await?;
         payout.cancel_payout(profile.id)?;
         self.payouts.update_in_op(&mut op, &mut payout).await?;
@@ -911,6 +1225,76 @@ impl App {
         Ok(())
     }
 
+    #[instrument(name = "app.approve_payout", skip(self), err)]
+    pub async fn approve_payout(
+        &self,
+        profile: &Profile,
+        id: PayoutId,
+    ) -> Result<(), ApplicationError> {
+        let mut op = self.payouts.begin_op().await?;
+        let mut payout = self
+            .payouts
+            .find_by_id_for_update(&mut op, profile.account_id, id)
+            .await?;
+        payout.approve(profile.id)?;
+        self.payouts.update_in_op(&mut op, &mut payout).await?;
+        self.outbox
+            .publish_in_op(
+                &mut op,
+                payout.account_id,
+                OutboxEventPayload::PayoutApproved {
+                    id: payout.id,
+                    profile_id: payout.profile_id,
+                    wallet_id: payout.wallet_id,
+                    payout_queue_id: payout.payout_queue_id,
+                    satoshis: payout.satoshis,
+                    destination: payout.destination.clone(),
+                    approved_by: profile.id,
+                    n_approvals: payout.approved_by().count() as u32,
+                    required_approvals: payout.required_approvals().unwrap_or_default(),
+                },
+            )
+            .await?;
+        op.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.reject_payout", skip(self), err)]
+    pub async fn reject_payout(
+        &self,
+        profile: &Profile,
+        id: PayoutId,
+        reason: Option<String>,
+    ) -> Result<(), ApplicationError> {
+        let mut op = self.payouts.begin_op().await?;
+        let mut payout = self
+            .payouts
+            .find_by_id_for_update(&mut op, profile.account_id, id)
+            .await?;
+        payout.reject(profile.id, reason.clone())?;
+        self.payouts.update_in_op(&mut op, &mut payout).await?;
+        self.outbox
+            .publish_in_op(
+                &mut op,
+                payout.account_id,
+                OutboxEventPayload::PayoutRejected {
+                    id: payout.id,
+                    profile_id: payout.profile_id,
+                    wallet_id: payout.wallet_id,
+                    payout_queue_id: payout.payout_queue_id,
+                    satoshis: payout.satoshis,
+                    destination: payout.destination,
+                    rejected_by: profile.id,
+                    reason,
+                },
+            )
+            .await?;
+        self.ledger
+            .payout_cancelled(op.into(), LedgerTransactionId::new(), id)
+            .await?;
+        Ok(())
+    }
+
     #[instrument(name = "app.list_wallets", skip_all, err)]
     pub async fn list_wallets(&self, profile: &Profile) -> Result<Vec<Wallet>, ApplicationError> {
         Ok(self.wallets.list_for_account(profile.account_id).await?)
@@ -952,17 +1336,29 @@ impl App {
     pub async fn list_payouts(
         &self,
         profile: &Profile,
-        wallet_name: String,
+        wallet_name: Option<String>,
+        payout_queue_name: Option<String>,
+        mut filter: PayoutFilter,
         page: u64,
         page_size: u64,
     ) -> Result<Vec<PayoutWithInclusionEstimate>, ApplicationError> {
-        let wallet = self
-            .wallets
-            .find_by_account_id_and_name(profile.account_id, wallet_name)
-            .await?;
+        if let Some(wallet_name) = wallet_name {
+            let wallet = self
+                .wallets
+                .find_by_account_id_and_name(profile.account_id, wallet_name)
+                .await?;
+            filter.wallet_id = Some(wallet.id);
+        }
+        if let Some(payout_queue_name) = payout_queue_name {
+            let payout_queue = self
+                .payout_queues
+                .find_by_account_id_and_name(profile.account_id, payout_queue_name)
+                .await?;
+            filter.payout_queue_id = Some(payout_queue.id);
+        }
         let payouts = self
             .payouts
-            .list_for_wallet(profile.account_id, wallet.id, page, page_size)
+            .list_for_account(profile.account_id, &filter, page, page_size)
             .await?;
 
         Ok(self
@@ -999,12 +1395,31 @@ impl App {
             payout_queue.update_description(desc)
         }
         if let Some(config) = new_config {
+            if !config.has_valid_fee_rate_bounds() {
+                return Err(ApplicationError::InvalidFeeRateBounds);
+            }
+            self.check_consolidation_wallets(profile, &config).await?;
             payout_queue.update_config(config)
         }
         self.payout_queues.update(&mut payout_queue).await?;
         Ok(())
     }
 
+    async fn check_consolidation_wallets(
+        &self,
+        profile: &Profile,
+        config: &PayoutQueueConfig,
+    ) -> Result<(), ApplicationError> {
+        if let Some(consolidation) = config.consolidation.as_ref() {
+            for wallet_id in consolidation.wallet_ids.iter() {
+                self.wallets
+                    .find_by_account_id_and_id(profile.account_id, *wallet_id)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     #[allow(clippy::type_complexity)]
     #[instrument(name = "app.get_batch", skip_all, err)]
     pub async fn get_batch(
@@ -1034,12 +1449,285 @@ impl App {
         Ok((batch, payouts, signing_sessions))
     }
 
+    #[instrument(name = "app.list_signing_sessions", skip(self), err)]
+    pub async fn list_signing_sessions(
+        &self,
+        profile: &Profile,
+        batch_id: BatchId,
+    ) -> Result<Vec<SigningSession>, ApplicationError> {
+        self.batches
+            .find_by_id(profile.account_id, batch_id)
+            .await?;
+        Ok(self
+            .signing_sessions
+            .list_for_batch(profile.account_id, batch_id)
+            .await?
+            .map(|sessions| sessions.xpub_sessions.into_values().collect())
+            .unwrap_or_default())
+    }
+
+    #[instrument(name = "app.retry_signing", skip(self), err)]
+    pub async fn retry_signing(
+        &self,
+        profile: &Profile,
+        batch_id: BatchId,
+    ) -> Result<(), ApplicationError> {
+        let batch = self
+            .batches
+            .find_by_id(profile.account_id, batch_id)
+            .await?;
+        if batch.is_replaced() {
+            return Err(ApplicationError::BatchAlreadyReplaced(batch_id));
+        }
+        if batch.signed_tx.is_some() {
+            return Err(ApplicationError::BatchAlreadySigned(batch_id));
+        }
+        let mut tx = self.pool.begin().await?;
+        if !self.batches.try_lock_for_signing(&mut tx, batch_id).await? {
+            return Err(ApplicationError::BatchSigningInProgress(batch_id));
+        }
+        job::retry_batch_signing(tx, profile.account_id, batch_id).await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.list_batches", skip_all, err)]
+    pub async fn list_batches(
+        &self,
+        profile: &Profile,
+        wallet_name: Option<String>,
+        payout_queue_name: Option<String>,
+        mut filter: BatchFilter,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Vec<(BatchListing, Option<BatchSigningSession>)>, ApplicationError> {
+        if let Some(wallet_name) = wallet_name {
+            let wallet = self
+                .wallets
+                .find_by_account_id_and_name(profile.account_id, wallet_name)
+                .await?;
+            filter.wallet_id = Some(wallet.id);
+        }
+        if let Some(payout_queue_name) = payout_queue_name {
+            let payout_queue = self
+                .payout_queues
+                .find_by_account_id_and_name(profile.account_id, payout_queue_name)
+                .await?;
+            filter.payout_queue_id = Some(payout_queue.id);
+        }
+        let batches = self
+            .batches
+            .list_for_account(profile.account_id, &filter, page, page_size)
+            .await?;
+        let mut ret = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let signing_sessions = self
+                .signing_sessions
+                .list_for_batch(profile.account_id, batch.id)
+                .await?;
+            ret.push((batch, signing_sessions));
+        }
+        Ok(ret)
+    }
+
+    #[instrument(name = "app.bump_batch_fee", skip(self), err)]
+    pub async fn bump_batch_fee(
+        &self,
+        profile: &Profile,
+        batch_id: BatchId,
+        tx_priority: Option<TxPriority>,
+        fee_rate_sats_per_vbyte: Option<u64>,
+    ) -> Result<BatchId, ApplicationError> {
+        if fee_rate_sats_per_vbyte == Some(0) {
+            return Err(ApplicationError::FeeRateMustBePositive);
+        }
+        let batch = self
+            .batches
+            .find_by_id(profile.account_id, batch_id)
+            .await?;
+        if batch.is_replaced() {
+            return Err(ApplicationError::BatchAlreadyReplaced(batch_id));
+        }
+        if !batch.accounting_complete() {
+            return Err(ApplicationError::BatchNotReadyForReplacement(batch_id));
+        }
+        let payout_queue = self
+            .payout_queues
+            .find_by_account_id_and_id(profile.account_id, batch.payout_queue_id)
+            .await?;
+        let fee_rate = match fee_rate_sats_per_vbyte {
+            Some(rate) => bitcoin::FeeRate::from_sat_per_vb(rate as f32),
+            None => {
+                self.fees_client
+                    .fee_rate(tx_priority.unwrap_or(payout_queue.config.tx_priority))
+                    .await?
+            }
+        };
+
+        let mut tx = self.pool.begin().await?;
+        let (pinned_utxos, spend_settled) = self.utxos.list_batch_inputs(&mut tx, batch_id).await?;
+        if spend_settled {
+            return Err(ApplicationError::BatchAlreadyConfirmed(batch_id));
+        }
+        let change_outpoints: HashMap<KeychainId, Vec<bitcoin::OutPoint>> = batch
+            .wallet_summaries
+            .values()
+            .filter_map(|s| {
+                s.change_outpoint
+                    .map(|out| (s.current_keychain_id, vec![out]))
+            })
+            .collect();
+        if !change_outpoints.is_empty()
+            && self
+                .utxos
+                .list_utxos_by_outpoint(&change_outpoints)
+                .await?
+                .iter()
+                .any(|utxo| utxo.spending_batch_id.is_some())
+        {
+            return Err(ApplicationError::BatchChangeAlreadySpent(batch_id));
+        }
+
+        let mut payouts = self
+            .payouts
+            .list_for_batch(profile.account_id, batch_id)
+            .await?;
+        let n_payouts = payouts.values().fold(0, |acc, p| acc + p.len());
+        // Include consolidating wallets that have no payouts in the batch
+        let tx_payouts = batch
+            .wallet_summaries
+            .keys()
+            .map(|wallet_id| {
+                let tx_payouts = payouts
+                    .get(wallet_id)
+                    .map(|payouts| payouts.iter().map(TxPayout::from).collect())
+                    .unwrap_or_default();
+                (*wallet_id, tx_payouts)
+            })
+            .collect();
+
+        // Outpoints pinned by queued payouts stay reserved for them
+        let pinned_by_payouts = self
+            .payouts
+            .list_unbatched_pins(&mut tx, profile.account_id)
+            .await?
+            .into_iter()
+            .flat_map(|pins| pins.outpoints)
+            .collect();
+
+        let queue_id = payout_queue.id;
+        let FinishedPsbtBuild {
+            psbt,
+            included_payouts,
+            included_utxos,
+            wallet_totals,
+            tx_id,
+            fee_satoshis,
+            ..
+        } = job::process_payout_queue::construct_replacement_psbt(
+            &self.pool,
+            &mut tx,
+            tx_payouts,
+            pinned_utxos,
+            pinned_by_payouts,
+            &self.utxos,
+            &self.wallets,
+            payout_queue,
+            fee_rate,
+        )
+        .await?;
+        let (tx_id, psbt) = match (tx_id, psbt) {
+            (Some(tx_id), Some(psbt))
+                if included_payouts.values().fold(0, |acc, p| acc + p.len()) == n_payouts =>
+            {
+                (tx_id, psbt)
+            }
+            _ => return Err(ApplicationError::CouldNotBuildReplacementBatch(batch_id)),
+        };
+        // BIP125 rule 4: the replacement must pay for its own relay on top of the original fee
+        let replacement_vsize = (u64::from(fee_satoshis) as f32 / fee_rate.as_sat_per_vb()).ceil();
+        let min_fee = batch.total_fee_sats
+            + Satoshis::from(INCREMENTAL_RELAY_FEE_SATS_PER_VBYTE * replacement_vsize as u64);
+        if fee_satoshis < min_fee {
+            return Err(ApplicationError::FeeBumpTooLow(min_fee));
+        }
+
+        let wallet_ids: Vec<WalletId> = wallet_totals.keys().copied().collect();
+        let new_batch = NewBatch::builder()
+            .account_id(profile.account_id)
+            .id(BatchId::new())
+            .payout_queue_id(queue_id)
+            .tx_id(tx_id)
+            .unsigned_psbt(psbt)
+            .total_fee_sats(fee_satoshis)
+            .replaces_batch_id(batch_id)
+            .wallet_summaries(
+                wallet_totals
+                    .into_iter()
+                    .map(|(wallet_id, total)| (wallet_id, WalletSummary::from(total)))
+                    .collect(),
+            )
+            .build()
+            .expect("Couldn't build batch");
+        let new_batch_id = self.batches.create_in_op(&mut tx, new_batch).await?;
+        self.utxos
+            .reserve_utxos_in_batch(
+                &mut tx,
+                profile.account_id,
+                new_batch_id,
+                queue_id,
+                fee_rate,
+                included_utxos
+                    .into_values()
+                    .flat_map(|keychain_map| {
+                        keychain_map
+                            .into_iter()
+                            .flat_map(|(keychain_id, outpoints)| {
+                                outpoints
+                                    .into_iter()
+                                    .map(move |outpoint| (keychain_id, outpoint))
+                            })
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .await?;
+
+        let vouts: HashMap<PayoutId, u32> = included_payouts
+            .into_values()
+            .flat_map(|payouts| {
+                payouts
+                    .into_iter()
+                    .map(|((id, _, _), vout)| (PayoutId::from(id), vout))
+            })
+            .collect();
+        for payout in payouts.values_mut().flatten() {
+            let vout = *vouts
+                .get(&payout.id)
+                .expect("payout not included in replacement");
+            payout.commit_to_replacement_batch(
+                new_batch_id,
+                bitcoin::OutPoint { txid: tx_id, vout },
+            )?;
+            self.payouts.update_in_op(&mut tx, payout).await?;
+        }
+
+        job::spawn_replacement_batch_jobs(
+            tx,
+            profile.account_id,
+            new_batch_id,
+            wallet_ids.into_iter(),
+        )
+        .await?;
+
+        Ok(new_batch_id)
+    }
+
     #[instrument(name = "app.subscribe_all", skip(self), err)]
     pub async fn subscribe_all(
         &self,
         profile: &Profile,
         start_after: Option<u64>,
         augment: bool,
+        filter: OutboxEventFilter,
     ) -> Result<OutboxListener, ApplicationError> {
         let res = self
             .outbox
@@ -1047,11 +1735,139 @@ impl App {
                 profile.account_id,
                 start_after.map(EventSequence::from),
                 augment,
+                filter,
             )
             .await?;
         Ok(res)
     }
 
+    #[instrument(name = "app.create_webhook_endpoint", skip(self), err)]
+    pub async fn create_webhook_endpoint(
+        &self,
+        profile: &Profile,
+        url: String,
+    ) -> Result<(WebhookEndpointId, String), ApplicationError> {
+        validate_destination(
+            &url,
+            &self.config.jobs.webhook_delivery.allowed_private_hosts,
+        )
+        .await?;
+        let new_endpoint = NewWebhookEndpoint::builder()
+            .account_id(profile.account_id)
+            .url(url)
+            .encryption_key(self.config.signer_encryption.key)
+            .build()
+            .expect("Couldn't build NewWebhookEndpoint");
+        let secret = new_endpoint.secret().to_string();
+        let start_after = self.outbox.latest_sequence(profile.account_id).await?;
+        let mut op = self.webhook_endpoints.begin_op().await?;
+        let endpoint = self
+            .webhook_endpoints
+            .create_in_op(&mut op, new_endpoint)
+            .await?;
+        self.webhook_deliveries
+            .init_cursor(op.tx_mut(), profile.account_id, endpoint.id, start_after)
+            .await?;
+        op.commit().await?;
+        Ok((endpoint.id, secret))
+    }
+
+    #[instrument(name = "app.list_webhook_endpoints", skip(self), err)]
+    pub async fn list_webhook_endpoints(
+        &self,
+        profile: &Profile,
+    ) -> Result<Vec<WebhookEndpoint>, ApplicationError> {
+        Ok(self
+            .webhook_endpoints
+            .list_for_account_id(profile.account_id)
+            .await?)
+    }
+
+    /// Returns the new signing secret if it was rotated.
+    #[instrument(name = "app.update_webhook_endpoint", skip(self), err)]
+    pub async fn update_webhook_endpoint(
+        &self,
+        profile: &Profile,
+        id: WebhookEndpointId,
+        url: Option<String>,
+        enabled: Option<bool>,
+        rotate_secret: bool,
+    ) -> Result<Option<String>, ApplicationError> {
+        let mut endpoint = self
+            .webhook_endpoints
+            .find_by_account_id_and_id(profile.account_id, id)
+            .await?;
+        if let Some(url) = url {
+            validate_destination(
+                &url,
+                &self.config.jobs.webhook_delivery.allowed_private_hosts,
+            )
+            .await?;
+            endpoint.update_url(url)?;
+        }
+        if let Some(enabled) = enabled {
+            endpoint.set_enabled(enabled);
+        }
+        let secret =
+            rotate_secret.then(|| endpoint.rotate_secret(&self.config.signer_encryption.key));
+        self.webhook_endpoints.update(&mut endpoint).await?;
+        Ok(secret)
+    }
+
+    #[instrument(name = "app.delete_webhook_endpoint", skip(self), err)]
+    pub async fn delete_webhook_endpoint(
+        &self,
+        profile: &Profile,
+        id: WebhookEndpointId,
+    ) -> Result<(), ApplicationError> {
+        let mut endpoint = self
+            .webhook_endpoints
+            .find_by_account_id_and_id(profile.account_id, id)
+            .await?;
+        endpoint.delete();
+        self.webhook_endpoints.update(&mut endpoint).await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.list_webhook_deliveries", skip(self), err)]
+    pub async fn list_webhook_deliveries(
+        &self,
+        profile: &Profile,
+        id: WebhookEndpointId,
+        status: Option<WebhookDeliveryStatus>,
+        limit: Option<usize>,
+    ) -> Result<Vec<WebhookDelivery>, ApplicationError> {
+        let endpoint = self
+            .webhook_endpoints
+            .find_by_account_id_and_id(profile.account_id, id)
+            .await?;
+        Ok(self
+            .webhook_deliveries
+            .list_for_endpoint(endpoint.id, status, limit.unwrap_or(100))
+            .await?)
+    }
+
+    #[instrument(name = "app.redeliver_webhook", skip(self), err)]
+    pub async fn redeliver_webhook(
+        &self,
+        profile: &Profile,
+        id: WebhookEndpointId,
+        sequence: u64,
+    ) -> Result<(), ApplicationError> {
+        let endpoint = self
+            .webhook_endpoints
+            .find_by_account_id_and_id(profile.account_id, id)
+            .await?;
+        let sequence = EventSequence::from(sequence);
+        let mut tx = self.pool.begin().await?;
+        self.webhook_deliveries
+            .reset_for_redelivery(&mut tx, endpoint.id, sequence)
+            .await?;
+        job::spawn_webhook_delivery(&mut tx, (endpoint.account_id, endpoint.id, sequence)).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
     #[instrument(name = "app.spawn_sync_all_wallets", level = "trace", skip_all, err)]
     async fn spawn_sync_all_wallets(
         pool: sqlx::PgPool,
@@ -1086,4 +1902,19 @@ impl App {
         });
         Ok(())
     }
+
+    #[instrument(name = "app.spawn_dispatch_webhooks", level = "trace", skip_all, err)]
+    async fn spawn_dispatch_webhooks(
+        pool: sqlx::PgPool,
+        delay: std::time::Duration,
+    ) -> Result<(), ApplicationError> {
+        tokio::spawn(async move {
+            loop {
+                let _ =
+                    job::spawn_dispatch_webhooks(&pool, std::time::Duration::from_secs(1)).await;
+                tokio::time::sleep(delay).await;
+            }
+        });
+        Ok(())
+    }
 }
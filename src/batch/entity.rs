@@ -12,6 +12,9 @@ pub struct Batch {
     pub wallet_summaries: HashMap<WalletId, WalletSummary>,
     pub unsigned_psbt: bitcoin::psbt::PartiallySignedTransaction,
     pub signed_tx: Option<bitcoin::Transaction>,
+    pub total_fee_sats: Satoshis,
+    pub replaces_batch_id: Option<BatchId>,
+    pub replaced_by_batch_id: Option<BatchId>,
 }
 
 impl Batch {
@@ -20,6 +23,16 @@ impl Batch {
             .values()
             .all(|s| s.batch_created_ledger_tx_id.is_some())
     }
+
+    pub fn is_replaced(&self) -> bool {
+        self.replaced_by_batch_id.is_some()
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.wallet_summaries
+            .values()
+            .any(|s| s.batch_broadcast_ledger_tx_id.is_some())
+    }
 }
 
 #[derive(Builder, Clone)]
@@ -31,6 +44,8 @@ pub struct NewBatch {
     pub(super) total_fee_sats: Satoshis,
     pub(super) unsigned_psbt: bitcoin::psbt::PartiallySignedTransaction,
     pub(super) wallet_summaries: HashMap<WalletId, WalletSummary>,
+    #[builder(setter(strip_option), default)]
+    pub(super) replaces_batch_id: Option<BatchId>,
 }
 
 impl NewBatch {
@@ -61,4 +76,5 @@ pub struct WalletSummary {
     pub change_outpoint: Option<bitcoin::OutPoint>,
     pub batch_created_ledger_tx_id: Option<LedgerTransactionId>,
     pub batch_broadcast_ledger_tx_id: Option<LedgerTransactionId>,
+    pub batch_replaced_ledger_tx_id: Option<LedgerTransactionId>,
 }
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+
+use crate::primitives::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BatchStatus {
+    /// Not yet seen in the mempool
+    Pending,
+    /// Broadcast but the batch transaction has not confirmed yet
+    Broadcast,
+    /// The batch transaction has confirmed - derived from the settlement of the spent inputs
+    /// (`spend_settled_ledger_tx_id`)
+    Confirmed,
+}
+
+impl BatchStatus {
+    pub(super) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Broadcast => "broadcast",
+            Self::Confirmed => "confirmed",
+        }
+    }
+
+    pub(super) fn from_db(status: &str) -> Self {
+        match status {
+            "confirmed" => Self::Confirmed,
+            "broadcast" => Self::Broadcast,
+            _ => Self::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BatchFilter {
+    pub payout_queue_id: Option<PayoutQueueId>,
+    pub wallet_id: Option<WalletId>,
+    pub tx_id: Option<bitcoin::Txid>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub status: Option<BatchStatus>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchListing {
+    pub id: BatchId,
+    pub payout_queue_id: PayoutQueueId,
+    pub bitcoin_tx_id: bitcoin::Txid,
+    pub total_fee_sats: Satoshis,
+    pub cpfp_fee_sats: Satoshis,
+    pub n_payouts: u32,
+    pub status: BatchStatus,
+    pub replaces_batch_id: Option<BatchId>,
+    pub replaced_by_batch_id: Option<BatchId>,
+    pub created_at: DateTime<Utc>,
+}
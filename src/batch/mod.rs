@@ -1,6 +1,8 @@
 mod entity;
 pub mod error;
+mod filter;
 mod repo;
 
 pub use entity::*;
+pub use filter::*;
 pub use repo::*;
@@ -4,7 +4,7 @@ use tracing::instrument;
 
 use std::collections::HashMap;
 
-use super::{entity::*, error::BatchError};
+use super::{entity::*, error::BatchError, filter::*};
 use crate::primitives::*;
 
 pub struct BatchInfo {
@@ -13,6 +13,11 @@ pub struct BatchInfo {
     pub created_ledger_tx_id: LedgerTxId,
 }
 
+pub struct ReplacedBatchInfo {
+    pub created_ledger_tx_id: LedgerTxId,
+    pub broadcast_ledger_tx_id: Option<LedgerTxId>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Batches {
     pool: PgPool,
@@ -31,14 +36,15 @@ impl Batches {
     ) -> Result<BatchId, BatchError> {
         let serializied_psbt = batch.unsigned_psbt.serialize();
         sqlx::query!(
-            r#"INSERT INTO bria_batches (id, account_id, payout_queue_id, total_fee_sats, bitcoin_tx_id, unsigned_psbt)
-            VALUES ($1, $2, $3, $4, $5, $6)"#,
+            r#"INSERT INTO bria_batches (id, account_id, payout_queue_id, total_fee_sats, bitcoin_tx_id, unsigned_psbt, replaces_batch_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
             batch.id as BatchId,
             batch.account_id as AccountId,
             batch.payout_queue_id as PayoutQueueId,
             i64::from(batch.total_fee_sats),
             batch.tx_id.as_ref() as &[u8],
             serializied_psbt.as_slice() as &[u8],
+            batch.replaces_batch_id as Option<BatchId>,
         ).execute(op.as_executor()).await?;
 
         let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
@@ -90,7 +96,9 @@ impl Batches {
                     payout_queue_id, unsigned_psbt, signed_tx, bitcoin_tx_id, s.batch_id,
                     s.wallet_id, s.current_keychain_id, s.signing_keychains, total_in_sats,
                     total_spent_sats, change_sats, change_address, change_vout, s.total_fee_sats,
-                    cpfp_fee_sats, cpfp_details, batch_created_ledger_tx_id, batch_broadcast_ledger_tx_id
+                    cpfp_fee_sats, cpfp_details, batch_created_ledger_tx_id, batch_broadcast_ledger_tx_id,
+                    batch_replaced_ledger_tx_id, b.total_fee_sats AS batch_total_fee_sats, b.replaces_batch_id,
+                    (SELECT id FROM bria_batches WHERE replaces_batch_id = b.id) AS replaced_by_batch_id
             FROM bria_batch_wallet_summaries s
             LEFT JOIN bria_batches b ON b.id = s.batch_id
             WHERE s.batch_id = $1 AND b.account_id = $2"#,
@@ -114,6 +122,9 @@ impl Batches {
             .map(|tx| bitcoin::consensus::deserialize(tx))
             .transpose()?;
         let payout_queue_id = PayoutQueueId::from(rows[0].payout_queue_id);
+        let total_fee_sats = Satoshis::from(rows[0].batch_total_fee_sats);
+        let replaces_batch_id = rows[0].replaces_batch_id.map(BatchId::from);
+        let replaced_by_batch_id = rows[0].replaced_by_batch_id.map(BatchId::from);
 
         for row in rows.into_iter() {
             let wallet_id = WalletId::from(row.wallet_id);
@@ -148,6 +159,9 @@ impl Batches {
                     batch_broadcast_ledger_tx_id: row
                         .batch_broadcast_ledger_tx_id
                         .map(LedgerTxId::from),
+                    batch_replaced_ledger_tx_id: row
+                        .batch_replaced_ledger_tx_id
+                        .map(LedgerTxId::from),
                 },
             );
         }
@@ -160,9 +174,108 @@ impl Batches {
             unsigned_psbt,
             signed_tx,
             wallet_summaries,
+            total_fee_sats,
+            replaces_batch_id,
+            replaced_by_batch_id,
         })
     }
 
+    #[instrument(name = "batches.list_for_account", skip(self))]
+    pub async fn list_for_account(
+        &self,
+        account_id: AccountId,
+        filter: &BatchFilter,
+        page: u64,
+        page_size: u64,
+    ) -> Result<Vec<BatchListing>, BatchError> {
+        let offset = (page.max(1) - 1) * page_size;
+        let tx_id = filter
+            .tx_id
+            .as_ref()
+            .map(bitcoin::consensus::encode::serialize);
+        let rows = sqlx::query!(
+            r#"SELECT id, payout_queue_id, bitcoin_tx_id, total_fee_sats, replaces_batch_id,
+                      replaced_by_batch_id, created_at,
+                      cpfp_fee_sats AS "cpfp_fee_sats!", n_payouts AS "n_payouts!", status AS "status!"
+            FROM (
+                SELECT b.id, b.payout_queue_id, b.bitcoin_tx_id, b.total_fee_sats, b.replaces_batch_id, b.created_at,
+                    (SELECT id FROM bria_batches WHERE replaces_batch_id = b.id) AS replaced_by_batch_id,
+                    (SELECT COALESCE(SUM(s.cpfp_fee_sats), 0)::BIGINT
+                     FROM bria_batch_wallet_summaries s WHERE s.batch_id = b.id) AS cpfp_fee_sats,
+                    (SELECT COUNT(*) FROM bria_payouts p WHERE p.batch_id = b.id) AS n_payouts,
+                    CASE
+                        WHEN EXISTS (
+                            SELECT 1 FROM bria_utxos u
+                            WHERE u.spending_batch_id = b.id AND u.spend_settled_ledger_tx_id IS NOT NULL
+                        ) THEN 'confirmed'
+                        WHEN EXISTS (
+                            SELECT 1 FROM bria_batch_wallet_summaries s
+                            WHERE s.batch_id = b.id AND s.batch_broadcast_ledger_tx_id IS NOT NULL
+                        ) THEN 'broadcast'
+                        ELSE 'pending'
+                    END AS status
+                FROM bria_batches b
+                WHERE b.account_id = $1
+                AND ($2::UUID IS NULL OR b.payout_queue_id = $2)
+                AND ($3::UUID IS NULL OR EXISTS (
+                    SELECT 1 FROM bria_batch_wallet_summaries s
+                    WHERE s.batch_id = b.id AND s.wallet_id = $3
+                ))
+                AND ($4::BYTEA IS NULL OR b.bitcoin_tx_id = $4)
+                AND ($5::TIMESTAMPTZ IS NULL OR b.created_at >= $5)
+                AND ($6::TIMESTAMPTZ IS NULL OR b.created_at < $6)
+            ) batches
+            WHERE ($7::VARCHAR IS NULL OR status = $7)
+            ORDER BY created_at DESC, id
+            LIMIT $8 OFFSET $9"#,
+            account_id as AccountId,
+            filter.payout_queue_id as Option<PayoutQueueId>,
+            filter.wallet_id as Option<WalletId>,
+            tx_id.as_deref(),
+            filter.created_after,
+            filter.created_before,
+            filter.status.map(|status| status.as_str()),
+            page_size as i64,
+            offset as i64,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(BatchListing {
+                    id: BatchId::from(row.id),
+                    payout_queue_id: PayoutQueueId::from(row.payout_queue_id),
+                    bitcoin_tx_id: bitcoin::consensus::deserialize(&row.bitcoin_tx_id)?,
+                    total_fee_sats: Satoshis::from(row.total_fee_sats),
+                    cpfp_fee_sats: Satoshis::from(row.cpfp_fee_sats),
+                    n_payouts: row.n_payouts as u32,
+                    status: BatchStatus::from_db(&row.status),
+                    replaces_batch_id: row.replaces_batch_id.map(BatchId::from),
+                    replaced_by_batch_id: row.replaced_by_batch_id.map(BatchId::from),
+                    created_at: row.created_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Held by the signing job for the duration of an attempt so that
+    /// a batch is never signed by two attempts at once
+    #[instrument(name = "batches.try_lock_for_signing", skip(self, op))]
+    pub async fn try_lock_for_signing(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        batch_id: BatchId,
+    ) -> Result<bool, BatchError> {
+        let row = sqlx::query!(
+            r#"SELECT pg_try_advisory_xact_lock(hashtext('bria_batch_signing:' || $1::UUID::TEXT)) AS "locked!""#,
+            batch_id as BatchId,
+        )
+        .fetch_one(op.as_executor())
+        .await?;
+        Ok(row.locked)
+    }
+
     #[instrument(name = "batches.set_signed_tx", skip(self))]
     pub async fn set_signed_tx(
         &self,
@@ -219,10 +332,11 @@ impl Batches {
                  SELECT id, payout_queue_id FROM bria_batches
                  WHERE bitcoin_tx_id = $1
                )
-               SELECT b.id, b. payout_queue_id, s.batch_broadcast_ledger_tx_id as "ledger_id?", s.batch_created_ledger_tx_id
+               SELECT b.id, b. payout_queue_id, s.batch_broadcast_ledger_tx_id as "ledger_id?", s.batch_created_ledger_tx_id,
+                      s.batch_replaced_ledger_tx_id as "replaced_ledger_id?"
                FROM b
                LEFT JOIN (
-                   SELECT batch_id, batch_broadcast_ledger_tx_id, batch_created_ledger_tx_id
+                   SELECT batch_id, batch_broadcast_ledger_tx_id, batch_created_ledger_tx_id, batch_replaced_ledger_tx_id
                    FROM bria_batch_wallet_summaries
                    WHERE wallet_id = $2 AND batch_id = ANY(SELECT id FROM b)
                    FOR UPDATE
@@ -233,7 +347,10 @@ impl Batches {
         )
         .fetch_optional(&mut *tx)
         .await?;
-        if row.is_none() || row.as_ref().unwrap().batch_created_ledger_tx_id.is_none() {
+        if row.is_none()
+            || row.as_ref().unwrap().batch_created_ledger_tx_id.is_none()
+            || row.as_ref().unwrap().replaced_ledger_id.is_some()
+        {
             return Ok(None);
         }
         let row = row.unwrap();
@@ -274,4 +391,56 @@ impl Batches {
             ledger_transaction_id,
         )))
     }
+
+    #[instrument(name = "batches.set_batch_replaced_ledger_tx_id", skip(self))]
+    pub async fn set_batch_replaced_ledger_tx_id(
+        &self,
+        replaced_batch_id: BatchId,
+        wallet_id: WalletId,
+    ) -> Result<Option<(Transaction<'_, Postgres>, ReplacedBatchInfo, LedgerTxId)>, BatchError>
+    {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query!(
+            r#"SELECT batch_created_ledger_tx_id, batch_broadcast_ledger_tx_id, batch_replaced_ledger_tx_id
+               FROM bria_batch_wallet_summaries
+               WHERE batch_id = $1 AND wallet_id = $2
+               FOR UPDATE"#,
+            replaced_batch_id as BatchId,
+            wallet_id as WalletId,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (created_ledger_tx_id, broadcast_ledger_tx_id) = match row {
+            Some(row) if row.batch_replaced_ledger_tx_id.is_none() => {
+                match row.batch_created_ledger_tx_id {
+                    Some(created) => (
+                        LedgerTxId::from(created),
+                        row.batch_broadcast_ledger_tx_id.map(LedgerTxId::from),
+                    ),
+                    None => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        };
+        let ledger_transaction_id = LedgerTxId::new();
+        sqlx::query!(
+            r#"UPDATE bria_batch_wallet_summaries
+               SET batch_replaced_ledger_tx_id = $1
+               WHERE batch_id = $2 AND wallet_id = $3"#,
+            ledger_transaction_id as LedgerTxId,
+            replaced_batch_id as BatchId,
+            wallet_id as WalletId,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        Ok(Some((
+            tx,
+            ReplacedBatchInfo {
+                created_ledger_tx_id,
+                broadcast_ledger_tx_id,
+            },
+            ledger_transaction_id,
+        )))
+    }
 }
@@ -26,11 +26,11 @@ impl From<(Payout, Option<&BatchInclusionEstimate>)> for PayoutWithInclusionEsti
         let estimate = if payout.batch_id.is_some() || payout.is_cancelled() {
             None
         } else {
-            estimated_batch_inclusion
+            estimated_batch_inclusion.copied()
         };
         Self {
             payout,
-            estimated_batch_inclusion: estimate.copied(),
+            estimated_batch_inclusion: estimate,
         }
     }
 }
@@ -52,11 +52,23 @@ impl BatchInclusion {
     pub async fn estimate_next_queue_trigger(
         &self,
         payout_queue: PayoutQueue,
+    ) -> Result<Option<BatchInclusionEstimate>, BatchInclusionError> {
+        self.estimate_queue_trigger_not_before(payout_queue, None)
+            .await
+    }
+
+    pub async fn estimate_queue_trigger_not_before(
+        &self,
+        payout_queue: PayoutQueue,
+        not_before: Option<DateTime<Utc>>,
     ) -> Result<Option<BatchInclusionEstimate>, BatchInclusionError> {
         let id = payout_queue.id;
+        let interval = payout_queue.spawn_in();
         let mut next_queue_trigger_times =
             self.next_queue_trigger_times(vec![payout_queue]).await?;
-        Ok(next_queue_trigger_times.remove(&id))
+        Ok(next_queue_trigger_times
+            .remove(&id)
+            .and_then(|next_trigger| first_trigger_not_before(next_trigger, interval, not_before)))
     }
 
     pub async fn include_estimate(
@@ -71,7 +83,9 @@ impl BatchInclusion {
             .payout_queues
             .find_by_account_id_and_id(account_id, payout.payout_queue_id)
             .await?;
-        let estimate = self.estimate_next_queue_trigger(queue).await?;
+        let estimate = self
+            .estimate_queue_trigger_not_before(queue, payout.not_before)
+            .await?;
         Ok(PayoutWithInclusionEstimate {
             estimated_batch_inclusion: estimate,
             payout,
@@ -84,12 +98,21 @@ impl BatchInclusion {
         payouts: Vec<Payout>,
     ) -> Result<Vec<PayoutWithInclusionEstimate>, BatchInclusionError> {
         let queues = self.payout_queues.list_for_account_id(account_id).await?;
+        let intervals: HashMap<_, _> = queues.iter().map(|q| (q.id, q.spawn_in())).collect();
         let next_queue_trigger_times = self.next_queue_trigger_times(queues).await?;
         Ok(payouts
             .into_iter()
             .map(|payout| {
-                let estimate = next_queue_trigger_times.get(&payout.payout_queue_id);
-                PayoutWithInclusionEstimate::from((payout, estimate))
+                let estimate = next_queue_trigger_times
+                    .get(&payout.payout_queue_id)
+                    .and_then(|next_trigger| {
+                        first_trigger_not_before(
+                            *next_trigger,
+                            intervals.get(&payout.payout_queue_id).copied().flatten(),
+                            payout.not_before,
+                        )
+                    });
+                PayoutWithInclusionEstimate::from((payout, estimate.as_ref()))
             })
             .collect())
     }
@@ -140,3 +163,62 @@ impl BatchInclusion {
         Ok(map)
     }
 }
+
+/// The first trigger of a queue that will pick up a payout scheduled for `not_before`.
+/// Manual queues have no predictable trigger once the next one has been missed.
+fn first_trigger_not_before(
+    next_trigger: BatchInclusionEstimate,
+    interval: Option<std::time::Duration>,
+    not_before: Option<DateTime<Utc>>,
+) -> Option<BatchInclusionEstimate> {
+    let not_before = match not_before {
+        Some(not_before) if not_before > next_trigger => not_before,
+        _ => return Some(next_trigger),
+    };
+    let interval = Duration::from_std(interval?)
+        .expect("interval value will always be less than i64")
+        .num_milliseconds();
+    if interval <= 0 {
+        return Some(not_before);
+    }
+    let behind = (not_before - next_trigger).num_milliseconds();
+    let n_intervals = (behind + interval - 1) / interval;
+    Some(
+        next_trigger
+            + Duration::try_milliseconds(n_intervals * interval)
+                .expect("could not convert to duration"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_trigger_not_before_rounds_up_to_interval() {
+        let next_trigger = Utc::now();
+        let interval = std::time::Duration::from_secs(60);
+        let secs = |n| Duration::try_seconds(n).unwrap();
+
+        assert_eq!(
+            first_trigger_not_before(next_trigger, Some(interval), None),
+            Some(next_trigger)
+        );
+        assert_eq!(
+            first_trigger_not_before(next_trigger, Some(interval), Some(next_trigger - secs(10))),
+            Some(next_trigger)
+        );
+        assert_eq!(
+            first_trigger_not_before(next_trigger, Some(interval), Some(next_trigger + secs(90))),
+            Some(next_trigger + secs(120))
+        );
+        assert_eq!(
+            first_trigger_not_before(next_trigger, Some(interval), Some(next_trigger + secs(120))),
+            Some(next_trigger + secs(120))
+        );
+        assert_eq!(
+            first_trigger_not_before(next_trigger, None, Some(next_trigger + secs(90))),
+            None
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use bdk::{
+    bitcoin::{Network, Transaction},
+    blockchain::{
+        rpc::{Auth, RpcBlockchain, RpcConfig, RpcSyncParams},
+        ConfigurableBlockchain,
+    },
+    Wallet,
+};
+use bitcoincore_rpc::{Client, RpcApi};
+use chrono::{DateTime, Utc};
+
+use super::ChainBackend;
+use crate::{
+    app::BitcoindRpcConfig,
+    bdk::{error::BdkError, pg::SqlxWalletDb},
+    primitives::KeychainId,
+};
+
+pub struct BitcoindRpcBackend {
+    client: Client,
+    network: Network,
+    config: BitcoindRpcConfig,
+}
+
+impl BitcoindRpcBackend {
+    pub fn connect(network: Network, config: &BitcoindRpcConfig) -> Result<Self, BdkError> {
+        let client = Client::new(
+            &config.url,
+            bitcoincore_rpc::Auth::UserPass(config.rpc_user.clone(), config.rpc_password.clone()),
+        )?;
+        Ok(Self {
+            client,
+            network,
+            config: config.clone(),
+        })
+    }
+
+    // Each keychain is tracked in its own watch-only wallet on the node
+    fn rpc_config(&self, keychain_id: KeychainId, wallet_created_at: DateTime<Utc>) -> RpcConfig {
+        RpcConfig {
+            url: self.config.url.clone(),
+            auth: Auth::UserPass {
+                username: self.config.rpc_user.clone(),
+                password: self.config.rpc_password.clone(),
+            },
+            network: self.network,
+            wallet_name: format!("bria_{keychain_id}"),
+            sync_params: Some(RpcSyncParams {
+                start_time: self
+                    .config
+                    .sync_start_time
+                    .unwrap_or(wallet_created_at.timestamp() as u64),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl ChainBackend for BitcoindRpcBackend {
+    fn get_height(&self) -> Result<u32, BdkError> {
+        Ok(self.client.get_block_count()? as u32)
+    }
+
+    fn broadcast(&self, tx: &Transaction) -> Result<(), BdkError> {
+        self.client.send_raw_transaction(tx)?;
+        Ok(())
+    }
+
+    fn sync_wallet(
+        &self,
+        keychain_id: KeychainId,
+        wallet_created_at: DateTime<Utc>,
+        wallet: &Wallet<SqlxWalletDb>,
+    ) -> Result<(), BdkError> {
+        let blockchain =
+            RpcBlockchain::from_config(&self.rpc_config(keychain_id, wallet_created_at))?;
+        Ok(wallet.sync(&blockchain, Default::default())?)
+    }
+}
@@ -0,0 +1,47 @@
+use bdk::{
+    bitcoin::Transaction,
+    blockchain::{Blockchain, ElectrumBlockchain, GetHeight},
+    Wallet,
+};
+use electrum_client::{Client, ConfigBuilder};
+
+use chrono::{DateTime, Utc};
+
+use super::ChainBackend;
+use crate::{
+    bdk::{error::BdkError, pg::SqlxWalletDb},
+    primitives::KeychainId,
+};
+
+pub struct ElectrumBackend {
+    inner: ElectrumBlockchain,
+}
+
+impl ElectrumBackend {
+    pub fn connect(electrum_url: &str) -> Result<Self, BdkError> {
+        let inner = ElectrumBlockchain::from(Client::from_config(
+            electrum_url,
+            ConfigBuilder::new().retry(10).timeout(Some(60)).build(),
+        )?);
+        Ok(Self { inner })
+    }
+}
+
+impl ChainBackend for ElectrumBackend {
+    fn get_height(&self) -> Result<u32, BdkError> {
+        Ok(self.inner.get_height()?)
+    }
+
+    fn broadcast(&self, tx: &Transaction) -> Result<(), BdkError> {
+        Ok(self.inner.broadcast(tx)?)
+    }
+
+    fn sync_wallet(
+        &self,
+        _keychain_id: KeychainId,
+        _wallet_created_at: DateTime<Utc>,
+        wallet: &Wallet<SqlxWalletDb>,
+    ) -> Result<(), BdkError> {
+        Ok(wallet.sync(&self.inner, Default::default())?)
+    }
+}
@@ -0,0 +1,156 @@
+use bdk::{
+    bitcoin::Transaction,
+    blockchain::{
+        esplora::EsploraBlockchainConfig, Blockchain, ConfigurableBlockchain, EsploraBlockchain,
+        GetHeight,
+    },
+    Wallet,
+};
+
+use chrono::{DateTime, Utc};
+
+use super::ChainBackend;
+use crate::{
+    app::EsploraConfig,
+    bdk::{error::BdkError, pg::SqlxWalletDb},
+    primitives::KeychainId,
+};
+
+pub struct EsploraBackend {
+    inner: EsploraBlockchain,
+}
+
+impl EsploraBackend {
+    pub fn new(config: &EsploraConfig) -> Result<Self, BdkError> {
+        let inner = EsploraBlockchain::from_config(&EsploraBlockchainConfig {
+            base_url: config.url.trim_end_matches('/').to_string(),
+            proxy: None,
+            concurrency: None,
+            stop_gap: config.stop_gap,
+            timeout: Some(config.timeout.as_secs()),
+        })?;
+        Ok(Self { inner })
+    }
+}
+
+impl ChainBackend for EsploraBackend {
+    fn get_height(&self) -> Result<u32, BdkError> {
+        Ok(self.inner.get_height()?)
+    }
+
+    fn broadcast(&self, tx: &Transaction) -> Result<(), BdkError> {
+        Ok(self.inner.broadcast(tx)?)
+    }
+
+    fn sync_wallet(
+        &self,
+        _keychain_id: KeychainId,
+        _wallet_created_at: DateTime<Utc>,
+        wallet: &Wallet<SqlxWalletDb>,
+    ) -> Result<(), BdkError> {
+        Ok(wallet.sync(&self.inner, Default::default())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::{absolute::LockTime, consensus::encode::serialize_hex};
+
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    use super::*;
+
+    struct MockRequest {
+        method: String,
+        path: String,
+        body: String,
+    }
+
+    fn mock_esplora(
+        n_requests: usize,
+        response_body: &'static str,
+    ) -> (String, mpsc::Receiver<MockRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(n_requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut parts = request_line.split_whitespace();
+                let method = parts.next().unwrap().to_string();
+                let path = parts.next().unwrap().to_string();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response_body.len(),
+                    response_body
+                )
+                .unwrap();
+                sender
+                    .send(MockRequest {
+                        method,
+                        path,
+                        body: String::from_utf8(body).unwrap(),
+                    })
+                    .unwrap();
+            }
+        });
+        (url, receiver)
+    }
+
+    fn backend(url: String) -> EsploraBackend {
+        EsploraBackend::new(&EsploraConfig {
+            url,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn get_height() {
+        let (url, requests) = mock_esplora(1, "812345");
+        let height = backend(url).get_height().unwrap();
+        assert_eq!(height, 812345);
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/blocks/tip/height");
+    }
+
+    #[test]
+    fn broadcast() {
+        let tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        };
+        let (url, requests) = mock_esplora(1, "");
+        backend(format!("{url}/")).broadcast(&tx).unwrap();
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/tx");
+        assert_eq!(request.body, serialize_hex(&tx));
+    }
+}
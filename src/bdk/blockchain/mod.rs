@@ -0,0 +1,40 @@
+mod bitcoind_rpc;
+mod electrum;
+mod esplora;
+
+use bdk::{bitcoin::Transaction, Wallet};
+use chrono::{DateTime, Utc};
+
+use std::sync::Arc;
+
+use super::{error::BdkError, pg::SqlxWalletDb};
+use crate::{
+    app::{BlockchainBackend, BlockchainConfig},
+    primitives::KeychainId,
+};
+
+pub use bitcoind_rpc::BitcoindRpcBackend;
+pub use electrum::ElectrumBackend;
+pub use esplora::EsploraBackend;
+
+pub trait ChainBackend: Send + Sync {
+    fn get_height(&self) -> Result<u32, BdkError>;
+    fn broadcast(&self, tx: &Transaction) -> Result<(), BdkError>;
+    fn sync_wallet(
+        &self,
+        keychain_id: KeychainId,
+        wallet_created_at: DateTime<Utc>,
+        wallet: &Wallet<SqlxWalletDb>,
+    ) -> Result<(), BdkError>;
+}
+
+pub fn init_backend(cfg: &BlockchainConfig) -> Result<Arc<dyn ChainBackend>, BdkError> {
+    let backend: Arc<dyn ChainBackend> = match cfg.backend {
+        BlockchainBackend::Electrum => Arc::new(ElectrumBackend::connect(&cfg.electrum_url)?),
+        BlockchainBackend::BitcoindRpc => {
+            Arc::new(BitcoindRpcBackend::connect(cfg.network, &cfg.bitcoind_rpc)?)
+        }
+        BlockchainBackend::Esplora => Arc::new(EsploraBackend::new(&cfg.esplora)?),
+    };
+    Ok(backend)
+}
@@ -8,6 +8,8 @@ pub enum BdkError {
     BdkLibError(#[from] bdk::Error),
     #[error("BdkError - ElectrumClient: {0}")]
     ElectrumClient(#[from] electrum_client::Error),
+    #[error("BdkError - BitcoindRpc: {0}")]
+    BitcoindRpc(#[from] bitcoincore_rpc::Error),
     #[error("BdkError - Sqlx: {0}")]
     Sqlx(#[from] sqlx::Error),
     #[error("BdkError - Serde: {0}")]
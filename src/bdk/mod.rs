@@ -1,3 +1,4 @@
+pub mod blockchain;
 pub mod error;
 pub(crate) mod pg;
 
@@ -94,6 +94,33 @@ impl AdminApiClient {
             .await?;
         output_json(response)
     }
+
+    pub async fn set_fee_rate_override(
+        &self,
+        sat_per_vb: f32,
+        expires_in_secs: u64,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::SetFeeRateOverrideRequest {
+            sat_per_vb: sat_per_vb as f64,
+            expires_in_secs,
+        });
+        let response = self
+            .connect()
+            .await?
+            .set_fee_rate_override(self.inject_admin_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn clear_fee_rate_override(&self) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ClearFeeRateOverrideRequest {});
+        let response = self
+            .connect()
+            .await?
+            .clear_fee_rate_override(self.inject_admin_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
 }
 
 pub fn print_admin_api_key(key: proto::AdminApiKey) {
@@ -3,7 +3,11 @@ use url::Url;
 
 use crate::{
     api::proto,
+    batch::BatchStatus,
+    payout::PayoutStatus,
     primitives::{bitcoin, TxPriority},
+    utxo::UtxoStatus,
+    webhook::WebhookDeliveryStatus,
 };
 type ProtoClient = proto::bria_service_client::BriaServiceClient<tonic::transport::Channel>;
 
@@ -70,17 +74,32 @@ impl ApiClient {
         name: String,
         addresses: Option<Vec<String>>,
         max_payout: Option<u64>,
+        limits: Option<Vec<String>>,
+        approval: Option<(u64, u32, Vec<String>)>,
     ) -> anyhow::Result<()> {
         let policy = proto::SpendingPolicy {
             allowed_payout_addresses: addresses.unwrap_or_default(),
             max_payout_sats: max_payout,
+            limits: parse_spending_limits(limits.unwrap_or_default())?,
+            approval: approval.map(
+                |(threshold_sats, required_approvals, approver_profile_ids)| {
+                    proto::ApprovalPolicy {
+                        threshold_sats,
+                        required_approvals,
+                        approver_profile_ids,
+                    }
+                },
+            ),
+        };
+        let spending_policy = if policy.allowed_payout_addresses.is_empty()
+            && policy.max_payout_sats.is_none()
+            && policy.limits.is_empty()
+            && policy.approval.is_none()
+        {
+            None
+        } else {
+            Some(policy)
         };
-        let spending_policy =
-            if policy.allowed_payout_addresses.is_empty() && policy.max_payout_sats.is_none() {
-                None
-            } else {
-                Some(policy)
-            };
 
         let request = tonic::Request::new(proto::CreateProfileRequest {
             name,
@@ -99,17 +118,32 @@ impl ApiClient {
         id: String,
         addresses: Option<Vec<String>>,
         max_payout: Option<u64>,
+        limits: Option<Vec<String>>,
+        approval: Option<(u64, u32, Vec<String>)>,
     ) -> anyhow::Result<()> {
         let policy = proto::SpendingPolicy {
             allowed_payout_addresses: addresses.unwrap_or_default(),
             max_payout_sats: max_payout,
+            limits: parse_spending_limits(limits.unwrap_or_default())?,
+            approval: approval.map(
+                |(threshold_sats, required_approvals, approver_profile_ids)| {
+                    proto::ApprovalPolicy {
+                        threshold_sats,
+                        required_approvals,
+                        approver_profile_ids,
+                    }
+                },
+            ),
+        };
+        let spending_policy = if policy.allowed_payout_addresses.is_empty()
+            && policy.max_payout_sats.is_none()
+            && policy.limits.is_empty()
+            && policy.approval.is_none()
+        {
+            None
+        } else {
+            Some(policy)
         };
-        let spending_policy =
-            if policy.allowed_payout_addresses.is_empty() && policy.max_payout_sats.is_none() {
-                None
-            } else {
-                Some(policy)
-            };
 
         let request = tonic::Request::new(proto::UpdateProfileRequest {
             id,
@@ -190,6 +224,28 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn get_unsigned_psbt(
+        &self,
+        batch_id: String,
+        xpub_ref: String,
+        output: Option<std::path::PathBuf>,
+        binary: bool,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::GetUnsignedPsbtRequest { batch_id, xpub_ref });
+        let response = self
+            .connect()
+            .await?
+            .get_unsigned_psbt(self.inject_auth_token(request)?)
+            .await?;
+        let path = match output {
+            Some(path) => path,
+            None => return output_json(response),
+        };
+        write_psbt_file(&path, &response.into_inner().unsigned_psbt, binary)?;
+        println!("psbt written to {}", path.display());
+        Ok(())
+    }
+
     pub async fn submit_signed_psbt(
         &self,
         batch_id: String,
@@ -209,6 +265,26 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn list_signing_sessions(&self, batch_id: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ListSigningSessionsRequest { batch_id });
+        let response = self
+            .connect()
+            .await?
+            .list_signing_sessions(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn retry_signing(&self, batch_id: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::RetrySigningRequest { batch_id });
+        let response = self
+            .connect()
+            .await?
+            .retry_signing(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn create_wallet(
         &self,
         name: String,
@@ -228,6 +304,46 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn update_wallet(
+        &self,
+        wallet_name: String,
+        new_name: Option<String>,
+        settle_income_after_n_confs: Option<u32>,
+        settle_change_after_n_confs: Option<u32>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::UpdateWalletRequest {
+            wallet_name,
+            new_name,
+            settle_income_after_n_confs,
+            settle_change_after_n_confs,
+        });
+        let response = self
+            .connect()
+            .await?
+            .update_wallet(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn rotate_wallet_keychain(
+        &self,
+        wallet_name: String,
+        config: impl Into<proto::keychain_config::Config>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::RotateWalletKeychainRequest {
+            wallet_name,
+            keychain_config: Some(proto::KeychainConfig {
+                config: Some(config.into()),
+            }),
+        });
+        let response = self
+            .connect()
+            .await?
+            .rotate_wallet_keychain(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn get_wallet_balance_summary(&self, wallet_name: String) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::GetWalletBalanceSummaryRequest { wallet_name });
         let response = self
@@ -286,9 +402,25 @@ impl ApiClient {
         output_json(response)
     }
 
-    pub async fn list_addresses(&self, wallet: String) -> anyhow::Result<()> {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn list_addresses(
+        &self,
+        wallet: String,
+        external_id_prefix: Option<String>,
+        profile_id: Option<String>,
+        metadata: Option<(String, String)>,
+        include_change: bool,
+        after: Option<String>,
+        page_size: Option<u32>,
+    ) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::ListAddressesRequest {
             wallet_name: wallet,
+            external_id_prefix,
+            profile_id,
+            metadata: metadata.map(|(path, value)| proto::MetadataFilter { path, value }),
+            include_change: Some(include_change),
+            after,
+            page_size,
         });
         let response = self
             .connect()
@@ -328,9 +460,35 @@ impl ApiClient {
         output_json(response)
     }
 
-    pub async fn list_utxos(&self, wallet: String) -> anyhow::Result<()> {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn list_utxos(
+        &self,
+        wallet: String,
+        keychain_id: Option<String>,
+        status: Option<UtxoStatus>,
+        min_value: Option<u64>,
+        max_value: Option<u64>,
+        min_detected_height: Option<u32>,
+        max_detected_height: Option<u32>,
+        after: Option<String>,
+        page_size: Option<u32>,
+    ) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::ListUtxosRequest {
             wallet_name: wallet,
+            keychain_id,
+            status: status.map(|status| match status {
+                UtxoStatus::Pending => proto::UtxoStatus::Pending as i32,
+                UtxoStatus::Settled => proto::UtxoStatus::Settled as i32,
+                UtxoStatus::Reserved => proto::UtxoStatus::Reserved as i32,
+                UtxoStatus::Spent => proto::UtxoStatus::Spent as i32,
+                UtxoStatus::Frozen => proto::UtxoStatus::Frozen as i32,
+            }),
+            min_value,
+            max_value,
+            min_detected_height,
+            max_detected_height,
+            after,
+            page_size,
         });
         let response = self
             .connect()
@@ -340,6 +498,125 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn freeze_utxo(&self, wallet_name: String, outpoint: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::FreezeUtxoRequest {
+            wallet_name,
+            outpoint,
+        });
+        let response = self
+            .connect()
+            .await?
+            .freeze_utxo(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn unfreeze_utxo(&self, wallet_name: String, outpoint: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::UnfreezeUtxoRequest {
+            wallet_name,
+            outpoint,
+        });
+        let response = self
+            .connect()
+            .await?
+            .unfreeze_utxo(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn create_webhook_endpoint(&self, url: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::CreateWebhookEndpointRequest { url });
+        let response = self
+            .connect()
+            .await?
+            .create_webhook_endpoint(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn list_webhook_endpoints(&self) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ListWebhookEndpointsRequest {});
+        let response = self
+            .connect()
+            .await?
+            .list_webhook_endpoints(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn update_webhook_endpoint(
+        &self,
+        id: String,
+        url: Option<String>,
+        enabled: Option<bool>,
+        rotate_secret: bool,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::UpdateWebhookEndpointRequest {
+            id,
+            url,
+            enabled,
+            rotate_secret,
+        });
+        let response = self
+            .connect()
+            .await?
+            .update_webhook_endpoint(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn delete_webhook_endpoint(&self, id: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::DeleteWebhookEndpointRequest { id });
+        let response = self
+            .connect()
+            .await?
+            .delete_webhook_endpoint(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn list_webhook_deliveries(
+        &self,
+        webhook_endpoint_id: String,
+        status: Option<WebhookDeliveryStatus>,
+        limit: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ListWebhookDeliveriesRequest {
+            webhook_endpoint_id,
+            status: status.map(|status| match status {
+                WebhookDeliveryStatus::Pending => proto::WebhookDeliveryStatus::Pending as i32,
+                WebhookDeliveryStatus::Delivered => proto::WebhookDeliveryStatus::Delivered as i32,
+                WebhookDeliveryStatus::DeadLetter => {
+                    proto::WebhookDeliveryStatus::DeadLetter as i32
+                }
+            }),
+            limit,
+        });
+        let response = self
+            .connect()
+            .await?
+            .list_webhook_deliveries(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn redeliver_webhook(
+        &self,
+        webhook_endpoint_id: String,
+        sequence: u64,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::RedeliverWebhookRequest {
+            webhook_endpoint_id,
+            sequence,
+        });
+        let response = self
+            .connect()
+            .await?
+            .redeliver_webhook(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn create_payout_queue(
         &self,
@@ -352,6 +629,9 @@ impl ApiClient {
         cpfp_payouts_after_mins: Option<u32>,
         cpfp_payouts_after_blocks: Option<u32>,
         force_min_change_sats: Option<u64>,
+        min_fee_rate: Option<u64>,
+        max_fee_rate: Option<u64>,
+        consolidation: Option<proto::ConsolidationConfig>,
     ) -> anyhow::Result<()> {
         let tx_priority = match tx_priority {
             TxPriority::NextBlock => proto::TxPriority::NextBlock as i32,
@@ -378,6 +658,9 @@ impl ApiClient {
             cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks,
             force_min_change_sats,
+            min_fee_rate,
+            max_fee_rate,
+            consolidation,
         };
 
         let request = tonic::Request::new(proto::CreatePayoutQueueRequest {
@@ -405,6 +688,21 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn get_fee_rate(&self, tx_priority: TxPriority) -> anyhow::Result<()> {
+        let tx_priority = match tx_priority {
+            TxPriority::NextBlock => proto::TxPriority::NextBlock as i32,
+            TxPriority::HalfHour => proto::TxPriority::HalfHour as i32,
+            TxPriority::OneHour => proto::TxPriority::OneHour as i32,
+        };
+        let request = tonic::Request::new(proto::GetFeeRateRequest { tx_priority });
+        let response = self
+            .connect()
+            .await?
+            .get_fee_rate(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn estimate_payout_fee(
         &self,
         wallet_name: String,
@@ -433,6 +731,7 @@ impl ApiClient {
         output_json(response)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn submit_payout(
         &self,
         wallet_name: String,
@@ -441,6 +740,8 @@ impl ApiClient {
         satoshis: u64,
         external_id: Option<String>,
         metadata: Option<serde_json::Value>,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        spend_outpoints: Vec<String>,
     ) -> anyhow::Result<()> {
         let destination = if let Ok(addr) = destination.parse::<bitcoin::BdkAddress<_>>() {
             proto::submit_payout_request::Destination::OnchainAddress(
@@ -456,6 +757,8 @@ impl ApiClient {
             satoshis,
             external_id,
             metadata: metadata.map(serde_json::from_value).transpose()?,
+            not_before: not_before.map(|time| time.timestamp() as u32),
+            spend_outpoints,
         });
         let response = self
             .connect()
@@ -475,16 +778,37 @@ impl ApiClient {
         output_json(response)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_payouts(
         &self,
-        wallet: String,
+        wallet: Option<String>,
         page: Option<u64>,
         page_size: Option<u64>,
+        status: Option<PayoutStatus>,
+        payout_queue_name: Option<String>,
+        profile_id: Option<String>,
+        created_after: Option<chrono::DateTime<chrono::Utc>>,
+        created_before: Option<chrono::DateTime<chrono::Utc>>,
+        external_id: Option<String>,
+        destination_address: Option<String>,
     ) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::ListPayoutsRequest {
             wallet_name: wallet,
             page,
             page_size,
+            status: status.map(|status| match status {
+                PayoutStatus::Queued => proto::PayoutStatus::Queued as i32,
+                PayoutStatus::Committed => proto::PayoutStatus::Committed as i32,
+                PayoutStatus::Broadcast => proto::PayoutStatus::Broadcast as i32,
+                PayoutStatus::Settled => proto::PayoutStatus::Settled as i32,
+                PayoutStatus::Cancelled => proto::PayoutStatus::Cancelled as i32,
+            }),
+            payout_queue_name,
+            profile_id,
+            created_after: created_after.map(|t| t.timestamp() as u32),
+            created_before: created_before.map(|t| t.timestamp() as u32),
+            external_id,
+            destination_address,
         });
         let response = self
             .connect()
@@ -533,6 +857,26 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn approve_payout(&self, id: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ApprovePayoutRequest { id });
+        let response = self
+            .connect()
+            .await?
+            .approve_payout(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn reject_payout(&self, id: String, reason: Option<String>) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::RejectPayoutRequest { id, reason });
+        let response = self
+            .connect()
+            .await?
+            .reject_payout(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn list_payout_queues(&self) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::ListPayoutQueuesRequest {});
         let response = self
@@ -580,6 +924,9 @@ impl ApiClient {
         cpfp_payouts_after_mins: Option<u32>,
         cpfp_payouts_after_blocks: Option<u32>,
         force_min_change_sats: Option<u64>,
+        min_fee_rate: Option<u64>,
+        max_fee_rate: Option<u64>,
+        consolidation: Option<proto::ConsolidationConfig>,
     ) -> anyhow::Result<()> {
         let tx_priority = tx_priority.map(|priority| match priority {
             TxPriority::NextBlock => proto::TxPriority::NextBlock as i32,
@@ -599,6 +946,9 @@ impl ApiClient {
                 cpfp_payouts_after_mins,
                 cpfp_payouts_after_blocks,
                 force_min_change_sats,
+                min_fee_rate,
+                max_fee_rate,
+                consolidation,
             })
         } else {
             None
@@ -626,15 +976,86 @@ impl ApiClient {
         output_json(response)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub async fn list_batches(
+        &self,
+        page: Option<u64>,
+        page_size: Option<u64>,
+        payout_queue_name: Option<String>,
+        wallet: Option<String>,
+        tx_id: Option<String>,
+        created_after: Option<chrono::DateTime<chrono::Utc>>,
+        created_before: Option<chrono::DateTime<chrono::Utc>>,
+        status: Option<BatchStatus>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ListBatchesRequest {
+            page,
+            page_size,
+            payout_queue_name,
+            wallet_name: wallet,
+            tx_id,
+            created_after: created_after.map(|t| t.timestamp() as u32),
+            created_before: created_before.map(|t| t.timestamp() as u32),
+            status: status.map(|status| match status {
+                BatchStatus::Pending => proto::BatchStatus::Pending as i32,
+                BatchStatus::Broadcast => proto::BatchStatus::Broadcast as i32,
+                BatchStatus::Confirmed => proto::BatchStatus::Confirmed as i32,
+            }),
+        });
+        let response = self
+            .connect()
+            .await?
+            .list_batches(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn bump_batch_fee(
+        &self,
+        id: String,
+        tx_priority: Option<TxPriority>,
+        fee_rate: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let target = match (tx_priority, fee_rate) {
+            (_, Some(rate)) => Some(proto::bump_batch_fee_request::Target::FeeRateSatsPerVbyte(
+                rate,
+            )),
+            (Some(priority), None) => Some(proto::bump_batch_fee_request::Target::TxPriority(
+                match priority {
+                    TxPriority::NextBlock => proto::TxPriority::NextBlock as i32,
+                    TxPriority::HalfHour => proto::TxPriority::HalfHour as i32,
+                    TxPriority::OneHour => proto::TxPriority::OneHour as i32,
+                },
+            )),
+            (None, None) => None,
+        };
+        let request = tonic::Request::new(proto::BumpBatchFeeRequest { id, target });
+        let response = self
+            .connect()
+            .await?
+            .bump_batch_fee(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn watch_events(
         &self,
         one_shot: bool,
         after_sequence: Option<u64>,
         augment: bool,
+        event_types: Vec<String>,
+        wallet_ids: Vec<String>,
+        payout_queue_ids: Vec<String>,
+        profile_ids: Vec<String>,
     ) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::SubscribeAllRequest {
             after_sequence,
             augment: Some(augment),
+            event_types,
+            wallet_ids,
+            payout_queue_ids,
+            profile_ids,
         });
 
         let mut stream = self
@@ -659,3 +1080,84 @@ fn output_json<T: serde::Serialize>(response: tonic::Response<T>) -> anyhow::Res
     println!("{}", serde_json::to_string_pretty(&response.into_inner())?);
     Ok(())
 }
+
+fn parse_spending_limits(limits: Vec<String>) -> anyhow::Result<Vec<proto::SpendingLimit>> {
+    limits
+        .into_iter()
+        .map(|limit| {
+            let parts: Vec<&str> = limit.split(':').collect();
+            if parts.len() != 3 {
+                anyhow::bail!(
+                    "limit '{limit}' is not of the form <WINDOW_SECS>:<MAX_SATS>:<MAX_PAYOUTS>"
+                );
+            }
+            let optional = |part: &str| -> anyhow::Result<Option<u64>> {
+                if part.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(part.parse().context("Couldn't parse limit")?))
+                }
+            };
+            Ok(proto::SpendingLimit {
+                window_secs: parts[0].parse().context("Couldn't parse limit window")?,
+                max_amount_sats: optional(parts[1])?,
+                max_payouts: optional(parts[2])?.map(u32::try_from).transpose()?,
+            })
+        })
+        .collect()
+}
+
+pub(super) fn consolidation_config(
+    below_fee_rate: Option<u64>,
+    wallet_ids: Vec<String>,
+    max_utxos: u32,
+    max_utxo_sats: Option<u64>,
+) -> Option<proto::ConsolidationConfig> {
+    below_fee_rate.map(|fee_rate_threshold| proto::ConsolidationConfig {
+        wallet_ids,
+        fee_rate_threshold,
+        max_utxos,
+        max_utxo_sats,
+    })
+}
+
+/// Writes a base64 encoded psbt to `path`, decoding it to the raw BIP174 format if `binary`.
+fn write_psbt_file(path: &std::path::Path, psbt: &str, binary: bool) -> anyhow::Result<()> {
+    if binary {
+        let psbt = psbt
+            .parse::<bitcoin::psbt::PartiallySignedTransaction>()
+            .context("Couldn't parse psbt")?;
+        std::fs::write(path, psbt.serialize())
+    } else {
+        std::fs::write(path, psbt)
+    }
+    .context("Couldn't write psbt file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bdk::bitcoin::{absolute::LockTime, psbt::PartiallySignedTransaction, Transaction};
+
+    #[test]
+    fn binary_psbt_file_round_trips() -> anyhow::Result<()> {
+        let psbt = PartiallySignedTransaction::from_unsigned_tx(Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        })?;
+        let path = std::env::temp_dir().join(format!("bria-{}.psbt", uuid::Uuid::new_v4()));
+
+        write_psbt_file(&path, &psbt.to_string(), true)?;
+        let written = std::fs::read(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(PartiallySignedTransaction::deserialize(&written)?, psbt);
+
+        write_psbt_file(&path, &psbt.to_string(), false)?;
+        let written = std::fs::read_to_string(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(written.parse::<PartiallySignedTransaction>()?, psbt);
+        Ok(())
+    }
+}
@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 use crate::{
-    admin::AdminApiConfig, api::ApiConfig, app::*, tracing::TracingConfig, xpub::EncryptionKey,
+    admin::AdminApiConfig, api::ApiConfig, app::*, metrics::MetricsConfig, tracing::TracingConfig,
+    xpub::EncryptionKey,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +20,8 @@ pub struct Config {
     pub admin: AdminApiConfig,
     #[serde(default)]
     pub api: ApiConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
 }
 
 pub struct EnvOverride {
@@ -1,7 +1,7 @@
 use bdk::{
     bitcoin::{bip32, secp256k1::Secp256k1, Network},
     keys::{DerivableKey, DescriptorKey, GeneratableKey, GeneratedKey},
-    miniscript::Segwitv0,
+    miniscript::{Segwitv0, Tap},
 };
 
 use chacha20poly1305::{
@@ -12,29 +12,42 @@ use serde::Serialize;
 
 use std::str::FromStr;
 
-pub fn gen_descriptor_keys(network: Network) -> anyhow::Result<()> {
+pub fn gen_descriptor_keys(network: Network, taproot: bool) -> anyhow::Result<()> {
     let root_key: GeneratedKey<bip32::ExtendedPrivKey, Segwitv0> =
         bip32::ExtendedPrivKey::generate(())?;
     let root_key = root_key.into_extended_key()?.into_xprv(network).unwrap();
     println!("ROOT KEY\n{}", root_key);
-    let path = bip32::DerivationPath::from_str("m/84'/0'/0'")?;
+    let path = if taproot {
+        bip32::DerivationPath::from_str("m/86'/0'/0'")?
+    } else {
+        bip32::DerivationPath::from_str("m/84'/0'/0'")?
+    };
     let secp = Secp256k1::new();
-    let external_key: DescriptorKey<Segwitv0> = root_key
-        .derive_priv(&secp, &path)
-        .unwrap()
-        .into_descriptor_key(
-            Some((root_key.fingerprint(&secp), path.clone())),
+    let account_key = root_key.derive_priv(&secp, &path).unwrap();
+    let origin = Some((root_key.fingerprint(&secp), path.clone()));
+    let (external_descriptor, internal_descriptor) = if taproot {
+        let external_key: DescriptorKey<Tap> = account_key.into_descriptor_key(
+            origin.clone(),
             bip32::DerivationPath::from_str("m/0").unwrap(),
         )?;
-    let internal_key: DescriptorKey<Segwitv0> = root_key
-        .derive_priv(&secp, &path)
-        .unwrap()
-        .into_descriptor_key(
-            Some((root_key.fingerprint(&secp), path.clone())),
-            bip32::DerivationPath::from_str("m/1").unwrap(),
+        let internal_key: DescriptorKey<Tap> = account_key
+            .into_descriptor_key(origin, bip32::DerivationPath::from_str("m/1").unwrap())?;
+        (
+            bdk::descriptor!(tr(external_key)).unwrap(),
+            bdk::descriptor!(tr(internal_key)).unwrap(),
+        )
+    } else {
+        let external_key: DescriptorKey<Segwitv0> = account_key.into_descriptor_key(
+            origin.clone(),
+            bip32::DerivationPath::from_str("m/0").unwrap(),
         )?;
-    let external_descriptor = bdk::descriptor!(wpkh(external_key)).unwrap();
-    let internal_descriptor = bdk::descriptor!(wpkh(internal_key)).unwrap();
+        let internal_key: DescriptorKey<Segwitv0> = account_key
+            .into_descriptor_key(origin, bip32::DerivationPath::from_str("m/1").unwrap())?;
+        (
+            bdk::descriptor!(wpkh(external_key)).unwrap(),
+            bdk::descriptor!(wpkh(internal_key)).unwrap(),
+        )
+    };
     let bitcoind_json = serde_json::json!([{
         "desc": external_descriptor.0.to_string_with_secret(&external_descriptor.1),
         "active": true,
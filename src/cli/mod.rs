@@ -12,9 +12,13 @@ use std::path::PathBuf;
 use url::Url;
 
 use crate::{
+    batch::BatchStatus,
     dev_constants,
+    payout::PayoutStatus,
     primitives::{bitcoin, TxPriority},
     token_store,
+    utxo::UtxoStatus,
+    webhook::WebhookDeliveryStatus,
 };
 use config::*;
 
@@ -90,6 +94,18 @@ enum Command {
         /// The max payout amount in Satoshi
         #[clap(short, long)]
         max_payout: Option<u64>,
+        /// Rolling spending limit as <WINDOW_SECS>:<MAX_SATS>:<MAX_PAYOUTS> (either max may be left empty)
+        #[clap(long = "limit")]
+        limits: Option<Vec<String>>,
+        /// Payouts above this amount in Satoshi require approval by other profiles
+        #[clap(long, requires = "required_approvals")]
+        approval_threshold: Option<u64>,
+        /// Number of distinct profiles that must approve a payout above the threshold
+        #[clap(long, requires = "approval_threshold")]
+        required_approvals: Option<u32>,
+        /// Id of a profile allowed to approve payouts above the threshold
+        #[clap(long = "approver", requires = "approval_threshold")]
+        approvers: Option<Vec<String>>,
     },
     /// Update a profile
     UpdateProfile {
@@ -112,6 +128,18 @@ enum Command {
         /// The max payout amount in Satoshi
         #[clap(short, long)]
         max_payout: Option<u64>,
+        /// Rolling spending limit as <WINDOW_SECS>:<MAX_SATS>:<MAX_PAYOUTS> (either max may be left empty)
+        #[clap(long = "limit")]
+        limits: Option<Vec<String>>,
+        /// Payouts above this amount in Satoshi require approval by other profiles
+        #[clap(long, requires = "required_approvals")]
+        approval_threshold: Option<u64>,
+        /// Number of distinct profiles that must approve a payout above the threshold
+        #[clap(long, requires = "approval_threshold")]
+        required_approvals: Option<u32>,
+        /// Id of a profile allowed to approve payouts above the threshold
+        #[clap(long = "approver", requires = "approval_threshold")]
+        approvers: Option<Vec<String>>,
     },
     /// List all profiles
     ListProfiles {
@@ -192,6 +220,29 @@ enum Command {
         #[clap(subcommand)]
         command: SetSignerConfigCommand,
     },
+    /// Get the unsigned psbt of a Batch for an xpub to sign offline
+    GetUnsignedPsbt {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        batch_id: String,
+        #[clap(short, long)]
+        xpub_ref: String,
+        /// Write the psbt to this file instead of printing the response
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+        /// Write the raw binary psbt rather than base64
+        #[clap(long, requires = "output")]
+        binary: bool,
+    },
     /// Submit a signed psbt
     SubmitSignedPsbt {
         #[clap(
@@ -211,6 +262,36 @@ enum Command {
         #[clap(short, long)]
         signed_psbt: String,
     },
+    /// List the signing sessions of a Batch with their attempt history
+    ListSigningSessions {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        batch_id: String,
+    },
+    /// Retry signing a Batch now instead of waiting for the next scheduled attempt
+    RetrySigning {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        batch_id: String,
+    },
     /// Create a wallet from imported xpubs
     CreateWallet {
         #[clap(
@@ -228,6 +309,47 @@ enum Command {
         #[clap(subcommand)]
         command: CreateWalletCommand,
     },
+    /// Update the name or settlement confirmations of a wallet
+    UpdateWallet {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        /// The new name of the wallet
+        #[clap(short, long)]
+        name: Option<String>,
+        /// Confirmations required before incoming utxos are settled
+        #[clap(long)]
+        settle_income_after_n_confs: Option<u32>,
+        /// Confirmations required before change utxos are settled
+        #[clap(long)]
+        settle_change_after_n_confs: Option<u32>,
+    },
+    /// Rotate the keychain used for new addresses and change of a wallet
+    RotateWalletKeychain {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        #[clap(subcommand)]
+        command: CreateWalletCommand,
+    },
     /// Report the balance of a wallet (as reflected in the ledger)
     WalletBalance {
         #[clap(
@@ -312,6 +434,25 @@ enum Command {
         api_key: String,
         #[clap(short, long)]
         wallet: String,
+        #[clap(long)]
+        external_id_prefix: Option<String>,
+        #[clap(long)]
+        profile_id: Option<String>,
+        /// Dot separated path into the address metadata (requires --metadata-value)
+        #[clap(long, requires = "metadata_value")]
+        metadata_path: Option<String>,
+        /// JSON value expected at --metadata-path
+        #[clap(long, requires = "metadata_path")]
+        metadata_value: Option<String>,
+        /// Also list change addresses
+        #[clap(long)]
+        include_change: bool,
+        /// The next_cursor returned by the previous page
+        #[clap(short, long)]
+        after: Option<String>,
+        /// Lists every address in a single response when neither this nor --after is set
+        #[clap(short, long)]
+        page_size: Option<u32>,
     },
     /// Find address by external id or address
     GetAddress {
@@ -344,6 +485,61 @@ enum Command {
         api_key: String,
         #[clap(short, long)]
         wallet: String,
+        /// Only list utxos of this keychain
+        #[clap(short, long)]
+        keychain_id: Option<String>,
+        #[clap(short, long)]
+        status: Option<UtxoStatus>,
+        #[clap(long)]
+        min_value: Option<u64>,
+        #[clap(long)]
+        max_value: Option<u64>,
+        #[clap(long)]
+        min_detected_height: Option<u32>,
+        #[clap(long)]
+        max_detected_height: Option<u32>,
+        /// The next_cursor returned by the previous page
+        #[clap(short, long)]
+        after: Option<String>,
+        /// Lists every utxo in a single response when neither this nor --after is set
+        #[clap(short, long)]
+        page_size: Option<u32>,
+    },
+    /// Exclude a utxo from input selection until it is unfrozen
+    FreezeUtxo {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        /// The outpoint of the utxo (<txid>:<vout>)
+        #[clap(short, long)]
+        outpoint: String,
+    },
+    /// Make a frozen utxo available for input selection again
+    UnfreezeUtxo {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        /// The outpoint of the utxo (<txid>:<vout>)
+        #[clap(short, long)]
+        outpoint: String,
     },
     /// Create a Payuot Queue
     CreatePayoutQueue {
@@ -375,6 +571,24 @@ enum Command {
         cpfp_payouts_after_blocks: Option<u32>,
         #[clap(long)]
         min_change: Option<u64>,
+        /// Floor for the estimated fee rate in sat/vB
+        #[clap(long)]
+        min_fee_rate: Option<u64>,
+        /// Batches are deferred while the estimated fee rate exceeds this (sat/vB)
+        #[clap(long)]
+        max_fee_rate: Option<u64>,
+        /// Consolidate small utxos while the fee rate is below this (sat/vB)
+        #[clap(long, requires = "consolidation_wallet_ids")]
+        consolidation_below_fee_rate: Option<u64>,
+        /// Wallet whose utxos get consolidated (repeatable)
+        #[clap(long = "consolidation-wallet-id")]
+        consolidation_wallet_ids: Vec<String>,
+        /// Maximum number of utxos merged per wallet and batch
+        #[clap(long, default_value = "100")]
+        consolidation_max_utxos: u32,
+        /// Only consolidate utxos up to this value
+        #[clap(long)]
+        consolidation_max_utxo_sats: Option<u64>,
     },
     /// Trigger Payout Queue
     TriggerPayoutQueue {
@@ -385,6 +599,21 @@ enum Command {
         #[clap(short, long)]
         name: String,
     },
+    /// Get the current fee rate quote for a priority
+    GetFeeRate {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short = 'p', long, default_value = "next-block")]
+        tx_priority: TxPriority,
+    },
     EstimatePayoutFee {
         #[clap(
             short,
@@ -428,6 +657,12 @@ enum Command {
         external_id: Option<String>,
         #[clap(short, long, value_parser = parse_json)]
         metadata: Option<serde_json::Value>,
+        /// Hold the payout back until this time (RFC 3339)
+        #[clap(long)]
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        /// Spend this utxo (<txid>:<vout>) in the batch including the payout (repeatable)
+        #[clap(long = "spend-outpoint")]
+        spend_outpoints: Vec<String>,
     },
     /// List pending Payouts
     ListPayouts {
@@ -441,12 +676,29 @@ enum Command {
         url: Option<Url>,
         #[clap(env = "BRIA_API_KEY", default_value = "")]
         api_key: String,
+        /// Only list payouts of this wallet (all wallets of the account if omitted)
         #[clap(short, long)]
-        wallet: String,
+        wallet: Option<String>,
         #[clap(short, long)]
         page: Option<u64>,
         #[clap(short = 's', long = "page-size")]
         page_size: Option<u64>,
+        #[clap(long)]
+        status: Option<PayoutStatus>,
+        #[clap(short = 'q', long = "queue-name")]
+        payout_queue_name: Option<String>,
+        #[clap(long)]
+        profile_id: Option<String>,
+        /// Only list payouts created at or after this time (RFC 3339)
+        #[clap(long)]
+        created_after: Option<chrono::DateTime<chrono::Utc>>,
+        /// Only list payouts created before this time (RFC 3339)
+        #[clap(long)]
+        created_before: Option<chrono::DateTime<chrono::Utc>>,
+        #[clap(long)]
+        external_id: Option<String>,
+        #[clap(long)]
+        destination_address: Option<String>,
     },
     /// Find Payout By external id or payout_id
     GetPayout {
@@ -479,6 +731,38 @@ enum Command {
         #[clap(short = 'i', long)]
         id: String,
     },
+    /// Approve a payout that is pending approval
+    ApprovePayout {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short = 'i', long)]
+        id: String,
+    },
+    /// Reject a payout that is pending approval
+    RejectPayout {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short = 'i', long)]
+        id: String,
+        #[clap(short, long)]
+        reason: Option<String>,
+    },
     /// List Wallets
     ListWallets {
         #[clap(
@@ -536,6 +820,24 @@ enum Command {
         cpfp_payouts_after_blocks: Option<u32>,
         #[clap(long)]
         min_change: Option<u64>,
+        /// Floor for the estimated fee rate in sat/vB
+        #[clap(long)]
+        min_fee_rate: Option<u64>,
+        /// Batches are deferred while the estimated fee rate exceeds this (sat/vB)
+        #[clap(long)]
+        max_fee_rate: Option<u64>,
+        /// Consolidate small utxos while the fee rate is below this (sat/vB)
+        #[clap(long, requires = "consolidation_wallet_ids")]
+        consolidation_below_fee_rate: Option<u64>,
+        /// Wallet whose utxos get consolidated (repeatable)
+        #[clap(long = "consolidation-wallet-id")]
+        consolidation_wallet_ids: Vec<String>,
+        /// Maximum number of utxos merged per wallet and batch
+        #[clap(long, default_value = "100")]
+        consolidation_max_utxos: u32,
+        /// Only consolidate utxos up to this value
+        #[clap(long)]
+        consolidation_max_utxo_sats: Option<u64>,
     },
     /// Get Batch details
     GetBatch {
@@ -552,6 +854,162 @@ enum Command {
         #[clap(short, long)]
         batch_id: String,
     },
+    /// List Batches
+    ListBatches {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        page: Option<u64>,
+        #[clap(short = 's', long = "page-size")]
+        page_size: Option<u64>,
+        #[clap(short = 'q', long = "queue-name")]
+        payout_queue_name: Option<String>,
+        /// Only list batches spending from this wallet
+        #[clap(short, long)]
+        wallet: Option<String>,
+        #[clap(long)]
+        tx_id: Option<String>,
+        /// Only list batches created at or after this time (RFC 3339)
+        #[clap(long)]
+        created_after: Option<chrono::DateTime<chrono::Utc>>,
+        /// Only list batches created before this time (RFC 3339)
+        #[clap(long)]
+        created_before: Option<chrono::DateTime<chrono::Utc>>,
+        #[clap(long)]
+        status: Option<BatchStatus>,
+    },
+    /// Replace a Batch with a higher fee transaction (RBF)
+    BumpBatchFee {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        batch_id: String,
+        #[clap(short = 'p', long, conflicts_with = "fee_rate")]
+        tx_priority: Option<TxPriority>,
+        /// Fee rate in sats/vbyte for the replacement
+        #[clap(short, long)]
+        fee_rate: Option<u64>,
+    },
+    /// Register an endpoint that receives every event as a signed POST request
+    CreateWebhookEndpoint {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        /// The http(s) url events will be posted to
+        #[clap(short, long)]
+        endpoint_url: String,
+    },
+    /// List the registered webhook endpoints
+    ListWebhookEndpoints {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+    },
+    /// Update a webhook endpoint
+    UpdateWebhookEndpoint {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        id: String,
+        #[clap(short, long)]
+        endpoint_url: Option<String>,
+        /// Pause (false) or resume (true) deliveries to the endpoint
+        #[clap(long)]
+        enabled: Option<bool>,
+        /// Generate a new signing secret
+        #[clap(long, default_value = "false")]
+        rotate_secret: bool,
+    },
+    /// Delete a webhook endpoint
+    DeleteWebhookEndpoint {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        id: String,
+    },
+    /// List the most recent deliveries to a webhook endpoint
+    ListWebhookDeliveries {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        id: String,
+        #[clap(short, long)]
+        status: Option<WebhookDeliveryStatus>,
+        #[clap(short, long)]
+        limit: Option<u64>,
+    },
+    /// Deliver an event to a webhook endpoint again
+    RedeliverWebhook {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        id: String,
+        /// The sequence of the event to redeliver
+        #[clap(short, long)]
+        sequence: u64,
+    },
     /// Watch or fetch events
     WatchEvents {
         #[clap(
@@ -573,6 +1031,18 @@ enum Command {
         /// Include augmented information in events
         #[clap(long, default_value = "false")]
         augment: bool,
+        /// Only watch events of this type (eg. utxo_detected), can be repeated
+        #[clap(long = "event-type")]
+        event_types: Vec<String>,
+        /// Only watch events of this wallet, can be repeated
+        #[clap(long = "wallet-id")]
+        wallet_ids: Vec<String>,
+        /// Only watch events of this payout queue, can be repeated
+        #[clap(long = "payout-queue-id")]
+        payout_queue_ids: Vec<String>,
+        /// Only watch events of payouts submitted by this profile, can be repeated
+        #[clap(long = "profile-id")]
+        profile_ids: Vec<String>,
     },
 }
 
@@ -582,6 +1052,9 @@ enum UtilsCommand {
     GenDescriptorKeys {
         #[clap(short, long, default_value = "bitcoin")]
         network: bitcoin::Network,
+        /// Generate single key taproot (tr) descriptors instead of wpkh
+        #[clap(long, default_value = "false")]
+        taproot: bool,
     },
     /// generate a hex encoded 32 byte random key
     GenSignerEncryptionKey {},
@@ -623,6 +1096,17 @@ enum AdminCommand {
         name: String,
     },
     ListAccounts {},
+    /// Override the estimated fee rate for all payout queues
+    SetFeeRateOverride {
+        /// The fee rate to use in sat/vB
+        #[clap(long)]
+        sat_per_vb: f32,
+        /// How long the override stays active
+        #[clap(long, default_value = "3600")]
+        expires_in_secs: u64,
+    },
+    /// Remove the active fee rate override
+    ClearFeeRateOverride {},
 }
 
 #[derive(Subcommand)]
@@ -636,6 +1120,15 @@ enum CreateWalletCommand {
         #[clap(short, long)]
         derivation: Option<String>,
     },
+    /// Initialize the wallet via single key taproot (tr)
+    Tr {
+        /// The xpub-ref or xpub to use
+        #[clap(short, long)]
+        xpub: String,
+        /// If an xpub is being imported, the derivation path to use
+        #[clap(short, long)]
+        derivation: Option<String>,
+    },
     /// Initialize the wallet via descriptors
     Descriptors {
         /// The descriptor for external addresses
@@ -729,7 +1222,9 @@ pub async fn run() -> anyhow::Result<()> {
             }
         }
         Command::Utils { command } => match command {
-            UtilsCommand::GenDescriptorKeys { network } => gen::gen_descriptor_keys(network)?,
+            UtilsCommand::GenDescriptorKeys { network, taproot } => {
+                gen::gen_descriptor_keys(network, taproot)?
+            }
             UtilsCommand::GenSignerEncryptionKey {} => gen::gen_signer_encryption_key()?,
             UtilsCommand::ExtractAddresses { path } => {
                 address_extractor::read_and_parse_addresses(path)?
@@ -759,6 +1254,17 @@ pub async fn run() -> anyhow::Result<()> {
                 AdminCommand::ListAccounts {} => {
                     client.list_accounts().await?;
                 }
+                AdminCommand::SetFeeRateOverride {
+                    sat_per_vb,
+                    expires_in_secs,
+                } => {
+                    client
+                        .set_fee_rate_override(sat_per_vb, expires_in_secs)
+                        .await?;
+                }
+                AdminCommand::ClearFeeRateOverride {} => {
+                    client.clear_fee_rate_override().await?;
+                }
             }
         }
         Command::CreateProfile {
@@ -767,9 +1273,25 @@ pub async fn run() -> anyhow::Result<()> {
             name,
             addresses,
             max_payout,
+            limits,
+            approval_threshold,
+            required_approvals,
+            approvers,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.create_profile(name, addresses, max_payout).await?;
+            client
+                .create_profile(
+                    name,
+                    addresses,
+                    max_payout,
+                    limits,
+                    approval_threshold
+                        .zip(required_approvals)
+                        .map(|(threshold, required)| {
+                            (threshold, required, approvers.unwrap_or_default())
+                        }),
+                )
+                .await?;
         }
         Command::UpdateProfile {
             url,
@@ -777,9 +1299,25 @@ pub async fn run() -> anyhow::Result<()> {
             id,
             addresses,
             max_payout,
+            limits,
+            approval_threshold,
+            required_approvals,
+            approvers,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.update_profile(id, addresses, max_payout).await?;
+            client
+                .update_profile(
+                    id,
+                    addresses,
+                    max_payout,
+                    limits,
+                    approval_threshold
+                        .zip(required_approvals)
+                        .map(|(threshold, required)| {
+                            (threshold, required, approvers.unwrap_or_default())
+                        }),
+                )
+                .await?;
         }
         Command::ListProfiles { url, api_key } => {
             let client = api_client(cli.bria_home, url, api_key);
@@ -812,6 +1350,19 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.set_signer_config(xpub, command).await?;
         }
+        Command::GetUnsignedPsbt {
+            url,
+            api_key,
+            batch_id,
+            xpub_ref,
+            output,
+            binary,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .get_unsigned_psbt(batch_id, xpub_ref, output, binary)
+                .await?;
+        }
         Command::SubmitSignedPsbt {
             url,
             api_key,
@@ -824,6 +1375,22 @@ pub async fn run() -> anyhow::Result<()> {
                 .submit_signed_psbt(batch_id, xpub_ref, signed_psbt)
                 .await?;
         }
+        Command::ListSigningSessions {
+            url,
+            api_key,
+            batch_id,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.list_signing_sessions(batch_id).await?;
+        }
+        Command::RetrySigning {
+            url,
+            api_key,
+            batch_id,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.retry_signing(batch_id).await?;
+        }
         Command::CreateWallet {
             url,
             api_key,
@@ -833,6 +1400,33 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.create_wallet(name, command).await?;
         }
+        Command::UpdateWallet {
+            url,
+            api_key,
+            wallet,
+            name,
+            settle_income_after_n_confs,
+            settle_change_after_n_confs,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .update_wallet(
+                    wallet,
+                    name,
+                    settle_income_after_n_confs,
+                    settle_change_after_n_confs,
+                )
+                .await?;
+        }
+        Command::RotateWalletKeychain {
+            url,
+            api_key,
+            wallet,
+            command,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.rotate_wallet_keychain(wallet, command).await?;
+        }
         Command::WalletBalance {
             url,
             api_key,
@@ -871,9 +1465,26 @@ pub async fn run() -> anyhow::Result<()> {
             url,
             api_key,
             wallet,
+            external_id_prefix,
+            profile_id,
+            metadata_path,
+            metadata_value,
+            include_change,
+            after,
+            page_size,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.list_addresses(wallet).await?;
+            client
+                .list_addresses(
+                    wallet,
+                    external_id_prefix,
+                    profile_id,
+                    metadata_path.zip(metadata_value),
+                    include_change,
+                    after,
+                    page_size,
+                )
+                .await?;
         }
         Command::GetAddress {
             url,
@@ -888,9 +1499,47 @@ pub async fn run() -> anyhow::Result<()> {
             url,
             api_key,
             wallet,
+            keychain_id,
+            status,
+            min_value,
+            max_value,
+            min_detected_height,
+            max_detected_height,
+            after,
+            page_size,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .list_utxos(
+                    wallet,
+                    keychain_id,
+                    status,
+                    min_value,
+                    max_value,
+                    min_detected_height,
+                    max_detected_height,
+                    after,
+                    page_size,
+                )
+                .await?;
+        }
+        Command::FreezeUtxo {
+            url,
+            api_key,
+            wallet,
+            outpoint,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.freeze_utxo(wallet, outpoint).await?;
+        }
+        Command::UnfreezeUtxo {
+            url,
+            api_key,
+            wallet,
+            outpoint,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.list_utxos(wallet).await?;
+            client.unfreeze_utxo(wallet, outpoint).await?;
         }
         Command::CreatePayoutQueue {
             url,
@@ -904,6 +1553,12 @@ pub async fn run() -> anyhow::Result<()> {
             cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks,
             min_change,
+            min_fee_rate,
+            max_fee_rate,
+            consolidation_below_fee_rate,
+            consolidation_wallet_ids,
+            consolidation_max_utxos,
+            consolidation_max_utxo_sats,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
             client
@@ -917,6 +1572,14 @@ pub async fn run() -> anyhow::Result<()> {
                     cpfp_payouts_after_mins,
                     cpfp_payouts_after_blocks,
                     min_change,
+                    min_fee_rate,
+                    max_fee_rate,
+                    api_client::consolidation_config(
+                        consolidation_below_fee_rate,
+                        consolidation_wallet_ids,
+                        consolidation_max_utxos,
+                        consolidation_max_utxo_sats,
+                    ),
                 )
                 .await?;
         }
@@ -924,6 +1587,14 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.trigger_payout_queue(name).await?;
         }
+        Command::GetFeeRate {
+            url,
+            api_key,
+            tx_priority,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.get_fee_rate(tx_priority).await?;
+        }
         Command::EstimatePayoutFee {
             url,
             api_key,
@@ -946,6 +1617,8 @@ pub async fn run() -> anyhow::Result<()> {
             amount,
             external_id,
             metadata,
+            not_before,
+            spend_outpoints,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
             client
@@ -956,6 +1629,8 @@ pub async fn run() -> anyhow::Result<()> {
                     amount,
                     external_id,
                     metadata,
+                    not_before,
+                    spend_outpoints,
                 )
                 .await?;
         }
@@ -965,9 +1640,29 @@ pub async fn run() -> anyhow::Result<()> {
             wallet,
             page,
             page_size,
+            status,
+            payout_queue_name,
+            profile_id,
+            created_after,
+            created_before,
+            external_id,
+            destination_address,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.list_payouts(wallet, page, page_size).await?;
+            client
+                .list_payouts(
+                    wallet,
+                    page,
+                    page_size,
+                    status,
+                    payout_queue_name,
+                    profile_id,
+                    created_after,
+                    created_before,
+                    external_id,
+                    destination_address,
+                )
+                .await?;
         }
         Command::GetPayout {
             url,
@@ -982,6 +1677,19 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.cancel_payout(id).await?;
         }
+        Command::ApprovePayout { url, api_key, id } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.approve_payout(id).await?;
+        }
+        Command::RejectPayout {
+            url,
+            api_key,
+            id,
+            reason,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.reject_payout(id, reason).await?;
+        }
         Command::ListWallets { url, api_key } => {
             let client = api_client(cli.bria_home, url, api_key);
             client.list_wallets().await?;
@@ -1001,6 +1709,12 @@ pub async fn run() -> anyhow::Result<()> {
             cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks,
             min_change,
+            min_fee_rate,
+            max_fee_rate,
+            consolidation_below_fee_rate,
+            consolidation_wallet_ids,
+            consolidation_max_utxos,
+            consolidation_max_utxo_sats,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
             client
@@ -1013,6 +1727,14 @@ pub async fn run() -> anyhow::Result<()> {
                     cpfp_payouts_after_mins,
                     cpfp_payouts_after_blocks,
                     min_change,
+                    min_fee_rate,
+                    max_fee_rate,
+                    api_client::consolidation_config(
+                        consolidation_below_fee_rate,
+                        consolidation_wallet_ids,
+                        consolidation_max_utxos,
+                        consolidation_max_utxo_sats,
+                    ),
                 )
                 .await?;
         }
@@ -1028,15 +1750,115 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.get_batch(batch_id).await?;
         }
+        Command::ListBatches {
+            url,
+            api_key,
+            page,
+            page_size,
+            payout_queue_name,
+            wallet,
+            tx_id,
+            created_after,
+            created_before,
+            status,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .list_batches(
+                    page,
+                    page_size,
+                    payout_queue_name,
+                    wallet,
+                    tx_id,
+                    created_after,
+                    created_before,
+                    status,
+                )
+                .await?;
+        }
+        Command::BumpBatchFee {
+            url,
+            api_key,
+            batch_id,
+            tx_priority,
+            fee_rate,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .bump_batch_fee(batch_id, tx_priority, fee_rate)
+                .await?;
+        }
+        Command::CreateWebhookEndpoint {
+            url,
+            api_key,
+            endpoint_url,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.create_webhook_endpoint(endpoint_url).await?;
+        }
+        Command::ListWebhookEndpoints { url, api_key } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.list_webhook_endpoints().await?;
+        }
+        Command::UpdateWebhookEndpoint {
+            url,
+            api_key,
+            id,
+            endpoint_url,
+            enabled,
+            rotate_secret,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .update_webhook_endpoint(id, endpoint_url, enabled, rotate_secret)
+                .await?;
+        }
+        Command::DeleteWebhookEndpoint { url, api_key, id } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.delete_webhook_endpoint(id).await?;
+        }
+        Command::ListWebhookDeliveries {
+            url,
+            api_key,
+            id,
+            status,
+            limit,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.list_webhook_deliveries(id, status, limit).await?;
+        }
+        Command::RedeliverWebhook {
+            url,
+            api_key,
+            id,
+            sequence,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.redeliver_webhook(id, sequence).await?;
+        }
         Command::WatchEvents {
             url,
             api_key,
             one_shot,
             after,
             augment,
+            event_types,
+            wallet_ids,
+            payout_queue_ids,
+            profile_ids,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.watch_events(one_shot, after, augment).await?;
+            client
+                .watch_events(
+                    one_shot,
+                    after,
+                    augment,
+                    event_types,
+                    wallet_ids,
+                    payout_queue_ids,
+                    profile_ids,
+                )
+                .await?;
         }
     }
     Ok(())
@@ -1058,6 +1880,7 @@ async fn run_cmd(
         admin,
         api,
         app,
+        metrics,
     }: Config,
     dev: bool,
     dev_xpub: Option<(String, String)>,
@@ -1090,6 +1913,16 @@ async fn run_cmd(
         });
     }));
 
+    let metrics_send = send.clone();
+    let metrics_pool = pool.clone();
+    handles.push(tokio::spawn(async move {
+        let _ = metrics_send.try_send(
+            super::metrics::run(metrics_pool, metrics)
+                .await
+                .context("Metrics server error"),
+        );
+    }));
+
     let api_send = send.clone();
     handles.push(tokio::spawn(async move {
         let _ = api_send.try_send(if dev {
@@ -1168,6 +2001,10 @@ impl From<CreateWalletCommand> for crate::api::proto::keychain_config::Config {
                 xpub,
                 derivation_path: derivation,
             }),
+            CreateWalletCommand::Tr { xpub, derivation } => Config::Tr(Tr {
+                xpub,
+                derivation_path: derivation,
+            }),
             CreateWalletCommand::Descriptors {
                 descriptor,
                 change_descriptor,
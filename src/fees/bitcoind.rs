@@ -0,0 +1,66 @@
+use bdk::FeeRate;
+use bitcoincore_rpc::{Auth, Client, RpcApi};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::error::FeeEstimationError;
+use crate::primitives::TxPriority;
+
+#[derive(Clone, Debug)]
+pub struct BitcoindFeeClient {
+    config: BitcoindFeeConfig,
+}
+
+impl BitcoindFeeClient {
+    pub fn new(config: BitcoindFeeConfig) -> Self {
+        Self { config }
+    }
+
+    #[instrument(name = "bitcoind.fee_rate", skip(self), ret, err)]
+    pub async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError> {
+        let config = self.config.clone();
+        let conf_target = priority.n_blocks() as u16;
+        let estimate = tokio::task::spawn_blocking(move || {
+            let client = Client::new(
+                &config.url,
+                Auth::UserPass(config.rpc_user, config.rpc_password),
+            )?;
+            client.estimate_smart_fee(conf_target, None)
+        })
+        .await??;
+        // estimatesmartfee reports BTC/kvB
+        let sats_per_kvb = estimate
+            .fee_rate
+            .ok_or_else(|| {
+                FeeEstimationError::NoEstimateAvailable(
+                    estimate.errors.unwrap_or_default().join(", "),
+                )
+            })?
+            .to_sat();
+        Ok(FeeRate::from_sat_per_vb(sats_per_kvb as f32 / 1000.0))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoindFeeConfig {
+    #[serde(default = "default_url")]
+    pub url: String,
+    #[serde(default)]
+    pub rpc_user: String,
+    #[serde(default)]
+    pub rpc_password: String,
+}
+
+impl Default for BitcoindFeeConfig {
+    fn default() -> Self {
+        Self {
+            url: default_url(),
+            rpc_user: String::new(),
+            rpc_password: String::new(),
+        }
+    }
+}
+
+fn default_url() -> String {
+    "http://127.0.0.1:18443".to_string()
+}
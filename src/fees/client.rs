@@ -1,34 +1,126 @@
 use bdk::FeeRate;
+use tokio::sync::RwLock;
 use tracing::instrument;
 
-use super::{blockstream::*, config::*, error::*, mempool_space::*};
+use std::{collections::HashMap, sync::Arc};
+
+use super::{
+    bitcoind::*, blockstream::*, config::*, error::*, mempool_space::*, overrides::*, quote::*,
+};
 use crate::primitives::TxPriority;
 
 #[derive(Clone, Debug)]
 pub struct FeesClient {
+    providers: Vec<FeeProvider>,
     mempool_space: MempoolSpaceClient,
     blockstream: BlockstreamClient,
+    bitcoind: BitcoindFeeClient,
+    overrides: FeeRateOverrides,
+    cache_ttl: std::time::Duration,
+    cache: Arc<RwLock<HashMap<TxPriority, FeeRateQuote>>>,
 }
 
 impl FeesClient {
-    pub fn new(config: FeesConfig) -> Self {
+    pub fn new(config: FeesConfig, pool: &sqlx::PgPool) -> Self {
         Self {
+            providers: config.providers,
             mempool_space: MempoolSpaceClient::new(config.mempool_space),
             blockstream: BlockstreamClient::new(config.blockstream),
+            bitcoind: BitcoindFeeClient::new(config.bitcoind),
+            overrides: FeeRateOverrides::new(pool),
+            cache_ttl: config.cache_ttl,
+            cache: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    #[instrument(name = "fees.fee_rate", skip(self), fields(fee_rate), err)]
+    pub async fn active_override(&self) -> Result<Option<FeeRateOverride>, FeeEstimationError> {
+        self.overrides.find_active().await
+    }
+
     pub async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError> {
-        let fee_rate = if let Ok(fee_rate) = self.mempool_space.fee_rate(priority).await {
-            fee_rate
+        Ok(self.fee_rate_quote(priority).await?.fee_rate)
+    }
+
+    #[instrument(
+        name = "fees.fee_rate_quote",
+        skip(self),
+        fields(fee_rate, source, stale),
+        err
+    )]
+    pub async fn fee_rate_quote(
+        &self,
+        priority: TxPriority,
+    ) -> Result<FeeRateQuote, FeeEstimationError> {
+        let quote = if let Some(fee_rate_override) = self.overrides.find_active().await? {
+            FeeRateQuote {
+                fee_rate: fee_rate_override.fee_rate,
+                source: FeeRateSource::Override,
+                fetched_at: chrono::Utc::now(),
+                stale: false,
+            }
         } else {
-            self.blockstream.fee_rate(priority).await?
+            self.cached_or_fetch(priority).await?
         };
-        tracing::Span::current().record(
+        let span = tracing::Span::current();
+        span.record(
             "fee_rate",
-            tracing::field::display(format!("{:?}", fee_rate)),
+            tracing::field::display(format!("{:?}", quote.fee_rate)),
         );
-        Ok(fee_rate)
+        span.record("source", tracing::field::debug(quote.source));
+        span.record("stale", quote.stale);
+        Ok(quote)
+    }
+
+    async fn cached_or_fetch(
+        &self,
+        priority: TxPriority,
+    ) -> Result<FeeRateQuote, FeeEstimationError> {
+        let cached = self.cache.read().await.get(&priority).copied();
+        if let Some(quote) = cached {
+            if quote.age() < self.cache_ttl {
+                return Ok(quote);
+            }
+        }
+        match self.fetch(priority).await {
+            Ok((fee_rate, provider)) => {
+                let quote = FeeRateQuote {
+                    fee_rate,
+                    source: FeeRateSource::from(provider),
+                    fetched_at: chrono::Utc::now(),
+                    stale: false,
+                };
+                self.cache.write().await.insert(priority, quote);
+                Ok(quote)
+            }
+            Err(e) => match cached {
+                Some(quote) => Ok(FeeRateQuote {
+                    stale: true,
+                    ..quote
+                }),
+                None => Err(e),
+            },
+        }
+    }
+
+    async fn fetch(
+        &self,
+        priority: TxPriority,
+    ) -> Result<(FeeRate, FeeProvider), FeeEstimationError> {
+        let mut last_err = FeeEstimationError::NoProvidersConfigured;
+        for provider in self.providers.iter() {
+            let res = match provider {
+                FeeProvider::MempoolSpace => self.mempool_space.fee_rate(priority).await,
+                FeeProvider::Blockstream => self.blockstream.fee_rate(priority).await,
+                FeeProvider::Bitcoind => self.bitcoind.fee_rate(priority).await,
+            };
+            match res {
+                Ok(fee_rate) => return Ok((fee_rate, *provider)),
+                Err(e) => {
+                    crate::metrics::record_fee_provider_error(*provider);
+                    last_err = e;
+                }
+            }
+        }
+        Err(last_err)
     }
 }
@@ -1,12 +1,50 @@
 use serde::{Deserialize, Serialize};
 
+use super::bitcoind::BitcoindFeeConfig;
 use super::blockstream::BlockstreamConfig;
 use super::mempool_space::MempoolSpaceConfig;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeProvider {
+    MempoolSpace,
+    Blockstream,
+    Bitcoind,
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeesConfig {
+    /// Providers are queried in order until one returns an estimate
+    #[serde(default = "default_providers")]
+    pub providers: Vec<FeeProvider>,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl: std::time::Duration,
     #[serde(default)]
     pub mempool_space: MempoolSpaceConfig,
     #[serde(default)]
     pub blockstream: BlockstreamConfig,
+    #[serde(default)]
+    pub bitcoind: BitcoindFeeConfig,
+}
+
+impl Default for FeesConfig {
+    fn default() -> Self {
+        Self {
+            providers: default_providers(),
+            cache_ttl: default_cache_ttl(),
+            mempool_space: MempoolSpaceConfig::default(),
+            blockstream: BlockstreamConfig::default(),
+            bitcoind: BitcoindFeeConfig::default(),
+        }
+    }
+}
+
+fn default_providers() -> Vec<FeeProvider> {
+    vec![FeeProvider::MempoolSpace, FeeProvider::Blockstream]
+}
+
+fn default_cache_ttl() -> std::time::Duration {
+    std::time::Duration::from_secs(60)
 }
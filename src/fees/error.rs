@@ -6,4 +6,14 @@ pub enum FeeEstimationError {
     FeeEstimation(#[from] reqwest_middleware::Error),
     #[error("FeeEstimationError - CouldNotDecodeResponseBody: {0}")]
     CouldNotDecodeResponseBody(reqwest::Error),
+    #[error("FeeEstimationError - BitcoindRpc: {0}")]
+    BitcoindRpc(#[from] bitcoincore_rpc::Error),
+    #[error("FeeEstimationError - JoinError: {0}")]
+    JoinError(#[from] tokio::task::JoinError),
+    #[error("FeeEstimationError - NoEstimateAvailable: {0}")]
+    NoEstimateAvailable(String),
+    #[error("FeeEstimationError - Sqlx: {0}")]
+    Sqlx(#[from] sqlx::Error),
+    #[error("FeeEstimationError - NoProvidersConfigured")]
+    NoProvidersConfigured,
 }
@@ -1,19 +1,25 @@
 #![allow(warnings)]
+mod bitcoind;
 mod blockstream;
 mod client;
 mod config;
 pub mod error;
 mod mempool_space;
+mod overrides;
+mod quote;
 
 use bdk::bitcoin::{locktime::absolute::LockTime, Transaction, TxOut, Weight};
 use std::collections::HashMap;
 
 use crate::primitives::*;
 
+pub use bitcoind::*;
 pub use blockstream::*;
 pub use client::*;
 pub use config::*;
 pub use mempool_space::*;
+pub use overrides::*;
+pub use quote::*;
 
 use error::FeeEstimationError;
 
@@ -26,9 +32,13 @@ pub async fn fees_to_encumber(
     satisfaction_weight: usize,
 ) -> Result<Satoshis, FeeEstimationError> {
     let fee_rate = fees_client.fee_rate(TxPriority::NextBlock).await?;
-    Ok(Satoshis::from(fee_rate.fee_wu(Weight::from_wu(
+    Ok(fee_to_spend_input(fee_rate, satisfaction_weight))
+}
+
+pub fn fee_to_spend_input(fee_rate: bitcoin::FeeRate, satisfaction_weight: usize) -> Satoshis {
+    Satoshis::from(fee_rate.fee_wu(Weight::from_wu(
         (TXIN_BASE_WEIGHT + satisfaction_weight) as u64,
-    ))))
+    )))
 }
 
 pub fn estimate_proportional_fee(
@@ -155,6 +165,17 @@ mod tests {
         assert_eq!(estimate, total_fee);
     }
 
+    #[test]
+    fn test_tr_input_is_cheaper_to_encumber() {
+        let wpkh : bdk::descriptor::ExtendedDescriptor = "wpkh([6f2fa1b2/84'/0'/0']tpubDDDDGYiFda8HfJRc2AHFJDxVzzEtBPrKsbh35EaW2UGd5qfzrF2G87ewAgeeRyHEz4iB3kvhAYW1sH6dpLepTkFUzAktumBN8AXeXWE9nd1/0/*)".parse().unwrap();
+        let tr : bdk::descriptor::ExtendedDescriptor = "tr([6f2fa1b2/86'/0'/0']tpubDDDDGYiFda8HfJRc2AHFJDxVzzEtBPrKsbh35EaW2UGd5qfzrF2G87ewAgeeRyHEz4iB3kvhAYW1sH6dpLepTkFUzAktumBN8AXeXWE9nd1/0/*)".parse().unwrap();
+
+        let tr_weight = tr.max_satisfaction_weight().unwrap();
+        // scriptSigLen + stackLen + sigLen + schnorr signature with sighash byte
+        assert_eq!(tr_weight, 4 + 1 + 1 + 65);
+        assert!(tr_weight < wpkh.max_satisfaction_weight().unwrap());
+    }
+
     #[test]
     fn test_allocate_proportional_fees() {
         let fees = Satoshis::from(1000);
@@ -0,0 +1,76 @@
+use bdk::FeeRate;
+use sqlx::PgPool;
+use tracing::instrument;
+
+use super::error::FeeEstimationError;
+
+#[derive(Debug, Clone)]
+pub struct FeeRateOverride {
+    pub fee_rate: FeeRate,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeeRateOverrides {
+    pool: PgPool,
+}
+
+impl FeeRateOverrides {
+    pub fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    #[instrument(name = "fee_rate_overrides.set", skip(self), err)]
+    pub async fn set(
+        &self,
+        fee_rate: FeeRate,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<FeeRateOverride, FeeEstimationError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"UPDATE bria_fee_rate_overrides SET expires_at = NOW() WHERE expires_at > NOW()"#
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"INSERT INTO bria_fee_rate_overrides (id, sat_per_vb, expires_at)
+            VALUES ($1, $2, $3)"#,
+            uuid::Uuid::new_v4(),
+            fee_rate.as_sat_per_vb() as f64,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(FeeRateOverride {
+            fee_rate,
+            expires_at,
+        })
+    }
+
+    #[instrument(name = "fee_rate_overrides.clear", skip(self), err)]
+    pub async fn clear(&self) -> Result<(), FeeEstimationError> {
+        sqlx::query!(
+            r#"UPDATE bria_fee_rate_overrides SET expires_at = NOW() WHERE expires_at > NOW()"#
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    #[instrument(name = "fee_rate_overrides.find_active", skip(self), err)]
+    pub async fn find_active(&self) -> Result<Option<FeeRateOverride>, FeeEstimationError> {
+        let row = sqlx::query!(
+            r#"SELECT sat_per_vb, expires_at FROM bria_fee_rate_overrides
+            WHERE expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1"#
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|row| FeeRateOverride {
+            fee_rate: FeeRate::from_sat_per_vb(row.sat_per_vb as f32),
+            expires_at: row.expires_at,
+        }))
+    }
+}
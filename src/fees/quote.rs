@@ -0,0 +1,40 @@
+use bdk::FeeRate;
+use serde::{Deserialize, Serialize};
+
+use super::config::FeeProvider;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeRateSource {
+    Override,
+    MempoolSpace,
+    Blockstream,
+    Bitcoind,
+}
+
+impl From<FeeProvider> for FeeRateSource {
+    fn from(provider: FeeProvider) -> Self {
+        match provider {
+            FeeProvider::MempoolSpace => Self::MempoolSpace,
+            FeeProvider::Blockstream => Self::Blockstream,
+            FeeProvider::Bitcoind => Self::Bitcoind,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FeeRateQuote {
+    pub fee_rate: FeeRate,
+    pub source: FeeRateSource,
+    pub fetched_at: chrono::DateTime<chrono::Utc>,
+    /// Set when every provider failed and the last known good rate is served instead
+    pub stale: bool,
+}
+
+impl FeeRateQuote {
+    pub fn age(&self) -> std::time::Duration {
+        (chrono::Utc::now() - self.fetched_at)
+            .to_std()
+            .unwrap_or_default()
+    }
+}
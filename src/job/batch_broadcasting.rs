@@ -1,12 +1,10 @@
-use bdk::blockchain::{Blockchain, ElectrumBlockchain};
-use electrum_client::{Client, ConfigBuilder};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 use std::collections::HashMap;
 
 use super::error::JobError;
-use crate::{app::BlockchainConfig, batch::*, bdk::error::BdkError, primitives::*};
+use crate::{app::BlockchainConfig, batch::*, bdk::blockchain::init_backend, primitives::*};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchBroadcastingData {
@@ -27,23 +25,18 @@ pub async fn execute(
     blockchain_cfg: BlockchainConfig,
     batches: Batches,
 ) -> Result<BatchBroadcastingData, JobError> {
-    let blockchain = init_electrum(&blockchain_cfg.electrum_url).await?;
+    let blockchain = init_backend(&blockchain_cfg)?;
     let batch = batches.find_by_id(data.account_id, data.batch_id).await?;
     let span = tracing::Span::current();
     span.record("txid", tracing::field::display(batch.bitcoin_tx_id));
+    if batch.is_replaced() {
+        return Ok(data);
+    }
     if batch.accounting_complete() {
         if let Some(tx) = batch.signed_tx {
-            blockchain.broadcast(&tx).map_err(BdkError::BdkLibError)?;
+            blockchain.broadcast(&tx)?;
             span.record("broadcast", true);
         }
     }
     Ok(data)
 }
-
-async fn init_electrum(electrum_url: &str) -> Result<ElectrumBlockchain, BdkError> {
-    let blockchain = ElectrumBlockchain::from(Client::from_config(
-        electrum_url,
-        ConfigBuilder::new().retry(10).timeout(Some(60)).build(),
-    )?);
-    Ok(blockchain)
-}
@@ -44,6 +44,26 @@ pub async fn execute(
     let mut stalled = false;
     let mut last_err = None;
     let mut current_keychain = None;
+    // Released when the attempt finishes and the transaction is dropped
+    let mut signing_lock = pool.begin().await?;
+    if !batches
+        .try_lock_for_signing(&mut signing_lock, data.batch_id)
+        .await?
+    {
+        span.record("finalization_status", "in_progress");
+        return Ok((data, false));
+    }
+    // Loaded under the lock so an attempt that just finished is visible
+    let batch = batches.find_by_id(data.account_id, data.batch_id).await?;
+    if batch.is_replaced() {
+        span.record("finalization_status", "replaced");
+        return Ok((data, false));
+    }
+    if batch.signed_tx.is_some() {
+        span.record("finalization_status", "already_signed");
+        return Ok((data, false));
+    }
+    let expected_fee = batch.total_fee_sats;
     let (mut sessions, mut account_xpub_cache) = if let Some(batch_session) = signing_sessions
         .list_for_batch(data.account_id, data.batch_id)
         .await?
@@ -98,19 +118,28 @@ pub async fn execute(
                 .find_from_ref(data.account_id, xpub_fingerprint)
                 .await?
         };
+        // Batches created before global xpubs were added to the psbt can't carry key origins
+        if !session.unsigned_psbt.xpub.is_empty() {
+            if let Err(err) = psbt_validator::validate_key_origins(&session.unsigned_psbt) {
+                attempt_failed(session, &err);
+                stalled = true;
+                tracing::error!("{}", err.to_string());
+                continue;
+            }
+        }
         let mut client = match account_xpub
             .remote_signing_client(signer_encryption_config.key)
             .await
         {
             Ok(Some(client)) => client,
             Ok(None) => {
-                session.attempt_failed(SigningFailureReason::SignerConfigMissing);
+                attempt_failed(session, SigningFailureReason::SignerConfigMissing);
                 stalled = true;
                 tracing::warn!("signer_config_missing");
                 continue;
             }
             Err(err) => {
-                session.attempt_failed(&err);
+                attempt_failed(session, &err);
                 tracing::error!("{}", err.to_string());
                 last_err = Some(err);
                 continue;
@@ -118,10 +147,20 @@ pub async fn execute(
         };
         match client.sign_psbt(&session.unsigned_psbt).await {
             Ok(psbt) => {
+                if let Err(err) = psbt_validator::validate_signed_psbt_matches_batch(
+                    &psbt,
+                    &session.unsigned_psbt,
+                    expected_fee,
+                ) {
+                    attempt_failed(session, &err);
+                    stalled = true;
+                    tracing::error!("{}", err.to_string());
+                    continue;
+                }
                 session.remote_signing_complete(psbt);
             }
             Err(err) => {
-                session.attempt_failed(&err);
+                attempt_failed(session, &err);
                 tracing::error!("{}", err.to_string());
                 last_err = Some(err);
                 continue;
@@ -186,3 +225,9 @@ pub async fn execute(
         Ok((data, false))
     }
 }
+
+fn attempt_failed(session: &mut SigningSession, reason: impl Into<SigningFailureReason>) {
+    let reason = reason.into();
+    crate::metrics::record_signing_session_failure(&reason);
+    session.attempt_failed(reason);
+}
@@ -35,6 +35,7 @@ pub async fn execute(
         bitcoin_tx_id,
         payout_queue_id,
         mut wallet_summaries,
+        replaces_batch_id,
         ..
     } = batches.find_by_id(data.account_id, data.batch_id).await?;
 
@@ -42,6 +43,30 @@ pub async fn execute(
         .remove(&data.wallet_id)
         .expect("wallet summary not found");
     let wallet = wallets.find_by_id(data.wallet_id).await?;
+
+    if let Some(replaced_batch_id) = replaces_batch_id {
+        if let Some((mut tx, replaced, tx_id)) = batches
+            .set_batch_replaced_ledger_tx_id(replaced_batch_id, data.wallet_id)
+            .await?
+        {
+            if let Some(broadcast_ledger_tx_id) = replaced.broadcast_ledger_tx_id {
+                bria_utxos
+                    .revert_spend(&mut tx, broadcast_ledger_tx_id)
+                    .await?;
+            }
+            ledger
+                .batch_replaced(
+                    tx,
+                    tx_id,
+                    replaced.created_ledger_tx_id,
+                    replaced.broadcast_ledger_tx_id,
+                    id,
+                    wallet.ledger_account_ids,
+                )
+                .await?;
+        }
+    }
+
     let (income_ids, settled_sats) = bria_utxos
         .accounting_info_for_batch(data.batch_id, data.wallet_id)
         .await?;
@@ -52,7 +77,8 @@ pub async fn execute(
         .list_for_batch(data.account_id, data.batch_id)
         .await?
         .remove(&data.wallet_id)
-        .expect("payouts not found");
+        // Wallets that only consolidate utxos in the batch have no payouts
+        .unwrap_or_default();
     if let Some((tx, tx_id)) = batches
         .set_batch_created_ledger_tx_id(data.batch_id, data.wallet_id)
         .await?
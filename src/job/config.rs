@@ -13,8 +13,13 @@ pub struct JobsConfig {
     #[serde_as(as = "serde_with::DurationSeconds<u64>")]
     #[serde(default = "default_respawn_all_outbox_handlers_delay")]
     pub respawn_all_outbox_handlers_delay: Duration,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_dispatch_webhooks_delay")]
+    pub dispatch_webhooks_delay: Duration,
     #[serde(default)]
     pub signing: SigningJobConfig,
+    #[serde(default)]
+    pub webhook_delivery: WebhookDeliveryJobConfig,
 }
 
 #[serde_with::serde_as]
@@ -29,13 +34,34 @@ pub struct SigningJobConfig {
     pub max_retry_delay: Duration,
 }
 
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryJobConfig {
+    #[serde(default = "default_webhook_delivery_warn_retries")]
+    pub warn_retries: u32,
+    #[serde(default = "default_webhook_delivery_max_attempts")]
+    pub max_attempts: u32,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_webhook_delivery_max_retry_delay")]
+    pub max_retry_delay: Duration,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_webhook_delivery_timeout")]
+    pub timeout: Duration,
+    /// Hosts that webhook endpoints may use even though they resolve to
+    /// loopback, private or link local addresses.
+    #[serde(default)]
+    pub allowed_private_hosts: Vec<String>,
+}
+
 impl Default for JobsConfig {
     fn default() -> Self {
         Self {
             sync_all_wallets_delay: default_sync_all_wallets_delay(),
             process_all_payout_queues_delay: default_process_all_payout_queues_delay(),
             respawn_all_outbox_handlers_delay: default_respawn_all_outbox_handlers_delay(),
+            dispatch_webhooks_delay: default_dispatch_webhooks_delay(),
             signing: SigningJobConfig::default(),
+            webhook_delivery: WebhookDeliveryJobConfig::default(),
         }
     }
 }
@@ -50,6 +76,18 @@ impl Default for SigningJobConfig {
     }
 }
 
+impl Default for WebhookDeliveryJobConfig {
+    fn default() -> Self {
+        Self {
+            warn_retries: default_webhook_delivery_warn_retries(),
+            max_attempts: default_webhook_delivery_max_attempts(),
+            max_retry_delay: default_webhook_delivery_max_retry_delay(),
+            timeout: default_webhook_delivery_timeout(),
+            allowed_private_hosts: Vec::new(),
+        }
+    }
+}
+
 fn default_sync_all_wallets_delay() -> Duration {
     Duration::from_secs(5)
 }
@@ -73,3 +111,23 @@ fn default_signing_max_attempts() -> u32 {
 fn default_signing_max_retry_delay() -> Duration {
     Duration::from_secs(300)
 }
+
+fn default_dispatch_webhooks_delay() -> Duration {
+    Duration::from_secs(2)
+}
+
+fn default_webhook_delivery_warn_retries() -> u32 {
+    6 // About 1 minute
+}
+
+fn default_webhook_delivery_max_attempts() -> u32 {
+    15 // About 4 hours
+}
+
+fn default_webhook_delivery_max_retry_delay() -> Duration {
+    Duration::from_secs(3600)
+}
+
+fn default_webhook_delivery_timeout() -> Duration {
+    Duration::from_secs(10)
+}
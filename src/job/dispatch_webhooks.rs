@@ -0,0 +1,49 @@
+use tracing::instrument;
+
+use super::{error::JobError, webhook_delivery::WebhookDeliveryData};
+use crate::{outbox::*, webhook::*};
+
+const DISPATCH_BATCH_SIZE: usize = 100;
+
+#[instrument(
+    name = "job.dispatch_webhooks",
+    skip_all,
+    fields(webhook_endpoint_id = %endpoint.id, n_enqueued),
+    err
+)]
+#[allow(clippy::type_complexity)]
+pub async fn execute(
+    pool: &sqlx::PgPool,
+    outbox: &Outbox,
+    webhook_deliveries: &WebhookDeliveries,
+    endpoint: &WebhookEndpoint,
+) -> Result<
+    Option<(
+        sqlx::Transaction<'static, sqlx::Postgres>,
+        Vec<WebhookDeliveryData>,
+    )>,
+    JobError,
+> {
+    let mut tx = pool.begin().await?;
+    let cursor = match webhook_deliveries.lock_cursor(&mut tx, endpoint.id).await? {
+        Some(cursor) => cursor,
+        None => return Ok(None),
+    };
+    let events = outbox
+        .load_events_after(endpoint.account_id, cursor, DISPATCH_BATCH_SIZE)
+        .await?;
+    if events.is_empty() {
+        return Ok(None);
+    }
+    let sequences = webhook_deliveries
+        .enqueue(&mut tx, endpoint.id, &events)
+        .await?;
+    tracing::Span::current().record("n_enqueued", sequences.len());
+    Ok(Some((
+        tx,
+        sequences
+            .into_iter()
+            .map(|sequence| WebhookDeliveryData::from((endpoint, sequence)))
+            .collect(),
+    )))
+}
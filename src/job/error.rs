@@ -16,6 +16,7 @@ use crate::{
     signing_session::error::SigningSessionError,
     utxo::error::UtxoError,
     wallet::error::WalletError,
+    webhook::error::WebhookError,
     xpub::{error::XPubError, SigningClientError},
 };
 
@@ -52,6 +53,8 @@ pub enum JobError {
     OutboxError(#[from] OutboxError),
     #[error("{0}")]
     SigningClientError(#[from] SigningClientError),
+    #[error("{0}")]
+    WebhookError(#[from] WebhookError),
     #[error("JobError - Sqlx: {0}")]
     Sqlx(#[from] sqlx::Error),
     #[error("JobError - PsbtMissingInSigningSessions")]
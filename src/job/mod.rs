@@ -2,8 +2,10 @@ mod batch_broadcasting;
 mod batch_signing;
 mod batch_wallet_accounting;
 mod config;
+mod dispatch_webhooks;
 mod executor;
 mod sync_wallet;
+mod webhook_delivery;
 
 pub mod error;
 pub mod process_payout_queue;
@@ -17,7 +19,7 @@ use uuid::{uuid, Uuid};
 use crate::{
     address::Addresses, app::BlockchainConfig, batch::*, fees::FeesClient, ledger::Ledger,
     outbox::*, payout::*, payout_queue::*, primitives::*, signing_session::*, utxo::Utxos,
-    wallet::*, xpub::*,
+    wallet::*, webhook::*, xpub::*,
 };
 use batch_broadcasting::BatchBroadcastingData;
 use batch_signing::BatchSigningData;
@@ -27,9 +29,11 @@ pub use executor::JobExecutionError;
 use executor::JobExecutor;
 use process_payout_queue::ProcessPayoutQueueData;
 use sync_wallet::SyncWalletData;
+use webhook_delivery::WebhookDeliveryData;
 
 const SYNC_ALL_WALLETS_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000001");
 const PROCESS_ALL_PAYOUT_QUEUES_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000002");
+const DISPATCH_WEBHOOKS_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000003");
 
 #[allow(clippy::too_many_arguments)]
 pub async fn start_job_runner(
@@ -44,6 +48,8 @@ pub async fn start_job_runner(
     ledger: Ledger,
     utxos: Utxos,
     addresses: Addresses,
+    webhook_endpoints: WebhookEndpoints,
+    webhook_deliveries: WebhookDeliveries,
     config: JobsConfig,
     blockchain_cfg: BlockchainConfig,
     signer_encryption_config: SignerEncryptionConfig,
@@ -58,7 +64,13 @@ pub async fn start_job_runner(
         batch_wallet_accounting,
         batch_signing,
         batch_broadcasting,
+        dispatch_webhooks,
+        webhook_delivery,
     ]);
+    registry.set_context(WebhookClient::new(
+        config.webhook_delivery.timeout,
+        config.webhook_delivery.allowed_private_hosts.clone(),
+    ));
     registry.set_context(config);
     registry.set_context(blockchain_cfg);
     registry.set_context(outbox);
@@ -73,6 +85,8 @@ pub async fn start_job_runner(
     registry.set_context(addresses);
     registry.set_context(signer_encryption_config);
     registry.set_context(fees_client);
+    registry.set_context(webhook_endpoints);
+    registry.set_context(webhook_deliveries);
 
     Ok(registry.runner(pool).set_keep_alive(false).run().await?)
 }
@@ -134,6 +148,75 @@ async fn process_all_payout_queues(
     Ok(())
 }
 
+#[job(name = "dispatch_webhooks")]
+async fn dispatch_webhooks(
+    mut current_job: CurrentJob,
+    outbox: Outbox,
+    webhook_endpoints: WebhookEndpoints,
+    webhook_deliveries: WebhookDeliveries,
+    JobsConfig {
+        dispatch_webhooks_delay: delay,
+        ..
+    }: JobsConfig,
+) -> Result<(), JobError> {
+    let pool = current_job.pool().clone();
+    JobExecutor::builder(&mut current_job)
+        .build()
+        .expect("couldn't build JobExecutor")
+        .execute(|_| async move {
+            for endpoint in webhook_endpoints.list_active().await? {
+                if let Some((mut tx, deliveries)) =
+                    dispatch_webhooks::execute(&pool, &outbox, &webhook_deliveries, &endpoint)
+                        .await?
+                {
+                    for data in deliveries {
+                        spawn_webhook_delivery(&mut tx, data).await?;
+                    }
+                    tx.commit().await?;
+                }
+            }
+            Ok::<(), JobError>(())
+        })
+        .await?;
+    spawn_dispatch_webhooks(current_job.pool(), delay).await?;
+    Ok(())
+}
+
+#[job(name = "webhook_delivery", channel_name = "webhook_delivery")]
+async fn webhook_delivery(
+    mut current_job: CurrentJob,
+    JobsConfig {
+        webhook_delivery: config,
+        ..
+    }: JobsConfig,
+    webhook_endpoints: WebhookEndpoints,
+    webhook_deliveries: WebhookDeliveries,
+    webhook_client: WebhookClient,
+    signer_encryption_config: SignerEncryptionConfig,
+) -> Result<(), JobError> {
+    let max_attempts = config.max_attempts;
+    JobExecutor::builder(&mut current_job)
+        .warn_retries(config.warn_retries)
+        .max_attempts(max_attempts)
+        .max_retry_delay(config.max_retry_delay)
+        .build()
+        .expect("couldn't build JobExecutor")
+        .execute(|data| async move {
+            let data: WebhookDeliveryData = data.expect("no WebhookDeliveryData available");
+            webhook_delivery::execute(
+                data,
+                max_attempts,
+                webhook_endpoints,
+                webhook_deliveries,
+                webhook_client,
+                signer_encryption_config,
+            )
+            .await
+        })
+        .await?;
+    Ok(())
+}
+
 #[job(name = "sync_wallet")]
 #[allow(clippy::too_many_arguments)]
 async fn sync_wallet(
@@ -147,6 +230,7 @@ async fn sync_wallet(
     fees_client: FeesClient,
 ) -> Result<(), JobError> {
     let pool = current_job.pool().clone();
+    let sync_times = wallets.clone();
     let mut has_more = false;
     let more_ref = &mut has_more;
     let data = JobExecutor::builder(&mut current_job)
@@ -167,6 +251,7 @@ async fn sync_wallet(
                 fees_client,
             )
             .await?;
+            sync_times.record_synced(data.wallet_id).await?;
             *more_ref = more;
             Ok::<_, JobError>(data)
         })
@@ -208,6 +293,7 @@ async fn schedule_process_payout_queue(mut current_job: CurrentJob) -> Result<()
 }
 
 #[job(name = "process_payout_queue")]
+#[allow(clippy::too_many_arguments)]
 async fn process_payout_queue(
     mut current_job: CurrentJob,
     payouts: Payouts,
@@ -216,6 +302,8 @@ async fn process_payout_queue(
     payout_queues: PayoutQueues,
     batches: Batches,
     fees_client: FeesClient,
+    outbox: Outbox,
+    ledger: Ledger,
 ) -> Result<(), JobError> {
     let pool = current_job.pool().clone();
     JobExecutor::builder(&mut current_job)
@@ -233,6 +321,8 @@ async fn process_payout_queue(
                 utxos,
                 data,
                 fees_client,
+                outbox,
+                ledger,
             )
             .await?;
             if let Some((mut tx, wallet_ids)) = res {
@@ -475,6 +565,42 @@ async fn spawn_batch_signing(
     }
 }
 
+/// Pulls a signing attempt that is waiting out its retry delay forward,
+/// spawning a new one only if none is queued for the batch.
+#[instrument(name = "job.retry_batch_signing", skip_all, fields(error, error.level, error.message), err)]
+pub async fn retry_batch_signing(
+    mut tx: sqlx::Transaction<'_, sqlx::Postgres>,
+    account_id: AccountId,
+    batch_id: BatchId,
+) -> Result<(), JobError> {
+    let queued = sqlx::query!(
+        r#"UPDATE mq_msgs SET attempt_at = LEAST(attempt_at, NOW())
+           WHERE channel_name = 'batch_signing' AND channel_args = $1 AND attempt_at IS NOT NULL"#,
+        format!("batch_id:{}", batch_id)
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if queued > 0 {
+        tx.commit().await?;
+        return Ok(());
+    }
+    spawn_batch_signing(tx, (account_id, batch_id)).await
+}
+
+#[instrument(name = "job.spawn_replacement_batch_jobs", skip_all, fields(error, error.level, error.message), err)]
+pub async fn spawn_replacement_batch_jobs(
+    mut tx: sqlx::Transaction<'_, sqlx::Postgres>,
+    account_id: AccountId,
+    batch_id: BatchId,
+    wallet_ids: impl Iterator<Item = WalletId>,
+) -> Result<(), JobError> {
+    for wallet_id in wallet_ids {
+        spawn_batch_wallet_accounting(&mut tx, (account_id, batch_id, wallet_id)).await?;
+    }
+    spawn_batch_signing(tx, (account_id, batch_id)).await
+}
+
 #[instrument(name = "job.spawn_all_batch_signings", skip_all, fields(error, error.level, error.message), err)]
 pub async fn spawn_all_batch_signings(
     mut tx: sqlx::Transaction<'_, sqlx::Postgres>,
@@ -520,6 +646,48 @@ async fn spawn_batch_broadcasting(
     }
 }
 
+#[instrument(name = "job.spawn_dispatch_webhooks", skip_all, fields(error, error.level, error.message), err)]
+pub async fn spawn_dispatch_webhooks(
+    pool: &sqlx::PgPool,
+    delay: std::time::Duration,
+) -> Result<(), JobError> {
+    match JobBuilder::new_with_id(DISPATCH_WEBHOOKS_ID, "dispatch_webhooks")
+        .set_channel_name("dispatch_webhooks")
+        .set_delay(delay)
+        .spawn(pool)
+        .await
+    {
+        Err(sqlx::Error::Database(err)) if err.message().contains("duplicate key") => Ok(()),
+        Err(e) => {
+            crate::tracing::insert_error_fields(tracing::Level::ERROR, &e);
+            Err(e.into())
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
+#[instrument(name = "job.spawn_webhook_delivery", skip_all, fields(error, error.level, error.message), err)]
+pub async fn spawn_webhook_delivery(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    data: impl Into<WebhookDeliveryData>,
+) -> Result<(), JobError> {
+    let data = data.into();
+    match webhook_delivery
+        .builder()
+        .set_json(&data)
+        .expect("Couldn't set json")
+        .set_channel_args(&format!("webhook_endpoint_id:{}", data.webhook_endpoint_id))
+        .spawn(&mut **tx)
+        .await
+    {
+        Err(e) => {
+            crate::tracing::insert_error_fields(tracing::Level::ERROR, &e);
+            Err(e.into())
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
 fn schedule_payout_queue_channel_arg(payout_queue_id: PayoutQueueId) -> String {
     format!("payout_queue_id:{payout_queue_id}")
 }
@@ -585,6 +753,17 @@ impl From<(&ProcessPayoutQueueData, WalletId)> for BatchWalletAccountingData {
     }
 }
 
+impl From<(AccountId, BatchId, WalletId)> for BatchWalletAccountingData {
+    fn from((account_id, batch_id, wallet_id): (AccountId, BatchId, WalletId)) -> Self {
+        Self {
+            tracing_data: crate::tracing::extract_tracing_data(),
+            account_id,
+            batch_id,
+            wallet_id,
+        }
+    }
+}
+
 impl From<&ProcessPayoutQueueData> for BatchSigningData {
     fn from(data: &ProcessPayoutQueueData) -> Self {
         Self {
@@ -623,3 +802,27 @@ impl From<BatchSigningData> for BatchBroadcastingData {
         }
     }
 }
+
+impl From<(&WebhookEndpoint, EventSequence)> for WebhookDeliveryData {
+    fn from((endpoint, sequence): (&WebhookEndpoint, EventSequence)) -> Self {
+        Self {
+            account_id: endpoint.account_id,
+            webhook_endpoint_id: endpoint.id,
+            sequence,
+            tracing_data: crate::tracing::extract_tracing_data(),
+        }
+    }
+}
+
+impl From<(AccountId, WebhookEndpointId, EventSequence)> for WebhookDeliveryData {
+    fn from(
+        (account_id, webhook_endpoint_id, sequence): (AccountId, WebhookEndpointId, EventSequence),
+    ) -> Self {
+        Self {
+            account_id,
+            webhook_endpoint_id,
+            sequence,
+            tracing_data: crate::tracing::extract_tracing_data(),
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::instrument;
 
 use super::error::JobError;
 use crate::{
-    batch::*, fees::FeesClient, payout::*, payout_queue::*, primitives::*, utxo::*, wallet::*,
+    batch::*, fees::FeesClient, ledger::Ledger, outbox::*, payout::*, payout_queue::*,
+    primitives::*, utxo::*, wallet::*,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +24,10 @@ pub struct ProcessPayoutQueueData {
         n_unbatched_payouts,
         payout_queue_name,
         n_reserved_utxos,
+        n_pinned_utxos,
+        n_deferred_payouts,
+        n_cancelled_payouts,
+        n_consolidated_utxos,
         n_cpfp_utxos,
         tx_id,
         total_fee_sats,
@@ -30,7 +35,8 @@ pub struct ProcessPayoutQueueData {
         total_change_sats,
         psbt,
         batch_id,
-        payout_queue_id
+        payout_queue_id,
+        deferred = false
     ),
     err
 )]
@@ -44,6 +50,8 @@ pub(super) async fn execute<'a>(
     utxos: Utxos,
     data: ProcessPayoutQueueData,
     fees_client: FeesClient,
+    outbox: Outbox,
+    ledger: Ledger,
 ) -> Result<
     (
         ProcessPayoutQueueData,
@@ -51,16 +59,58 @@ pub(super) async fn execute<'a>(
     ),
     JobError,
 > {
-    let payout_queue = payout_queues
+    let mut payout_queue = payout_queues
         .find_by_account_id_and_id(data.account_id, data.payout_queue_id)
         .await?;
+    cancel_payouts_with_spent_pins(&pool, &payouts, &wallets, &utxos, &ledger, &data).await?;
     let mut tx = pool.begin().await?;
     let mut unbatched_payouts = payouts
         .list_unbatched(&mut tx, data.account_id, data.payout_queue_id)
         .await?;
-    let fee_rate = fees_client
-        .fee_rate(payout_queue.config.tx_priority)
-        .await?;
+    // An operator override takes precedence over the queue's fee rate bounds
+    let fee_rate = match fees_client.active_override().await? {
+        Some(fee_rate_override) => fee_rate_override.fee_rate,
+        None => {
+            let fee_rate = fees_client
+                .fee_rate(payout_queue.config.tx_priority)
+                .await?;
+            if payout_queue.config.exceeds_max_fee_rate(fee_rate) {
+                tracing::Span::current().record("deferred", true);
+                let n_unbatched_payouts = unbatched_payouts.n_payouts();
+                // Only announce the deferral once - not on every run while the fee rate stays high
+                if n_unbatched_payouts > 0 && payout_queue.defer_batching(fee_rate) {
+                    payout_queues
+                        .update_in_op(&mut tx, &mut payout_queue)
+                        .await?;
+                    outbox
+                        .publish_in_op(
+                            &mut tx,
+                            data.account_id,
+                            OutboxEventPayload::PayoutQueueBatchDeferred {
+                                payout_queue_id: data.payout_queue_id,
+                                fee_rate_sat_per_vb: fee_rate.as_sat_per_vb(),
+                                max_fee_rate: payout_queue
+                                    .config
+                                    .max_fee_rate
+                                    .expect("max_fee_rate is set"),
+                                n_unbatched_payouts,
+                                deferred_at: chrono::Utc::now(),
+                            },
+                        )
+                        .await?;
+                    tx.commit().await?;
+                }
+                return Ok((data, None));
+            }
+            payout_queue.config.apply_min_fee_rate(fee_rate)
+        }
+    };
+    let resumed = payout_queue.resume_batching();
+    if resumed {
+        payout_queues
+            .update_in_op(&mut tx, &mut payout_queue)
+            .await?;
+    }
     let FinishedPsbtBuild {
         psbt,
         included_payouts,
@@ -165,10 +215,70 @@ pub(super) async fn execute<'a>(
         if unbatched_payouts.n_not_batched() > 0 {
             queue_drain_error(unbatched_payouts.n_not_batched());
         }
+        if resumed {
+            tx.commit().await?;
+        }
         Ok((data, None))
     }
 }
 
+/// A payout pinned to an outpoint that has been spent elsewhere can never be
+/// batched as requested so it gets cancelled on behalf of its submitter.
+#[allow(clippy::result_large_err)]
+async fn cancel_payouts_with_spent_pins(
+    pool: &sqlx::PgPool,
+    payouts: &Payouts,
+    wallets: &Wallets,
+    utxos: &Utxos,
+    ledger: &Ledger,
+    data: &ProcessPayoutQueueData,
+) -> Result<(), JobError> {
+    tracing::Span::current().record("n_cancelled_payouts", 0);
+    let mut tx = pool.begin().await?;
+    let queue_pins: Vec<_> = payouts
+        .list_unbatched_pins(&mut tx, data.account_id)
+        .await?
+        .into_iter()
+        .filter(|pins| pins.payout_queue_id == data.payout_queue_id)
+        .collect();
+    tx.commit().await?;
+    if queue_pins.is_empty() {
+        return Ok(());
+    }
+
+    let wallet_ids: Vec<_> = queue_pins.iter().map(|pins| pins.wallet_id).collect();
+    let wallets: HashMap<WalletId, Wallet> = wallets.find_all(&wallet_ids).await?;
+    let mut n_cancelled_payouts = 0;
+    for pins in queue_pins {
+        let keychain_ids = match wallets.get(&pins.wallet_id) {
+            Some(wallet) => wallet.keychain_ids(),
+            None => continue,
+        };
+        if utxos
+            .spent_outpoints(keychain_ids, &pins.outpoints)
+            .await?
+            .is_empty()
+        {
+            continue;
+        }
+        let mut op = payouts.begin_op().await?;
+        let mut payout = payouts
+            .find_by_id_for_update(&mut op, data.account_id, pins.payout_id)
+            .await?;
+        // The payout may have been batched or cancelled in the meantime
+        if payout.cancel_payout(payout.profile_id).is_err() {
+            continue;
+        }
+        payouts.update_in_op(&mut op, &mut payout).await?;
+        ledger
+            .payout_cancelled(op.into(), LedgerTransactionId::new(), pins.payout_id)
+            .await?;
+        n_cancelled_payouts += 1;
+    }
+    tracing::Span::current().record("n_cancelled_payouts", n_cancelled_payouts);
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn construct_psbt(
     pool: &sqlx::Pool<sqlx::Postgres>,
@@ -191,24 +301,115 @@ pub async fn construct_psbt(
     span.record("payout_queue_id", tracing::field::display(queue_id));
     span.record("n_unbatched_payouts", unbatched_payouts.n_payouts());
 
-    let wallets = wallets.find_all(&unbatched_payouts.wallet_ids()).await?;
-    let reserved_utxos = {
+    let consolidation = if for_estimation {
+        None
+    } else {
+        queue_cfg.consolidation_at(fee_rate).cloned()
+    };
+    let mut wallet_ids = unbatched_payouts.wallet_ids();
+    if let Some(consolidation) = consolidation.as_ref() {
+        for wallet_id in consolidation.wallet_ids.iter() {
+            if !wallet_ids.contains(wallet_id) {
+                wallet_ids.push(*wallet_id);
+            }
+        }
+    }
+    let wallets = wallets.find_all(&wallet_ids).await?;
+    let (mut pinned_utxos, unspendable_pins) = {
         let keychain_ids = wallets.values().flat_map(|w: &Wallet| w.keychain_ids());
         utxos
-            .outpoints_bdk_should_not_select(tx, keychain_ids)
+            .pinned_outpoints(tx, keychain_ids, unbatched_payouts.spend_outpoints())
             .await?
     };
+    // Payouts pinned to an outpoint that can't be spent right now stay
+    // unbatched until it can be
+    let (deferred_payouts, deferred_pins) = unbatched_payouts.pinning_any_of(&unspendable_pins);
+    span.record("n_deferred_payouts", deferred_payouts.len());
+    pinned_utxos.retain(|_, outpoints| {
+        outpoints.retain(|outpoint| !deferred_pins.contains(outpoint));
+        !outpoints.is_empty()
+    });
+    span.record(
+        "n_pinned_utxos",
+        pinned_utxos.values().fold(0, |acc, v| acc + v.len()),
+    );
+    // Outpoints pinned by payouts that are not part of this build are kept for them
+    let pinned_by_payouts: HashSet<_> = unbatched_payouts
+        .pinned_by_other_queues()
+        .iter()
+        .chain(deferred_pins.iter())
+        .copied()
+        .collect();
+    let mut reserved_utxos = {
+        let keychain_ids = wallets.values().flat_map(|w: &Wallet| w.keychain_ids());
+        utxos
+            .outpoints_bdk_should_not_select(tx, keychain_ids, &pinned_by_payouts)
+            .await?
+    };
+    for (keychain_id, pinned) in pinned_utxos.iter() {
+        if let Some(reserved) = reserved_utxos.get_mut(keychain_id) {
+            reserved.retain(|out| !pinned.contains(out));
+        }
+    }
     span.record(
         "n_reserved_utxos",
         reserved_utxos.values().fold(0, |acc, v| acc + v.len()),
     );
 
+    let mut tx_payouts = unbatched_payouts.into_tx_payouts();
+    tx_payouts.retain(|_, payouts| {
+        payouts.retain(|(id, _, _)| !deferred_payouts.contains(&PayoutId::from(*id)));
+        !payouts.is_empty()
+    });
+    span.record("n_consolidated_utxos", 0);
+    if let Some(consolidation) = consolidation {
+        let mut n_consolidated_utxos = 0;
+        for wallet_id in consolidation.wallet_ids {
+            let wallet = match wallets.get(&wallet_id) {
+                Some(wallet) => wallet,
+                None => continue,
+            };
+            let keychain_id = wallet.current_keychain_id();
+            // Inputs worth less than the fee to spend them would only shrink the balance
+            let min_value = crate::fees::fee_to_spend_input(
+                fee_rate,
+                wallet
+                    .current_keychain_wallet(pool)
+                    .max_satisfaction_weight(),
+            );
+            let outpoints = utxos
+                .find_consolidation_utxos(
+                    tx,
+                    keychain_id,
+                    consolidation.max_utxos,
+                    consolidation.max_utxo_sats,
+                    min_value,
+                )
+                .await?;
+            // Spending a single utxo into a new one doesn't consolidate anything
+            if outpoints.len() < 2 {
+                continue;
+            }
+            let pinned = pinned_utxos.entry(keychain_id).or_default();
+            for outpoint in outpoints {
+                if !pinned.contains(&outpoint) && !pinned_by_payouts.contains(&outpoint) {
+                    pinned.push(outpoint);
+                    n_consolidated_utxos += 1;
+                }
+            }
+            tx_payouts.entry(wallet_id).or_default();
+        }
+        span.record("n_consolidated_utxos", n_consolidated_utxos);
+    }
+
     span.record("n_cpfp_utxos", 0);
 
     let mut cfg = PsbtBuilderConfig::builder()
         .consolidate_deprecated_keychains(queue_cfg.consolidate_deprecated_keychains)
         .fee_rate(fee_rate)
         .reserved_utxos(reserved_utxos)
+        .pinned_utxos(pinned_utxos)
+        .payout_pins(unbatched_payouts.spend_outpoints_by_payout())
         .force_min_change_output(queue_cfg.force_min_change_sats);
     if !for_estimation && queue_cfg.should_cpfp() {
         let keychain_ids = wallets.values().flat_map(|w| w.keychain_ids());
@@ -229,8 +430,6 @@ pub async fn construct_psbt(
         cfg = cfg.cpfp_utxos(utxos);
     }
 
-    let tx_payouts = unbatched_payouts.into_tx_payouts();
-
     Ok(PsbtBuilder::construct_psbt(
         pool,
         cfg.for_estimation(for_estimation)
@@ -242,6 +441,62 @@ pub async fn construct_psbt(
     .await?)
 }
 
+#[instrument(
+    name = "job.construct_replacement_psbt",
+    skip_all,
+    fields(n_reserved_utxos, n_pinned_utxos, payout_queue_id),
+    err
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn construct_replacement_psbt(
+    pool: &sqlx::Pool<sqlx::Postgres>,
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    tx_payouts: HashMap<WalletId, Vec<TxPayout>>,
+    pinned_utxos: HashMap<KeychainId, Vec<bitcoin::OutPoint>>,
+    pinned_by_payouts: HashSet<bitcoin::OutPoint>,
+    utxos: &Utxos,
+    wallets: &Wallets,
+    payout_queue: PayoutQueue,
+    fee_rate: bitcoin::FeeRate,
+) -> Result<FinishedPsbtBuild, JobError> {
+    let span = tracing::Span::current();
+    span.record("payout_queue_id", tracing::field::display(payout_queue.id));
+
+    let wallet_ids: Vec<WalletId> = tx_payouts.keys().copied().collect();
+    let wallets = wallets.find_all(&wallet_ids).await?;
+    let mut reserved_utxos = {
+        let keychain_ids = wallets.values().flat_map(|w: &Wallet| w.keychain_ids());
+        utxos
+            .outpoints_bdk_should_not_select(tx, keychain_ids, &pinned_by_payouts)
+            .await?
+    };
+    // The inputs of the replaced batch are reserved by it - but they must be spent again
+    for (keychain_id, pinned) in pinned_utxos.iter() {
+        if let Some(reserved) = reserved_utxos.get_mut(keychain_id) {
+            reserved.retain(|out| !pinned.contains(out));
+        }
+    }
+    span.record(
+        "n_reserved_utxos",
+        reserved_utxos.values().fold(0, |acc, v| acc + v.len()),
+    );
+    span.record(
+        "n_pinned_utxos",
+        pinned_utxos.values().fold(0, |acc, v| acc + v.len()),
+    );
+
+    let cfg = PsbtBuilderConfig::builder()
+        .consolidate_deprecated_keychains(payout_queue.config.consolidate_deprecated_keychains)
+        .fee_rate(fee_rate)
+        .reserved_utxos(reserved_utxos)
+        .pinned_utxos(pinned_utxos)
+        .force_min_change_output(payout_queue.config.force_min_change_sats)
+        .build()
+        .expect("Couldn't build PsbtBuilderConfig");
+
+    Ok(PsbtBuilder::construct_psbt(pool, cfg, tx_payouts, wallets).await?)
+}
+
 #[instrument(name = "job.queue_drain_error", fields(error = true, error.level, error.message))]
 fn queue_drain_error(n_not_batched: usize) {
     let span = tracing::Span::current();
@@ -291,6 +546,7 @@ impl From<WalletTotals> for WalletSummary {
             current_keychain_id: wt.change_keychain_id,
             batch_created_ledger_tx_id: None,
             batch_broadcast_ledger_tx_id: None,
+            batch_replaced_ledger_tx_id: None,
         }
     }
 }
@@ -1,5 +1,3 @@
-use bdk::blockchain::{ElectrumBlockchain, GetHeight};
-use electrum_client::{Client, ConfigBuilder};
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 
@@ -9,7 +7,7 @@ use crate::{
     app::BlockchainConfig,
     batch::*,
     bdk::{
-        error::BdkError,
+        blockchain::init_backend,
         pg::{ConfirmedIncomeUtxo, ConfirmedSpendTransaction, Transactions, Utxos as BdkUtxos},
     },
     fees::{self, FeesClient},
@@ -102,10 +100,13 @@ pub async fn execute(
         let keychain_id = keychain_wallet.keychain_id;
         utxos_to_fetch.clear();
         utxos_to_fetch.insert(keychain_id, Vec::<bitcoin::OutPoint>::new());
-        let (blockchain, current_height) = init_electrum(&deps.blockchain_cfg.electrum_url).await?;
+        let blockchain = init_backend(&deps.blockchain_cfg)?;
+        let current_height = blockchain.get_height()?;
         span.record("current_height", current_height);
         let latest_change_settle_height = wallet.config.latest_change_settle_height(current_height);
-        keychain_wallet.sync(blockchain).await?;
+        keychain_wallet
+            .sync(blockchain, wallet.created_at())
+            .await?;
         let bdk_txs = Transactions::new(keychain_id, pool.clone());
         let bdk_utxos = BdkUtxos::new(keychain_id, pool.clone());
         let mut txs_to_skip = Vec::new();
@@ -548,15 +549,6 @@ pub async fn execute(
     Ok((has_more, data))
 }
 
-async fn init_electrum(electrum_url: &str) -> Result<(ElectrumBlockchain, u32), BdkError> {
-    let blockchain = ElectrumBlockchain::from(Client::from_config(
-        electrum_url,
-        ConfigBuilder::new().retry(10).timeout(Some(60)).build(),
-    )?);
-    let current_height = blockchain.get_height()?;
-    Ok((blockchain, current_height))
-}
-
 fn address_metadata(tx_id: &bitcoin::Txid) -> serde_json::Value {
     serde_json::json! {
         {
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use std::collections::HashMap;
+
+use super::error::JobError;
+use crate::{outbox::EventSequence, primitives::*, webhook::*, xpub::SignerEncryptionConfig};
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryData {
+    pub(super) account_id: AccountId,
+    pub(super) webhook_endpoint_id: WebhookEndpointId,
+    // Job payload fields end up next to the flattened tracing data so they must be strings
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub(super) sequence: EventSequence,
+    #[serde(flatten)]
+    pub(super) tracing_data: HashMap<String, String>,
+}
+
+#[instrument(
+    name = "job.webhook_delivery",
+    skip_all,
+    fields(webhook_endpoint_id = %data.webhook_endpoint_id, sequence = %data.sequence, attempt, dead_letter = false),
+    err
+)]
+pub async fn execute(
+    data: WebhookDeliveryData,
+    max_attempts: u32,
+    webhook_endpoints: WebhookEndpoints,
+    webhook_deliveries: WebhookDeliveries,
+    webhook_client: WebhookClient,
+    signer_encryption_config: SignerEncryptionConfig,
+) -> Result<WebhookDeliveryData, JobError> {
+    let span = tracing::Span::current();
+    let endpoint = webhook_endpoints
+        .find_by_id(data.webhook_endpoint_id)
+        .await?;
+    if !endpoint.is_active() {
+        span.record("dead_letter", true);
+        webhook_deliveries
+            .record_failure(
+                endpoint.id,
+                data.sequence,
+                "webhook endpoint is not active".to_string(),
+                true,
+            )
+            .await?;
+        return Ok(data);
+    }
+    let delivery = match webhook_deliveries
+        .record_attempt(endpoint.id, data.sequence)
+        .await?
+    {
+        Some(delivery) => delivery,
+        None => return Ok(data),
+    };
+    span.record("attempt", delivery.attempts);
+
+    let result = match endpoint.secret(&signer_encryption_config.key) {
+        Ok(secret) => webhook_client.deliver(&endpoint, &secret, &delivery).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => {
+            webhook_deliveries
+                .mark_delivered(endpoint.id, data.sequence)
+                .await?;
+            Ok(data)
+        }
+        Err(e) => {
+            let dead_letter = delivery.attempts >= max_attempts;
+            webhook_deliveries
+                .record_failure(endpoint.id, data.sequence, e.to_string(), dead_letter)
+                .await?;
+            if dead_letter {
+                span.record("dead_letter", true);
+                return Ok(data);
+            }
+            Err(e.into())
+        }
+    }
+}
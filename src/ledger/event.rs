@@ -26,6 +26,7 @@ pub enum JournalEventMetadata {
     PayoutCancelled(PayoutCancelledMeta),
     BatchCreated(BatchCreatedMeta),
     BatchBroadcast(BatchBroadcastMeta),
+    BatchReplaced(BatchReplacedMeta),
     UnknownTransaction(Option<serde_json::Value>),
 }
 
@@ -82,6 +83,10 @@ impl TryFrom<SqlxLedgerEvent> for MaybeIgnored {
                         tx.metadata::<BatchBroadcastMeta>()?
                             .ok_or(LedgerError::MissingTxMetadata)?,
                     ),
+                    BATCH_REPLACED_ID => JournalEventMetadata::BatchReplaced(
+                        tx.metadata::<BatchReplacedMeta>()?
+                            .ok_or(LedgerError::MissingTxMetadata)?,
+                    ),
                     _ => JournalEventMetadata::UnknownTransaction(tx.metadata_json),
                 },
             ),
@@ -59,6 +59,7 @@ impl Ledger {
             templates::fix::legacy_batch_created(&inner).await?;
         }
         templates::BatchBroadcast::init(&inner).await?;
+        templates::BatchReplaced::init(&inner).await?;
 
         Ok(Self {
             inner,
@@ -362,6 +363,83 @@ impl Ledger {
         Ok(())
     }
 
+    #[instrument(name = "ledger.batch_replaced", skip(self, tx))]
+    pub async fn batch_replaced(
+        &self,
+        tx: Transaction<'_, Postgres>,
+        tx_id: LedgerTransactionId,
+        create_batch_tx_id: LedgerTransactionId,
+        broadcast_batch_tx_id: Option<LedgerTransactionId>,
+        replacement_batch_id: BatchId,
+        ledger_account_ids: WalletLedgerAccountIds,
+    ) -> Result<(), LedgerError> {
+        let txs = self
+            .inner
+            .transactions()
+            .list_by_ids(std::iter::once(create_batch_tx_id))
+            .await?;
+        let txn = txs.first().ok_or(LedgerError::TransactionNotFound)?;
+        let BatchCreatedMeta {
+            batch_info,
+            tx_summary,
+        } = txn.metadata()?.ok_or(LedgerError::MissingTxMetadata)?;
+        let entries = self
+            .inner
+            .entries()
+            .list_by_transaction_ids(std::iter::once(create_batch_tx_id))
+            .await?;
+        let encumbered_fees = entries
+            .into_values()
+            .flatten()
+            .find_map(|entry| match entry.entry_type.as_str() {
+                "BATCH_CREATED_FR_ENC_CR" => Some(Satoshis::from_btc(entry.units)),
+                _ => None,
+            })
+            .ok_or(LedgerError::ExpectedEntryNotFoundInTx(
+                "Encumbered fees entry not found",
+            ))?;
+
+        let encumbered_spending_fees = if let Some(broadcast_batch_tx_id) = broadcast_batch_tx_id {
+            #[derive(serde::Deserialize)]
+            struct ExtractEncumberedFees {
+                encumbered_spending_fees: EncumberedSpendingFees,
+            }
+            let txs = self
+                .inner
+                .transactions()
+                .list_by_ids(std::iter::once(broadcast_batch_tx_id))
+                .await?;
+            let ExtractEncumberedFees {
+                encumbered_spending_fees,
+            } = txs
+                .first()
+                .ok_or(LedgerError::TransactionNotFound)?
+                .metadata()?
+                .ok_or(LedgerError::MissingTxMetadata)?;
+            Some(encumbered_spending_fees)
+        } else {
+            None
+        };
+
+        let params = BatchReplacedParams {
+            journal_id: txn.journal_id,
+            ledger_account_ids,
+            encumbered_fees,
+            meta: BatchReplacedMeta {
+                batch_info,
+                tx_summary,
+                replacement_batch_id,
+                batch_created_tx_id: create_batch_tx_id,
+                batch_broadcast_tx_id: broadcast_batch_tx_id,
+                encumbered_spending_fees,
+            },
+        };
+        self.inner
+            .post_transaction_in_tx(tx, tx_id, BATCH_REPLACED_CODE, Some(params))
+            .await?;
+        Ok(())
+    }
+
     #[instrument(name = "ledger.spend_detected", skip(self, tx))]
     pub async fn spend_detected(
         &self,
@@ -456,6 +534,49 @@ impl Ledger {
     pub async fn get_wallet_ledger_account_balances(
         &self,
         journal_id: JournalId,
+        ids: WalletLedgerAccountIds,
+    ) -> Result<WalletLedgerAccountBalances, LedgerError> {
+        let mut balances = self
+            .inner
+            .balances()
+            .find_all(journal_id, ids.all_ids())
+            .await?;
+        Ok(self.wallet_ledger_account_balances(&mut balances, ids))
+    }
+
+    /// Loads the balances of several wallets in the same journal with a single query.
+    #[instrument(name = "ledger.get_wallets_ledger_account_balances", skip_all)]
+    pub async fn get_wallets_ledger_account_balances(
+        &self,
+        journal_id: JournalId,
+        wallets: impl IntoIterator<Item = (WalletId, WalletLedgerAccountIds)>,
+    ) -> Result<HashMap<WalletId, WalletLedgerAccountBalances>, LedgerError> {
+        let wallets: Vec<_> = wallets.into_iter().collect();
+        let mut balances = self
+            .inner
+            .balances()
+            .find_all(
+                journal_id,
+                wallets.iter().flat_map(|(_, ids)| ids.all_ids()),
+            )
+            .await?;
+        Ok(wallets
+            .into_iter()
+            .map(|(wallet_id, ids)| {
+                (
+                    wallet_id,
+                    self.wallet_ledger_account_balances(&mut balances, ids),
+                )
+            })
+            .collect())
+    }
+
+    fn wallet_ledger_account_balances(
+        &self,
+        balances: &mut HashMap<
+            sqlx_ledger::AccountId,
+            HashMap<Currency, sqlx_ledger::balance::AccountBalance>,
+        >,
         WalletLedgerAccountIds {
             onchain_incoming_id,
             onchain_at_rest_id,
@@ -466,25 +587,8 @@ impl Ledger {
             fee_id,
             dust_id,
         }: WalletLedgerAccountIds,
-    ) -> Result<WalletLedgerAccountBalances, LedgerError> {
-        let mut balances = self
-            .inner
-            .balances()
-            .find_all(
-                journal_id,
-                [
-                    onchain_incoming_id,
-                    onchain_at_rest_id,
-                    onchain_outgoing_id,
-                    effective_incoming_id,
-                    effective_at_rest_id,
-                    effective_outgoing_id,
-                    fee_id,
-                    dust_id,
-                ],
-            )
-            .await?;
-        Ok(WalletLedgerAccountBalances {
+    ) -> WalletLedgerAccountBalances {
+        WalletLedgerAccountBalances {
             onchain_incoming: balances
                 .get_mut(&onchain_incoming_id)
                 .and_then(|b| b.remove(&self.btc)),
@@ -505,7 +609,7 @@ impl Ledger {
                 .and_then(|b| b.remove(&self.btc)),
             fee: balances.get_mut(&fee_id).and_then(|b| b.remove(&self.btc)),
             dust: balances.get_mut(&dust_id).and_then(|b| b.remove(&self.btc)),
-        })
+        }
     }
 
     #[instrument(name = "ledger.get_account_ledger_account_balances", skip(self))]
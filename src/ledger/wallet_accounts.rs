@@ -29,6 +29,19 @@ pub struct WalletLedgerAccountIds {
 }
 
 impl WalletLedgerAccountIds {
+    pub fn all_ids(&self) -> [LedgerAccountId; 8] {
+        [
+            self.onchain_incoming_id,
+            self.onchain_at_rest_id,
+            self.onchain_outgoing_id,
+            self.effective_incoming_id,
+            self.effective_at_rest_id,
+            self.effective_outgoing_id,
+            self.fee_id,
+            self.dust_id,
+        ]
+    }
+
     pub fn get_wallet_id_prefix(&self) -> String {
         let uuid_string = self.onchain_incoming_id.to_string();
         let (_, suffix) = uuid_string.split_at(24);
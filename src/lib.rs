@@ -16,6 +16,7 @@ pub mod fees;
 mod job;
 pub mod job_svc;
 pub mod ledger;
+mod metrics;
 pub mod outbox;
 pub mod payout;
 pub mod payout_queue;
@@ -26,4 +27,5 @@ mod token_store;
 mod tracing;
 pub mod utxo;
 pub mod wallet;
+pub mod webhook;
 pub mod xpub;
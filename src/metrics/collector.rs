@@ -0,0 +1,128 @@
+use rust_decimal::Decimal;
+use tracing::instrument;
+
+use std::collections::HashMap;
+
+use super::{
+    error::MetricsError,
+    registry::{metrics, set_last_synced_at},
+};
+use crate::{
+    ledger::*,
+    payout::Payouts,
+    primitives::*,
+    wallet::{Wallet, Wallets},
+};
+
+/// Refreshes the gauges that are derived from database state rather than
+/// being recorded as things happen.
+#[derive(Clone)]
+pub(super) struct MetricsCollector {
+    wallets: Wallets,
+    payouts: Payouts,
+    ledger: Ledger,
+}
+
+impl MetricsCollector {
+    pub fn new(pool: &sqlx::PgPool) -> Self {
+        Self {
+            wallets: Wallets::new(pool),
+            payouts: Payouts::new(pool),
+            ledger: Ledger::new(pool),
+        }
+    }
+
+    #[instrument(name = "metrics.collect", skip(self), err)]
+    pub async fn collect(&self) -> Result<(), MetricsError> {
+        self.collect_wallet_balances().await?;
+        self.collect_queued_payouts().await?;
+        set_last_synced_at(self.wallets.last_synced_at().await?);
+        Ok(())
+    }
+
+    async fn collect_wallet_balances(&self) -> Result<(), MetricsError> {
+        let gauge = &metrics().wallet_balance;
+        let wallet_ids: Vec<_> = self
+            .wallets
+            .all_ids()
+            .await?
+            .map(|(_, wallet_id)| wallet_id)
+            .collect();
+        let mut wallets_by_journal: HashMap<_, Vec<_>> = HashMap::new();
+        for wallet in self
+            .wallets
+            .find_all::<Wallet>(&wallet_ids)
+            .await?
+            .into_values()
+        {
+            wallets_by_journal
+                .entry(wallet.journal_id)
+                .or_default()
+                .push((wallet.id, wallet.ledger_account_ids));
+        }
+        for (journal_id, wallets) in wallets_by_journal {
+            let balances = self
+                .ledger
+                .get_wallets_ledger_account_balances(journal_id, wallets)
+                .await?;
+            for (wallet_id, balances) in balances {
+                let WalletLedgerAccountBalances {
+                    onchain_incoming,
+                    onchain_at_rest,
+                    onchain_outgoing,
+                    effective_incoming,
+                    effective_at_rest,
+                    effective_outgoing,
+                    fee,
+                    dust,
+                } = balances;
+                let wallet_id = wallet_id.to_string();
+                for (bucket, balance) in [
+                    ("onchain_incoming", onchain_incoming),
+                    ("onchain_at_rest", onchain_at_rest),
+                    ("onchain_outgoing", onchain_outgoing),
+                    ("effective_incoming", effective_incoming),
+                    ("effective_at_rest", effective_at_rest),
+                    ("effective_outgoing", effective_outgoing),
+                    ("fee", fee),
+                    ("dust", dust),
+                ] {
+                    let (settled, pending, encumbered) = balance
+                        .as_ref()
+                        .map(|b| (b.settled(), b.pending(), b.encumbered()))
+                        .unwrap_or((Decimal::ZERO, Decimal::ZERO, Decimal::ZERO));
+                    for (layer, btc) in [
+                        ("settled", settled),
+                        ("pending", pending),
+                        ("encumbered", encumbered),
+                    ] {
+                        gauge
+                            .with_label_values(&[wallet_id.as_str(), bucket, layer])
+                            .set(i64::from(Satoshis::from_btc(btc)));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn collect_queued_payouts(&self) -> Result<(), MetricsError> {
+        let queued = self.payouts.queued_by_payout_queue().await?;
+        let metrics = metrics();
+        // Queues whose payouts were all batched no longer show up in the query
+        metrics.queued_payouts.reset();
+        metrics.queued_payouts_sats.reset();
+        for (payout_queue_id, n_payouts, total) in queued {
+            let payout_queue_id = payout_queue_id.to_string();
+            metrics
+                .queued_payouts
+                .with_label_values(&[&payout_queue_id])
+                .set(n_payouts as i64);
+            metrics
+                .queued_payouts_sats
+                .with_label_values(&[&payout_queue_id])
+                .set(i64::from(total));
+        }
+        Ok(())
+    }
+}
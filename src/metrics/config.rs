@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MetricsConfig {
+    #[serde(default = "default_port")]
+    pub listen_port: u16,
+    /// How often balances and queued payouts are re-read from the database
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_collect_interval")]
+    pub collect_interval: Duration,
+}
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            listen_port: default_port(),
+            collect_interval: default_collect_interval(),
+        }
+    }
+}
+
+fn default_port() -> u16 {
+    2744
+}
+
+fn default_collect_interval() -> Duration {
+    Duration::from_secs(30)
+}
@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+use crate::{ledger::error::LedgerError, payout::error::PayoutError, wallet::error::WalletError};
+
+#[derive(Error, Debug)]
+pub enum MetricsError {
+    #[error("MetricsError - Prometheus: {0}")]
+    Prometheus(#[from] prometheus::Error),
+    #[error("MetricsError - Hyper: {0}")]
+    Hyper(#[from] hyper::Error),
+    #[error("{0}")]
+    LedgerError(Box<LedgerError>),
+    #[error("{0}")]
+    PayoutError(#[from] PayoutError),
+    #[error("{0}")]
+    WalletError(#[from] WalletError),
+}
+
+impl From<LedgerError> for MetricsError {
+    fn from(err: LedgerError) -> Self {
+        Self::LedgerError(Box::new(err))
+    }
+}
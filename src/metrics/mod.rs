@@ -0,0 +1,22 @@
+mod collector;
+mod config;
+pub mod error;
+mod registry;
+mod server;
+
+use collector::*;
+pub use config::*;
+use error::*;
+pub use registry::{record_fee_provider_error, record_outbox_lag, record_signing_session_failure};
+
+pub async fn run(pool: sqlx::PgPool, config: MetricsConfig) -> Result<(), MetricsError> {
+    let collector = MetricsCollector::new(&pool);
+    let collect_interval = config.collect_interval;
+    tokio::spawn(async move {
+        loop {
+            let _ = collector.collect().await;
+            tokio::time::sleep(collect_interval).await;
+        }
+    });
+    server::start(config).await
+}
@@ -0,0 +1,194 @@
+use prometheus::{Encoder, GaugeVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use crate::{fees::FeeProvider, primitives::*, signing_session::SigningFailureReason};
+
+static METRICS: OnceLock<BriaMetrics> = OnceLock::new();
+
+pub(super) struct BriaMetrics {
+    registry: Registry,
+    pub(super) wallet_balance: IntGaugeVec,
+    pub(super) queued_payouts: IntGaugeVec,
+    pub(super) queued_payouts_sats: IntGaugeVec,
+    seconds_since_last_sync: GaugeVec,
+    last_synced_at: Mutex<HashMap<WalletId, chrono::DateTime<chrono::Utc>>>,
+    signing_session_failures: IntCounterVec,
+    fee_provider_errors: IntCounterVec,
+    outbox_lag: GaugeVec,
+}
+
+impl BriaMetrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("bria".to_string()), None)
+            .expect("Couldn't create metrics registry");
+        let ret = Self {
+            wallet_balance: IntGaugeVec::new(
+                Opts::new(
+                    "wallet_balance_sats",
+                    "Balance of each wallet ledger account by layer",
+                ),
+                &["wallet_id", "bucket", "layer"],
+            )
+            .expect("Couldn't create wallet_balance_sats"),
+            queued_payouts: IntGaugeVec::new(
+                Opts::new(
+                    "payout_queue_queued_payouts",
+                    "Number of payouts waiting to be batched",
+                ),
+                &["payout_queue_id"],
+            )
+            .expect("Couldn't create payout_queue_queued_payouts"),
+            queued_payouts_sats: IntGaugeVec::new(
+                Opts::new(
+                    "payout_queue_queued_sats",
+                    "Value of payouts waiting to be batched",
+                ),
+                &["payout_queue_id"],
+            )
+            .expect("Couldn't create payout_queue_queued_sats"),
+            seconds_since_last_sync: GaugeVec::new(
+                Opts::new(
+                    "wallet_seconds_since_last_sync",
+                    "Seconds since the last successful sync_wallet job",
+                ),
+                &["wallet_id"],
+            )
+            .expect("Couldn't create wallet_seconds_since_last_sync"),
+            last_synced_at: Mutex::new(HashMap::new()),
+            signing_session_failures: IntCounterVec::new(
+                Opts::new(
+                    "signing_session_failures_total",
+                    "Failed signing attempts by reason",
+                ),
+                &["reason"],
+            )
+            .expect("Couldn't create signing_session_failures_total"),
+            fee_provider_errors: IntCounterVec::new(
+                Opts::new(
+                    "fee_provider_errors_total",
+                    "Errors returned by fee estimation providers",
+                ),
+                &["provider"],
+            )
+            .expect("Couldn't create fee_provider_errors_total"),
+            outbox_lag: GaugeVec::new(
+                Opts::new(
+                    "outbox_lag_seconds",
+                    "Delay between a ledger event being recorded and its outbox event being published",
+                ),
+                &["account_id"],
+            )
+            .expect("Couldn't create outbox_lag_seconds"),
+            registry,
+        };
+        ret.register().expect("Couldn't register metrics");
+        ret
+    }
+
+    fn register(&self) -> Result<(), prometheus::Error> {
+        self.registry
+            .register(Box::new(self.wallet_balance.clone()))?;
+        self.registry
+            .register(Box::new(self.queued_payouts.clone()))?;
+        self.registry
+            .register(Box::new(self.queued_payouts_sats.clone()))?;
+        self.registry
+            .register(Box::new(self.seconds_since_last_sync.clone()))?;
+        self.registry
+            .register(Box::new(self.signing_session_failures.clone()))?;
+        self.registry
+            .register(Box::new(self.fee_provider_errors.clone()))?;
+        self.registry.register(Box::new(self.outbox_lag.clone()))?;
+        Ok(())
+    }
+
+    pub(super) fn render(&self) -> Result<String, prometheus::Error> {
+        let now = chrono::Utc::now();
+        for (wallet_id, synced_at) in self
+            .last_synced_at
+            .lock()
+            .expect("last_synced_at poisoned")
+            .iter()
+        {
+            self.seconds_since_last_sync
+                .with_label_values(&[&wallet_id.to_string()])
+                .set(seconds_between(*synced_at, now));
+        }
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer).expect("Prometheus text format is utf8"))
+    }
+}
+
+pub(super) fn metrics() -> &'static BriaMetrics {
+    METRICS.get_or_init(BriaMetrics::new)
+}
+
+pub(super) fn set_last_synced_at(last_synced_at: HashMap<WalletId, chrono::DateTime<chrono::Utc>>) {
+    *metrics()
+        .last_synced_at
+        .lock()
+        .expect("last_synced_at poisoned") = last_synced_at;
+}
+
+pub fn record_signing_session_failure(reason: &SigningFailureReason) {
+    let reason = match reason {
+        SigningFailureReason::SignerConfigMissing => "signer_config_missing",
+        SigningFailureReason::SigningClientError { .. } => "signing_client_error",
+        SigningFailureReason::InvalidSignedPsbt { .. } => "invalid_signed_psbt",
+        SigningFailureReason::InvalidUnsignedPsbt { .. } => "invalid_unsigned_psbt",
+    };
+    metrics()
+        .signing_session_failures
+        .with_label_values(&[reason])
+        .inc();
+}
+
+pub fn record_fee_provider_error(provider: FeeProvider) {
+    let provider = match provider {
+        FeeProvider::MempoolSpace => "mempool_space",
+        FeeProvider::Blockstream => "blockstream",
+        FeeProvider::Bitcoind => "bitcoind",
+    };
+    metrics()
+        .fee_provider_errors
+        .with_label_values(&[provider])
+        .inc();
+}
+
+pub fn record_outbox_lag(account_id: AccountId, recorded_at: chrono::DateTime<chrono::Utc>) {
+    metrics()
+        .outbox_lag
+        .with_label_values(&[&account_id.to_string()])
+        .set(seconds_between(recorded_at, chrono::Utc::now()));
+}
+
+fn seconds_between(from: chrono::DateTime<chrono::Utc>, to: chrono::DateTime<chrono::Utc>) -> f64 {
+    (to - from).num_milliseconds().max(0) as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_recorded_metrics() {
+        let wallet_id = WalletId::new();
+        set_last_synced_at([(wallet_id, chrono::Utc::now())].into_iter().collect());
+        record_fee_provider_error(FeeProvider::Blockstream);
+        record_signing_session_failure(&SigningFailureReason::SignerConfigMissing);
+
+        let text = metrics().render().unwrap();
+        assert!(text.contains(&format!(
+            "bria_wallet_seconds_since_last_sync{{wallet_id=\"{wallet_id}\"}}"
+        )));
+        assert!(text.contains("bria_fee_provider_errors_total{provider=\"blockstream\"}"));
+        assert!(
+            text.contains("bria_signing_session_failures_total{reason=\"signer_config_missing\"}")
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+
+use std::convert::Infallible;
+
+use super::{config::*, error::*, registry::metrics};
+
+pub(super) async fn start(server_config: MetricsConfig) -> Result<(), MetricsError> {
+    println!(
+        "Starting metrics server on port {}",
+        server_config.listen_port
+    );
+    let make_service =
+        make_service_fn(|_conn| async { Ok::<_, Infallible>(service_fn(handle_request)) });
+    Server::try_bind(&([0, 0, 0, 0], server_config.listen_port).into())?
+        .serve(make_service)
+        .await?;
+    Ok(())
+}
+
+async fn handle_request(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => match metrics().render() {
+            Ok(text) => Response::builder()
+                .header(CONTENT_TYPE, prometheus::TEXT_FORMAT)
+                .body(Body::from(text)),
+            Err(e) => Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from(e.to_string())),
+        },
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty()),
+    };
+    Ok(response.expect("Couldn't build metrics response"))
+}
@@ -60,6 +60,8 @@ impl Augmenter {
             }
             OutboxEventPayload::PayoutSubmitted { id, .. }
             | OutboxEventPayload::PayoutCancelled { id, .. }
+            | OutboxEventPayload::PayoutApproved { id, .. }
+            | OutboxEventPayload::PayoutRejected { id, .. }
             | OutboxEventPayload::PayoutCommitted { id, .. }
             | OutboxEventPayload::PayoutBroadcast { id, .. }
             | OutboxEventPayload::PayoutSettled { id, .. } => {
@@ -76,6 +78,11 @@ impl Augmenter {
                     address: None,
                 })
             }
+            OutboxEventPayload::WalletUpdated { .. }
+            | OutboxEventPayload::PayoutQueueBatchDeferred { .. } => Ok(Augmentation {
+                address: None,
+                payout: None,
+            }),
         }
     }
 }
@@ -97,6 +97,40 @@ pub enum OutboxEventPayload {
         satoshis: Satoshis,
         destination: PayoutDestination,
     },
+    PayoutApproved {
+        id: PayoutId,
+        profile_id: ProfileId,
+        wallet_id: WalletId,
+        payout_queue_id: PayoutQueueId,
+        satoshis: Satoshis,
+        destination: PayoutDestination,
+        approved_by: ProfileId,
+        n_approvals: u32,
+        required_approvals: u32,
+    },
+    PayoutRejected {
+        id: PayoutId,
+        profile_id: ProfileId,
+        wallet_id: WalletId,
+        payout_queue_id: PayoutQueueId,
+        satoshis: Satoshis,
+        destination: PayoutDestination,
+        rejected_by: ProfileId,
+        reason: Option<String>,
+    },
+    WalletUpdated {
+        wallet_id: WalletId,
+        name: String,
+        settle_income_after_n_confs: u32,
+        settle_change_after_n_confs: u32,
+    },
+    PayoutQueueBatchDeferred {
+        payout_queue_id: PayoutQueueId,
+        fee_rate_sat_per_vb: f32,
+        max_fee_rate: u64,
+        n_unbatched_payouts: usize,
+        deferred_at: chrono::DateTime<chrono::Utc>,
+    },
     PayoutCommitted {
         id: PayoutId,
         vout: u32,
@@ -135,6 +169,112 @@ pub enum OutboxEventPayload {
     },
 }
 
+pub const OUTBOX_EVENT_TYPES: [&str; 12] = [
+    "utxo_detected",
+    "utxo_settled",
+    "utxo_dropped",
+    "payout_submitted",
+    "payout_cancelled",
+    "payout_approved",
+    "payout_rejected",
+    "wallet_updated",
+    "payout_queue_batch_deferred",
+    "payout_committed",
+    "payout_broadcast",
+    "payout_settled",
+];
+
+impl OutboxEventPayload {
+    /// The serialized `type` tag of the payload
+    pub fn event_type(&self) -> &'static str {
+        use OutboxEventPayload::*;
+        match self {
+            UtxoDetected { .. } => "utxo_detected",
+            UtxoSettled { .. } => "utxo_settled",
+            UtxoDropped { .. } => "utxo_dropped",
+            PayoutSubmitted { .. } => "payout_submitted",
+            PayoutCancelled { .. } => "payout_cancelled",
+            PayoutApproved { .. } => "payout_approved",
+            PayoutRejected { .. } => "payout_rejected",
+            WalletUpdated { .. } => "wallet_updated",
+            PayoutQueueBatchDeferred { .. } => "payout_queue_batch_deferred",
+            PayoutCommitted { .. } => "payout_committed",
+            PayoutBroadcast { .. } => "payout_broadcast",
+            PayoutSettled { .. } => "payout_settled",
+        }
+    }
+
+    pub fn wallet_id(&self) -> Option<WalletId> {
+        use OutboxEventPayload::*;
+        match self {
+            UtxoDetected { wallet_id, .. }
+            | UtxoSettled { wallet_id, .. }
+            | UtxoDropped { wallet_id, .. }
+            | PayoutSubmitted { wallet_id, .. }
+            | PayoutCancelled { wallet_id, .. }
+            | PayoutApproved { wallet_id, .. }
+            | PayoutRejected { wallet_id, .. }
+            | WalletUpdated { wallet_id, .. }
+            | PayoutCommitted { wallet_id, .. }
+            | PayoutBroadcast { wallet_id, .. }
+            | PayoutSettled { wallet_id, .. } => Some(*wallet_id),
+            PayoutQueueBatchDeferred { .. } => None,
+        }
+    }
+
+    pub fn payout_queue_id(&self) -> Option<PayoutQueueId> {
+        use OutboxEventPayload::*;
+        match self {
+            PayoutSubmitted {
+                payout_queue_id, ..
+            }
+            | PayoutCancelled {
+                payout_queue_id, ..
+            }
+            | PayoutApproved {
+                payout_queue_id, ..
+            }
+            | PayoutRejected {
+                payout_queue_id, ..
+            }
+            | PayoutQueueBatchDeferred {
+                payout_queue_id, ..
+            }
+            | PayoutCommitted {
+                payout_queue_id, ..
+            }
+            | PayoutBroadcast {
+                payout_queue_id, ..
+            }
+            | PayoutSettled {
+                payout_queue_id, ..
+            } => Some(*payout_queue_id),
+            UtxoDetected { .. }
+            | UtxoSettled { .. }
+            | UtxoDropped { .. }
+            | WalletUpdated { .. } => None,
+        }
+    }
+
+    pub fn profile_id(&self) -> Option<ProfileId> {
+        use OutboxEventPayload::*;
+        match self {
+            PayoutSubmitted { profile_id, .. }
+            | PayoutCancelled { profile_id, .. }
+            | PayoutApproved { profile_id, .. }
+            | PayoutRejected { profile_id, .. }
+            | PayoutCommitted { profile_id, .. }
+            | PayoutBroadcast { profile_id, .. }
+            | PayoutSettled { profile_id, .. } => Some(*profile_id),
+            UtxoDetected { .. }
+            | UtxoSettled { .. }
+            | UtxoDropped { .. }
+            | WalletUpdated { .. }
+            | PayoutQueueBatchDeferred { .. } => None,
+        }
+    }
+}
+
 impl From<JournalEventMetadata> for Vec<OutboxEventPayload> {
     fn from(meta: JournalEventMetadata) -> Self {
         use JournalEventMetadata::*;
@@ -305,3 +445,11 @@ impl std::fmt::Display for EventSequence {
         write!(f, "{}", self.0)
     }
 }
+
+impl std::str::FromStr for EventSequence {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
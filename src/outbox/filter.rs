@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use super::event::*;
+use crate::primitives::*;
+
+/// Restricts which events an `OutboxListener` emits.
+/// Every non empty set must match. Events that don't carry the filtered
+/// attribute (eg. `wallet_updated` has no `payout_queue_id`) are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct OutboxEventFilter {
+    pub event_types: HashSet<String>,
+    pub wallet_ids: HashSet<WalletId>,
+    pub payout_queue_ids: HashSet<PayoutQueueId>,
+    pub profile_ids: HashSet<ProfileId>,
+}
+
+impl OutboxEventFilter {
+    pub fn is_empty(&self) -> bool {
+        self.event_types.is_empty()
+            && self.wallet_ids.is_empty()
+            && self.payout_queue_ids.is_empty()
+            && self.profile_ids.is_empty()
+    }
+
+    pub fn matches(&self, payload: &OutboxEventPayload) -> bool {
+        (self.event_types.is_empty() || self.event_types.contains(payload.event_type()))
+            && matches_id(&self.wallet_ids, payload.wallet_id())
+            && matches_id(&self.payout_queue_ids, payload.payout_queue_id())
+            && matches_id(&self.profile_ids, payload.profile_id())
+    }
+}
+
+fn matches_id<T: std::hash::Hash + Eq>(ids: &HashSet<T>, id: Option<T>) -> bool {
+    ids.is_empty() || id.map(|id| ids.contains(&id)).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payout_submitted(wallet_id: WalletId, payout_queue_id: PayoutQueueId) -> OutboxEventPayload {
+        OutboxEventPayload::PayoutSubmitted {
+            id: PayoutId::new(),
+            profile_id: ProfileId::new(),
+            wallet_id,
+            payout_queue_id,
+            satoshis: Satoshis::from(10_000),
+            destination: PayoutDestination::OnchainAddress {
+                value: Address::parse_from_trusted_source("mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU"),
+            },
+        }
+    }
+
+    fn wallet_updated(wallet_id: WalletId) -> OutboxEventPayload {
+        OutboxEventPayload::WalletUpdated {
+            wallet_id,
+            name: "wallet".to_string(),
+            settle_income_after_n_confs: 1,
+            settle_change_after_n_confs: 1,
+        }
+    }
+
+    #[test]
+    fn event_type_matches_serialized_tag() {
+        for payload in [
+            payout_submitted(WalletId::new(), PayoutQueueId::new()),
+            wallet_updated(WalletId::new()),
+        ] {
+            let json = serde_json::to_value(&payload).unwrap();
+            assert_eq!(json["type"], payload.event_type());
+            assert!(OUTBOX_EVENT_TYPES.contains(&payload.event_type()));
+        }
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let filter = OutboxEventFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&wallet_updated(WalletId::new())));
+    }
+
+    #[test]
+    fn filters_by_event_type_and_ids() {
+        let wallet_id = WalletId::new();
+        let payout_queue_id = PayoutQueueId::new();
+
+        let filter = OutboxEventFilter {
+            event_types: HashSet::from(["payout_submitted".to_string()]),
+            ..Default::default()
+        };
+        assert!(filter.matches(&payout_submitted(wallet_id, payout_queue_id)));
+        assert!(!filter.matches(&wallet_updated(wallet_id)));
+
+        let filter = OutboxEventFilter {
+            wallet_ids: HashSet::from([wallet_id]),
+            ..Default::default()
+        };
+        assert!(filter.matches(&payout_submitted(wallet_id, payout_queue_id)));
+        assert!(filter.matches(&wallet_updated(wallet_id)));
+        assert!(!filter.matches(&wallet_updated(WalletId::new())));
+
+        let filter = OutboxEventFilter {
+            wallet_ids: HashSet::from([wallet_id]),
+            payout_queue_ids: HashSet::from([payout_queue_id]),
+            ..Default::default()
+        };
+        assert!(filter.matches(&payout_submitted(wallet_id, payout_queue_id)));
+        assert!(!filter.matches(&payout_submitted(wallet_id, PayoutQueueId::new())));
+        assert!(!filter.matches(&wallet_updated(wallet_id)));
+    }
+}
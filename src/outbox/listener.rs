@@ -4,13 +4,14 @@ use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
 use std::{collections::BTreeMap, pin::Pin, task::Poll};
 
-use super::{augmentation::*, error::OutboxError, event::*, repo::*};
+use super::{augmentation::*, error::OutboxError, event::*, filter::*, repo::*};
 use crate::primitives::*;
 
 pub struct OutboxListener {
     repo: OutboxRepo,
     account_id: AccountId,
     augmenter: Option<Augmenter>,
+    filter: OutboxEventFilter,
     next_to_augment: Option<OutboxEvent<Augmentation>>,
     augmentation_handle: Option<JoinHandle<Result<Augmentation, OutboxError>>>,
     last_sequence: EventSequence,
@@ -23,9 +24,11 @@ pub struct OutboxListener {
 }
 
 impl OutboxListener {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         repo: OutboxRepo,
         augmenter: Option<Augmenter>,
+        filter: OutboxEventFilter,
         event_receiver: broadcast::Receiver<OutboxEvent<WithoutAugmentation>>,
         account_id: AccountId,
         start_after: EventSequence,
@@ -35,6 +38,7 @@ impl OutboxListener {
         Self {
             repo,
             augmenter,
+            filter,
             next_to_augment: None,
             augmentation_handle: None,
             account_id,
@@ -114,6 +118,10 @@ impl OutboxListener {
                 if let Some(handle) = self.next_page_handle.take() {
                     handle.abort();
                 }
+                // Filtered events are dropped here so they never get augmented
+                if !self.filter.matches(&event.payload) {
+                    continue;
+                }
                 return Poll::Ready(Some(OutboxEvent::<Augmentation>::from(event)));
             }
             self.cache.insert(seq, event);
@@ -1,6 +1,7 @@
 mod augmentation;
 pub mod error;
 mod event;
+mod filter;
 mod listener;
 mod repo;
 
@@ -17,6 +18,7 @@ use crate::{ledger::*, primitives::*};
 pub use augmentation::*;
 use error::OutboxError;
 pub use event::*;
+pub use filter::*;
 pub use listener::*;
 use repo::*;
 
@@ -27,7 +29,7 @@ const DEFAULT_BUFFER_SIZE: usize = 100;
 
 #[derive(Clone)]
 pub struct Outbox {
-    _pool: Pool<Postgres>,
+    pool: Pool<Postgres>,
     repo: OutboxRepo,
     augmenter: Augmenter,
     sequences: Arc<RwLock<SequenceMap>>,
@@ -45,7 +47,7 @@ impl Outbox {
         Self::spawn_pg_listener(pool, sender.clone(), repo.clone(), Arc::clone(&sequences)).await?;
 
         let ret = Self {
-            _pool: pool.clone(),
+            pool: pool.clone(),
             augmenter,
             repo,
             sequences,
@@ -82,7 +84,11 @@ impl Outbox {
         let payloads = Vec::<OutboxEventPayload>::from(ledger_event.metadata);
         let sequences = self.sequences_for(ledger_event.account_id).await?;
         let mut write_sequences = sequences.write().await;
-        let mut sequence = write_sequences.0;
+        let mut tx = self.pool.begin().await?;
+        let mut sequence = self
+            .repo
+            .lock_latest_sequence(&mut tx, ledger_event.account_id)
+            .await?;
         current_span.record("next_sequence", tracing::field::display(sequence));
         let events: Vec<OutboxEvent<_>> = payloads
             .into_iter()
@@ -100,28 +106,54 @@ impl Outbox {
             })
             .collect();
 
-        if let Err(res) = self.repo.persist_events(&events).await {
-            let mut write_seqs = self.sequences.write().await;
-            write_seqs.remove(&ledger_event.account_id);
+        if let Err(res) = self.repo.persist_events(&mut tx, &events).await {
             crate::tracing::insert_error_fields(tracing::Level::WARN, &res);
             return Err(res);
         }
+        tx.commit().await?;
         for event in events {
             self.event_sender
                 .send(event)
                 .map_err(|_| OutboxError::SendEventError)?;
         }
 
-        *write_sequences = (sequence, Some(ledger_event.ledger_event_id));
+        *write_sequences = (
+            sequence.max(write_sequences.0),
+            Some(ledger_event.ledger_event_id),
+        );
+        crate::metrics::record_outbox_lag(ledger_event.account_id, ledger_event.recorded_at);
 
         Ok(())
     }
 
+    /// Persists an event as part of `op` so it is only published if `op` commits.
+    /// Subscribers receive it through the `bria_outbox_events` notification.
+    #[instrument("outbox.publish_in_op", skip(self, op), err)]
+    pub async fn publish_in_op(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        account_id: AccountId,
+        payload: OutboxEventPayload,
+    ) -> Result<(), OutboxError> {
+        let sequence = self.repo.lock_latest_sequence(op, account_id).await?.next();
+        let event: OutboxEvent<WithoutAugmentation> = OutboxEvent::builder()
+            .account_id(account_id)
+            .sequence(sequence)
+            .payload(payload)
+            .recorded_at(chrono::Utc::now())
+            .build()
+            .expect("Could not build OutboxEvent");
+        self.repo
+            .persist_events(op, std::slice::from_ref(&event))
+            .await
+    }
+
     pub async fn register_listener(
         &self,
         account_id: AccountId,
         start_after: Option<EventSequence>,
         augment: bool,
+        filter: OutboxEventFilter,
     ) -> Result<OutboxListener, OutboxError> {
         let sub = self.event_receiver.resubscribe();
         let latest_known = self.sequences_for(account_id).await?.read().await.0;
@@ -129,6 +161,7 @@ impl Outbox {
         Ok(OutboxListener::new(
             self.repo.clone(),
             augment.then(|| self.augmenter.clone()),
+            filter,
             sub,
             account_id,
             start,
@@ -137,6 +170,24 @@ impl Outbox {
         ))
     }
 
+    pub async fn latest_sequence(
+        &self,
+        account_id: AccountId,
+    ) -> Result<EventSequence, OutboxError> {
+        Ok(self.sequences_for(account_id).await?.read().await.0)
+    }
+
+    pub async fn load_events_after(
+        &self,
+        account_id: AccountId,
+        start_after: EventSequence,
+        limit: usize,
+    ) -> Result<Vec<OutboxEvent<WithoutAugmentation>>, OutboxError> {
+        self.repo
+            .load_next_page(account_id, start_after, limit)
+            .await
+    }
+
     #[instrument("outbox.last_ledger_event_id", skip(self), ret, err)]
     pub async fn last_ledger_event_id(
         &self,
@@ -16,7 +16,36 @@ impl OutboxRepo {
         Self { pool: pool.clone() }
     }
 
-    pub async fn persist_events<T>(&self, events: &[OutboxEvent<T>]) -> Result<(), OutboxError> {
+    /// Locks the account's sequence until `op` completes and returns the last sequence in use
+    pub async fn lock_latest_sequence(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        account_id: AccountId,
+    ) -> Result<EventSequence, OutboxError> {
+        sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtext('bria_outbox_events:' || $1::UUID::TEXT))",
+            Uuid::from(account_id),
+        )
+        .execute(op.as_executor())
+        .await?;
+        let row = sqlx::query!(
+            r#"
+            SELECT COALESCE(MAX(sequence), 0) AS "sequence!: EventSequence"
+            FROM bria_outbox_events
+            WHERE account_id = $1
+            "#,
+            Uuid::from(account_id),
+        )
+        .fetch_one(op.as_executor())
+        .await?;
+        Ok(row.sequence)
+    }
+
+    pub async fn persist_events<T>(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        events: &[OutboxEvent<T>],
+    ) -> Result<(), OutboxError> {
         if events.is_empty() {
             return Ok(());
         }
@@ -37,7 +66,7 @@ impl OutboxRepo {
             builder.push_bind(event.recorded_at);
         });
         let query = query_builder.build();
-        query.execute(&self.pool).await?;
+        query.execute(op.as_executor()).await?;
         Ok(())
     }
 
@@ -29,9 +29,32 @@ pub enum PayoutEvent {
         batch_id: BatchId,
         outpoint: bitcoin::OutPoint,
     },
+    CommittedToReplacementBatch {
+        batch_id: BatchId,
+        replaced_batch_id: BatchId,
+        outpoint: bitcoin::OutPoint,
+    },
     Cancelled {
         executed_by: ProfileId,
     },
+    ApprovalRequired {
+        required_approvals: u32,
+        #[serde(default)]
+        approvers: Vec<ProfileId>,
+    },
+    Approved {
+        approved_by: ProfileId,
+    },
+    Rejected {
+        rejected_by: ProfileId,
+        reason: Option<String>,
+    },
+    Scheduled {
+        not_before: chrono::DateTime<chrono::Utc>,
+    },
+    SpendOutpointsPinned {
+        outpoints: Vec<bitcoin::OutPoint>,
+    },
 }
 
 #[derive(EsEntity, Builder)]
@@ -51,6 +74,10 @@ pub struct Payout {
     pub external_id: String,
     #[builder(setter(into), default)]
     pub metadata: Option<serde_json::Value>,
+    #[builder(setter(into), default)]
+    pub not_before: Option<chrono::DateTime<chrono::Utc>>,
+    #[builder(default)]
+    pub spend_outpoints: Vec<bitcoin::OutPoint>,
 
     pub(super) events: EntityEvents<PayoutEvent>,
 }
@@ -69,20 +96,133 @@ impl Payout {
         Ok(())
     }
 
+    pub fn commit_to_replacement_batch(
+        &mut self,
+        batch_id: BatchId,
+        outpoint: bitcoin::OutPoint,
+    ) -> Result<(), PayoutError> {
+        let replaced_batch_id = self.batch_id.ok_or(PayoutError::PayoutNotCommitted)?;
+        self.events.push(PayoutEvent::CommittedToReplacementBatch {
+            batch_id,
+            replaced_batch_id,
+            outpoint,
+        });
+        self.batch_id = Some(batch_id);
+        self.outpoint = Some(outpoint);
+        Ok(())
+    }
+
+    pub fn approve(&mut self, profile_id: ProfileId) -> Result<(), PayoutError> {
+        self.check_can_decide_approval(profile_id)?;
+        if self.approved_by().any(|id| id == profile_id) {
+            return Err(PayoutError::PayoutAlreadyApprovedByProfile(profile_id));
+        }
+        self.events.push(PayoutEvent::Approved {
+            approved_by: profile_id,
+        });
+        Ok(())
+    }
+
+    pub fn reject(
+        &mut self,
+        profile_id: ProfileId,
+        reason: Option<String>,
+    ) -> Result<(), PayoutError> {
+        self.check_can_decide_approval(profile_id)?;
+        self.events.push(PayoutEvent::Rejected {
+            rejected_by: profile_id,
+            reason,
+        });
+        Ok(())
+    }
+
+    pub fn required_approvals(&self) -> Option<u32> {
+        self.events.iter_all().find_map(|event| match event {
+            PayoutEvent::ApprovalRequired {
+                required_approvals, ..
+            } => Some(*required_approvals),
+            _ => None,
+        })
+    }
+
+    pub fn approvers(&self) -> &[ProfileId] {
+        self.events
+            .iter_all()
+            .find_map(|event| match event {
+                PayoutEvent::ApprovalRequired { approvers, .. } => Some(approvers.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn approved_by(&self) -> impl Iterator<Item = ProfileId> + '_ {
+        self.events.iter_all().filter_map(|event| match event {
+            PayoutEvent::Approved { approved_by } => Some(*approved_by),
+            _ => None,
+        })
+    }
+
+    pub fn is_pending_approval(&self) -> bool {
+        match self.required_approvals() {
+            Some(required) => {
+                !self.is_cancelled() && self.approved_by().count() < required as usize
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_due(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.not_before
+            .map(|not_before| not_before <= now)
+            .unwrap_or(true)
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.events
+            .iter_all()
+            .any(|event| matches!(event, PayoutEvent::Rejected { .. }))
+    }
+
     pub fn is_cancelled(&self) -> bool {
         for event in self.events.iter_all() {
-            if let PayoutEvent::Cancelled { .. } = event {
+            if let PayoutEvent::Cancelled { .. } | PayoutEvent::Rejected { .. } = event {
                 return true;
             }
         }
         false
     }
 
+    fn check_can_decide_approval(&self, profile_id: ProfileId) -> Result<(), PayoutError> {
+        if self.is_cancelled() {
+            return Err(PayoutError::PayoutAlreadyCancelled);
+        }
+        if !self.is_pending_approval() {
+            return Err(PayoutError::PayoutNotPendingApproval);
+        }
+        if self.profile_id == profile_id {
+            return Err(PayoutError::SubmitterCannotDecideApproval);
+        }
+        if !self.approvers().contains(&profile_id) {
+            return Err(PayoutError::ProfileNotAnApprover(profile_id));
+        }
+        Ok(())
+    }
+
     fn is_already_committed(&self) -> bool {
         self.batch_id.is_some()
     }
 }
 
+impl From<&Payout> for TxPayout {
+    fn from(payout: &Payout) -> Self {
+        (
+            uuid::Uuid::from(payout.id),
+            payout.destination.onchain_address().clone(),
+            payout.satoshis,
+        )
+    }
+}
+
 impl TryFromEvents<PayoutEvent> for Payout {
     fn try_from_events(events: EntityEvents<PayoutEvent>) -> Result<Self, EsEntityError> {
         let mut builder = PayoutBuilder::default();
@@ -114,7 +254,16 @@ impl TryFromEvents<PayoutEvent> for Payout {
                 PayoutEvent::MetadataUpdated { metadata } => {
                     builder = builder.metadata(metadata.clone());
                 }
-                PayoutEvent::CommittedToBatch { batch_id, outpoint } => {
+                PayoutEvent::Scheduled { not_before } => {
+                    builder = builder.not_before(*not_before);
+                }
+                PayoutEvent::SpendOutpointsPinned { outpoints } => {
+                    builder = builder.spend_outpoints(outpoints.clone());
+                }
+                PayoutEvent::CommittedToBatch { batch_id, outpoint }
+                | PayoutEvent::CommittedToReplacementBatch {
+                    batch_id, outpoint, ..
+                } => {
                     builder = builder.batch_id(*batch_id).outpoint(*outpoint);
                 }
                 _ => (),
@@ -141,6 +290,14 @@ pub struct NewPayout {
     pub(super) external_id: String,
     #[builder(default, setter(into))]
     pub(super) metadata: Option<serde_json::Value>,
+    #[builder(default, setter(strip_option))]
+    pub(super) required_approvals: Option<u32>,
+    #[builder(default)]
+    pub(super) approvers: Vec<ProfileId>,
+    #[builder(default, setter(into))]
+    pub(super) not_before: Option<chrono::DateTime<chrono::Utc>>,
+    #[builder(default)]
+    pub(super) spend_outpoints: Vec<bitcoin::OutPoint>,
 }
 
 impl NewPayout {
@@ -170,6 +327,20 @@ impl IntoEvents<PayoutEvent> for NewPayout {
         if let Some(metadata) = self.metadata {
             events.push(PayoutEvent::MetadataUpdated { metadata });
         }
+        if let Some(required_approvals) = self.required_approvals {
+            events.push(PayoutEvent::ApprovalRequired {
+                required_approvals,
+                approvers: self.approvers,
+            });
+        }
+        if let Some(not_before) = self.not_before {
+            events.push(PayoutEvent::Scheduled { not_before });
+        }
+        if !self.spend_outpoints.is_empty() {
+            events.push(PayoutEvent::SpendOutpointsPinned {
+                outpoints: self.spend_outpoints,
+            });
+        }
         EntityEvents::init(self.id, events)
     }
 }
@@ -244,4 +415,143 @@ mod tests {
         let result = payout.cancel_payout(payout.profile_id);
         assert!(matches!(result, Err(PayoutError::PayoutAlreadyCommitted)));
     }
+
+    #[test]
+    fn commit_to_replacement_batch() {
+        let mut events = init_events();
+        let replaced_batch_id = BatchId::new();
+        events.push(PayoutEvent::CommittedToBatch {
+            batch_id: replaced_batch_id,
+            outpoint: bitcoin::OutPoint {
+                txid: "4010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d"
+                    .parse()
+                    .unwrap(),
+                vout: 0,
+            },
+        });
+        let mut payout = Payout::try_from_events(events).unwrap();
+
+        let batch_id = BatchId::new();
+        let outpoint = bitcoin::OutPoint {
+            txid: "e4b0c9f4a4e3c1dd4d5de3cf1ba7c3c6f0b6e1c7d4e1a3c0f3f4d8a7a1b2c3d4"
+                .parse()
+                .unwrap(),
+            vout: 1,
+        };
+        assert!(payout
+            .commit_to_replacement_batch(batch_id, outpoint)
+            .is_ok());
+        assert_eq!(payout.batch_id, Some(batch_id));
+        assert_eq!(payout.outpoint, Some(outpoint));
+        assert!(matches!(
+            payout.events.iter_all().last().unwrap(),
+            PayoutEvent::CommittedToReplacementBatch { replaced_batch_id: id, .. } if *id == replaced_batch_id
+        ));
+    }
+
+    #[test]
+    fn can_not_replace_batch_of_uncommitted_payout() {
+        let mut payout = Payout::try_from_events(init_events()).unwrap();
+        let result = payout.commit_to_replacement_batch(
+            BatchId::new(),
+            bitcoin::OutPoint {
+                txid: "4010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d"
+                    .parse()
+                    .unwrap(),
+                vout: 0,
+            },
+        );
+        assert!(matches!(result, Err(PayoutError::PayoutNotCommitted)));
+    }
+
+    #[test]
+    fn approve_payout() {
+        let approvers = vec![ProfileId::new(), ProfileId::new(), ProfileId::new()];
+        let mut events = init_events();
+        events.push(PayoutEvent::ApprovalRequired {
+            required_approvals: 2,
+            approvers: approvers.clone(),
+        });
+        let mut payout = Payout::try_from_events(events).unwrap();
+        assert!(payout.is_pending_approval());
+
+        assert!(payout.approve(approvers[0]).is_ok());
+        assert!(payout.is_pending_approval());
+        assert!(matches!(
+            payout.approve(approvers[0]),
+            Err(PayoutError::PayoutAlreadyApprovedByProfile(_))
+        ));
+
+        assert!(payout.approve(approvers[1]).is_ok());
+        assert!(!payout.is_pending_approval());
+        assert!(matches!(
+            payout.approve(approvers[2]),
+            Err(PayoutError::PayoutNotPendingApproval)
+        ));
+    }
+
+    #[test]
+    fn only_approvers_can_decide_approval() {
+        let mut events = init_events();
+        events.push(PayoutEvent::ApprovalRequired {
+            required_approvals: 1,
+            approvers: vec![ProfileId::new()],
+        });
+        let mut payout = Payout::try_from_events(events).unwrap();
+        assert!(matches!(
+            payout.approve(ProfileId::new()),
+            Err(PayoutError::ProfileNotAnApprover(_))
+        ));
+        assert!(matches!(
+            payout.reject(ProfileId::new(), None),
+            Err(PayoutError::ProfileNotAnApprover(_))
+        ));
+        assert!(payout.is_pending_approval());
+    }
+
+    #[test]
+    fn submitter_can_not_approve_own_payout() {
+        let mut events = init_events();
+        events.push(PayoutEvent::ApprovalRequired {
+            required_approvals: 1,
+            approvers: vec![],
+        });
+        let mut payout = Payout::try_from_events(events).unwrap();
+        let result = payout.approve(payout.profile_id);
+        assert!(matches!(
+            result,
+            Err(PayoutError::SubmitterCannotDecideApproval)
+        ));
+    }
+
+    #[test]
+    fn reject_payout() {
+        let approvers = vec![ProfileId::new(), ProfileId::new(), ProfileId::new()];
+        let mut events = init_events();
+        events.push(PayoutEvent::ApprovalRequired {
+            required_approvals: 2,
+            approvers: approvers.clone(),
+        });
+        let mut payout = Payout::try_from_events(events).unwrap();
+        assert!(payout.approve(approvers[0]).is_ok());
+        assert!(payout.reject(approvers[1], None).is_ok());
+        assert!(payout.is_rejected());
+        assert!(payout.is_cancelled());
+        assert!(!payout.is_pending_approval());
+        assert!(matches!(
+            payout.approve(approvers[2]),
+            Err(PayoutError::PayoutAlreadyCancelled)
+        ));
+    }
+
+    #[test]
+    fn scheduled_payout_is_due_after_not_before() {
+        let mut events = init_events();
+        let not_before = chrono::Utc::now() + chrono::Duration::try_hours(1).unwrap();
+        events.push(PayoutEvent::Scheduled { not_before });
+        let payout = Payout::try_from_events(events).unwrap();
+        assert_eq!(payout.not_before, Some(not_before));
+        assert!(!payout.is_due(chrono::Utc::now()));
+        assert!(payout.is_due(not_before));
+    }
 }
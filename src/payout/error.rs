@@ -6,12 +6,24 @@ pub enum PayoutError {
     Sqlx(sqlx::Error),
     #[error("PayoutError - Payout is already committed to batch")]
     PayoutAlreadyCommitted,
+    #[error("PayoutError - Payout is not committed to a batch")]
+    PayoutNotCommitted,
     #[error("PayoutError - Payout is already cancelled")]
     PayoutAlreadyCancelled,
+    #[error("PayoutError - Payout is not pending approval")]
+    PayoutNotPendingApproval,
+    #[error("PayoutError - Payout has already been approved by profile {0}")]
+    PayoutAlreadyApprovedByProfile(crate::primitives::ProfileId),
+    #[error("PayoutError - Submitting profile cannot approve or reject its own payout")]
+    SubmitterCannotDecideApproval,
+    #[error("PayoutError - Profile {0} is not an approver for this payout")]
+    ProfileNotAnApprover(crate::primitives::ProfileId),
     #[error("PayoutError - external_id already exists")]
     ExternalIdAlreadyExists,
     #[error("PayoutError - EsEntityError: {0}")]
     EsEntityError(es_entity::EsEntityError),
+    #[error("PayoutError - Serde: {0}")]
+    Serde(#[from] serde_json::Error),
     #[error("PayoutError - CursorDestructureError: {0}")]
     CursorDestructureError(#[from] es_entity::CursorDestructureError),
 }
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+
+use crate::primitives::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PayoutStatus {
+    Queued,
+    Committed,
+    Broadcast,
+    Settled,
+    Cancelled,
+}
+
+impl PayoutStatus {
+    pub(super) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Committed => "committed",
+            Self::Broadcast => "broadcast",
+            Self::Settled => "settled",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PayoutFilter {
+    pub wallet_id: Option<WalletId>,
+    pub payout_queue_id: Option<PayoutQueueId>,
+    pub profile_id: Option<ProfileId>,
+    pub status: Option<PayoutStatus>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub external_id: Option<String>,
+    pub destination_address: Option<String>,
+}
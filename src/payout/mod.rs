@@ -1,8 +1,10 @@
 mod entity;
 pub mod error;
+mod filter;
 mod repo;
 mod unbatched;
 
 pub use entity::*;
+pub use filter::*;
 pub use repo::*;
 pub use unbatched::*;
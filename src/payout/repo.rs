@@ -4,7 +4,7 @@ use tracing::instrument;
 
 use std::collections::HashMap;
 
-use super::{entity::*, error::*, unbatched::*};
+use super::{entity::*, error::*, filter::*, unbatched::*};
 use crate::primitives::*;
 
 #[derive(EsRepo, Clone, Debug)]
@@ -17,6 +17,11 @@ use crate::primitives::*;
         payout_queue_id(ty = "PayoutQueueId", update(persist = false)),
         profile_id(ty = "ProfileId", update(persist = false)),
         external_id(ty = "String", update(persist = false)),
+        destination_address(
+            ty = "String",
+            create(accessor = "destination.onchain_address().to_string()"),
+            update(persist = false)
+        ),
         batch_id(ty = "Option<BatchId>", create(persist = false),)
     ),
     tbl_prefix = "bria"
@@ -110,41 +115,81 @@ impl Payouts {
             query.after = end_cursor;
         }
 
+        let now = chrono::Utc::now();
         let filtered_payouts: HashMap<WalletId, Vec<UnbatchedPayout>> = unbatched_payouts
             .into_iter()
             .filter(|payout| {
-                !payout
-                    .events
-                    .iter_all()
-                    .any(|event| matches!(event, PayoutEvent::Cancelled { .. }))
+                !payout.is_cancelled() && !payout.is_pending_approval() && payout.is_due(now)
             })
             .filter_map(|unbatched_payout| UnbatchedPayout::try_from(unbatched_payout).ok())
             .fold(HashMap::new(), |mut map, payout| {
                 map.entry(payout.wallet_id).or_default().push(payout);
                 map
             });
-        Ok(UnbatchedPayouts::new(filtered_payouts))
+        let pinned_by_other_queues = self
+            .list_unbatched_pins(op, account_id)
+            .await?
+            .into_iter()
+            .filter(|pins| pins.payout_queue_id != payout_queue_id)
+            .flat_map(|pins| pins.outpoints)
+            .collect();
+        Ok(UnbatchedPayouts::new(
+            filtered_payouts,
+            pinned_by_other_queues,
+        ))
     }
 
-    #[instrument(name = "payouts.list_for_wallet", skip(self))]
-    pub async fn list_for_wallet(
+    #[instrument(name = "payouts.list_for_account", skip(self))]
+    pub async fn list_for_account(
         &self,
         account_id: AccountId,
-        wallet_id: WalletId,
+        filter: &PayoutFilter,
         page: u64,
         page_size: u64,
     ) -> Result<Vec<Payout>, PayoutError> {
-        let offset = (page - 1) * page_size;
+        let offset = (page.max(1) - 1) * page_size;
         let payouts = es_entity::es_query!(
             tbl_prefix = "bria",
             r#"
             SELECT *
             FROM bria_payouts
-            WHERE account_id = $1 AND wallet_id = $2
-            ORDER BY created_at DESC                
-            OFFSET $3"#,
+            WHERE account_id = $1
+            AND ($2::UUID IS NULL OR wallet_id = $2)
+            AND ($3::UUID IS NULL OR payout_queue_id = $3)
+            AND ($4::UUID IS NULL OR profile_id = $4)
+            AND ($5::TIMESTAMPTZ IS NULL OR created_at >= $5)
+            AND ($6::TIMESTAMPTZ IS NULL OR created_at < $6)
+            AND ($7::VARCHAR IS NULL OR external_id = $7)
+            AND ($8::VARCHAR IS NULL OR destination_address = $8)
+            AND ($9::VARCHAR IS NULL OR $9 = CASE
+                WHEN EXISTS (
+                    SELECT 1 FROM bria_payout_events c
+                    WHERE c.id = bria_payouts.id AND c.event_type IN ('cancelled', 'rejected')
+                ) THEN 'cancelled'
+                WHEN batch_id IS NULL THEN 'queued'
+                WHEN EXISTS (
+                    SELECT 1 FROM bria_utxos u
+                    WHERE u.spending_batch_id = bria_payouts.batch_id
+                    AND u.spend_settled_ledger_tx_id IS NOT NULL
+                ) THEN 'settled'
+                WHEN EXISTS (
+                    SELECT 1 FROM bria_batch_wallet_summaries s
+                    WHERE s.batch_id = bria_payouts.batch_id AND s.wallet_id = bria_payouts.wallet_id
+                    AND s.batch_broadcast_ledger_tx_id IS NOT NULL
+                ) THEN 'broadcast'
+                ELSE 'committed'
+            END)
+            ORDER BY created_at DESC
+            OFFSET $10"#,
             account_id as AccountId,
-            wallet_id as WalletId,
+            filter.wallet_id as Option<WalletId>,
+            filter.payout_queue_id as Option<PayoutQueueId>,
+            filter.profile_id as Option<ProfileId>,
+            filter.created_after,
+            filter.created_before,
+            filter.external_id,
+            filter.destination_address,
+            filter.status.map(|status| status.as_str()),
             offset as i64,
         )
         .fetch_n(self.pool(), page_size as usize)
@@ -275,8 +320,114 @@ impl Payouts {
         ))
     }
 
-    #[instrument(name = "payouts.find_by_id_for_cancellation", skip(self, op))]
-    pub async fn find_by_id_for_cancellation(
+    /// Count and value of payouts that have not been cancelled, rejected or
+    /// committed to a batch yet, grouped by payout queue.
+    #[instrument(name = "payouts.queued_by_payout_queue", skip(self))]
+    pub async fn queued_by_payout_queue(
+        &self,
+    ) -> Result<Vec<(PayoutQueueId, usize, Satoshis)>, PayoutError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                p.payout_queue_id,
+                COUNT(*) AS "n_payouts!",
+                COALESCE(SUM((e.event->>'satoshis')::NUMERIC), 0) AS "total_sats!"
+            FROM bria_payouts p
+            JOIN bria_payout_events e ON p.id = e.id
+            WHERE p.batch_id IS NULL AND e.event_type = 'initialized'
+            AND NOT EXISTS (
+                SELECT 1 FROM bria_payout_events c
+                WHERE c.id = p.id AND c.event_type IN ('cancelled', 'rejected')
+            )
+            GROUP BY p.payout_queue_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    PayoutQueueId::from(row.payout_queue_id),
+                    usize::try_from(row.n_payouts).expect("Couldn't unwrap n_payouts"),
+                    Satoshis::from(row.total_sats),
+                )
+            })
+            .collect())
+    }
+
+    #[instrument(name = "payouts.submitted_by_profile_since", skip(self, op))]
+    pub async fn submitted_by_profile_since(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        profile_id: ProfileId,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(usize, Satoshis), PayoutError> {
+        let res = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "n_payouts!",
+                COALESCE(SUM((e.event->>'satoshis')::NUMERIC), 0) AS "total_sats!"
+            FROM bria_payouts p
+            JOIN bria_payout_events e ON p.id = e.id
+            WHERE p.profile_id = $1 AND p.created_at >= $2 AND e.event_type = 'initialized'
+            AND NOT EXISTS (
+                SELECT 1 FROM bria_payout_events c
+                WHERE c.id = p.id AND c.event_type IN ('cancelled', 'rejected')
+            )
+            "#,
+            profile_id as ProfileId,
+            since
+        )
+        .fetch_one(op.as_executor())
+        .await?;
+
+        Ok((
+            usize::try_from(res.n_payouts).expect("Couldn't unwrap n_payouts"),
+            Satoshis::from(res.total_sats),
+        ))
+    }
+
+    /// Outpoints pinned by payouts that have not been cancelled, rejected or
+    /// committed to a batch yet.
+    #[instrument(name = "payouts.list_unbatched_pins", skip(self, op))]
+    pub async fn list_unbatched_pins(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        account_id: AccountId,
+    ) -> Result<Vec<PayoutPins>, PayoutError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT p.id, p.wallet_id, p.payout_queue_id, e.event->'outpoints' AS "outpoints!"
+            FROM bria_payouts p
+            JOIN bria_payout_events e ON p.id = e.id
+            WHERE p.account_id = $1 AND p.batch_id IS NULL
+            AND e.event_type = 'spend_outpoints_pinned'
+            AND NOT EXISTS (
+                SELECT 1 FROM bria_payout_events c
+                WHERE c.id = p.id AND c.event_type IN ('cancelled', 'rejected')
+            )
+            "#,
+            account_id as AccountId,
+        )
+        .fetch_all(op.as_executor())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(PayoutPins {
+                    payout_id: PayoutId::from(row.id),
+                    wallet_id: WalletId::from(row.wallet_id),
+                    payout_queue_id: PayoutQueueId::from(row.payout_queue_id),
+                    outpoints: serde_json::from_value(row.outpoints)?,
+                })
+            })
+            .collect()
+    }
+
+    #[instrument(name = "payouts.find_by_id_for_update", skip(self, op))]
+    pub async fn find_by_id_for_update(
         &self,
         op: &mut impl es_entity::AtomicOperation,
         account_id: AccountId,
@@ -1,7 +1,7 @@
 use derive_builder::Builder;
 
 use es_entity::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::entity::{Payout, PayoutEvent};
 use crate::primitives::*;
@@ -10,14 +10,19 @@ pub struct UnbatchedPayouts {
     inner: HashMap<WalletId, Vec<UnbatchedPayout>>,
     shifted: HashMap<PayoutId, UnbatchedPayout>,
     simulated_payout: Option<(WalletId, TxPayout)>,
+    pinned_by_other_queues: HashSet<bitcoin::OutPoint>,
     pub(super) batch_id: Option<BatchId>,
     pub(super) batched: Vec<UnbatchedPayout>,
 }
 
 impl UnbatchedPayouts {
-    pub(super) fn new(inner: HashMap<WalletId, Vec<UnbatchedPayout>>) -> Self {
+    pub(super) fn new(
+        inner: HashMap<WalletId, Vec<UnbatchedPayout>>,
+        pinned_by_other_queues: HashSet<bitcoin::OutPoint>,
+    ) -> Self {
         Self {
             inner,
+            pinned_by_other_queues,
             batch_id: None,
             simulated_payout: None,
             shifted: HashMap::new(),
@@ -76,6 +81,44 @@ impl UnbatchedPayouts {
         }
     }
 
+    pub fn spend_outpoints(&self) -> impl Iterator<Item = bitcoin::OutPoint> + '_ {
+        self.inner
+            .values()
+            .flatten()
+            .flat_map(|payout| payout.spend_outpoints.iter().copied())
+    }
+
+    /// Spend outpoints of every payout keyed by the payout id used in [`TxPayout`]
+    pub fn spend_outpoints_by_payout(&self) -> HashMap<uuid::Uuid, Vec<bitcoin::OutPoint>> {
+        self.inner
+            .values()
+            .flatten()
+            .filter(|payout| !payout.spend_outpoints.is_empty())
+            .map(|payout| (uuid::Uuid::from(payout.id), payout.spend_outpoints.clone()))
+            .collect()
+    }
+
+    /// Outpoints pinned by unbatched payouts of other payout queues
+    pub fn pinned_by_other_queues(&self) -> &HashSet<bitcoin::OutPoint> {
+        &self.pinned_by_other_queues
+    }
+
+    /// Payouts pinned to any of the given outpoints along with all the outpoints they pin
+    pub fn pinning_any_of(
+        &self,
+        outpoints: &HashSet<bitcoin::OutPoint>,
+    ) -> (HashSet<PayoutId>, HashSet<bitcoin::OutPoint>) {
+        let mut payout_ids = HashSet::new();
+        let mut pinned = HashSet::new();
+        for payout in self.inner.values().flatten() {
+            if payout.spend_outpoints.iter().any(|o| outpoints.contains(o)) {
+                payout_ids.insert(payout.id);
+                pinned.extend(payout.spend_outpoints.iter().copied());
+            }
+        }
+        (payout_ids, pinned)
+    }
+
     pub fn into_tx_payouts(&self) -> HashMap<WalletId, Vec<TxPayout>> {
         let mut ret: HashMap<WalletId, Vec<TxPayout>> = self
             .inner
@@ -90,6 +133,14 @@ impl UnbatchedPayouts {
     }
 }
 
+/// Outpoints pinned by a single payout that has not been batched yet
+pub struct PayoutPins {
+    pub payout_id: PayoutId,
+    pub wallet_id: WalletId,
+    pub payout_queue_id: PayoutQueueId,
+    pub outpoints: Vec<bitcoin::OutPoint>,
+}
+
 #[derive(Builder)]
 #[builder(pattern = "owned", build_fn(error = "EsEntityError"))]
 pub struct UnbatchedPayout {
@@ -97,6 +148,8 @@ pub struct UnbatchedPayout {
     pub wallet_id: WalletId,
     pub destination: PayoutDestination,
     pub satoshis: Satoshis,
+    #[builder(default)]
+    pub spend_outpoints: Vec<bitcoin::OutPoint>,
 
     pub(super) events: EntityEvents<PayoutEvent>,
 }
@@ -113,19 +166,24 @@ impl TryFrom<Payout> for UnbatchedPayout {
     fn try_from(payout: Payout) -> Result<Self, Self::Error> {
         let mut builder = UnbatchedPayoutBuilder::default();
         for event in payout.events.iter_all() {
-            if let PayoutEvent::Initialized {
-                id,
-                wallet_id,
-                destination,
-                satoshis,
-                ..
-            } = event
-            {
-                builder = builder
-                    .id(*id)
-                    .wallet_id(*wallet_id)
-                    .destination(destination.clone())
-                    .satoshis(*satoshis);
+            match event {
+                PayoutEvent::Initialized {
+                    id,
+                    wallet_id,
+                    destination,
+                    satoshis,
+                    ..
+                } => {
+                    builder = builder
+                        .id(*id)
+                        .wallet_id(*wallet_id)
+                        .destination(destination.clone())
+                        .satoshis(*satoshis);
+                }
+                PayoutEvent::SpendOutpointsPinned { outpoints } => {
+                    builder = builder.spend_outpoints(outpoints.clone());
+                }
+                _ => (),
             }
         }
         builder.events(payout.events).build()
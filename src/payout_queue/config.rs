@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-use crate::primitives::{Satoshis, TxPriority};
+use crate::primitives::{bitcoin::FeeRate, Satoshis, TxPriority, WalletId};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PayoutQueueConfig {
@@ -11,10 +11,27 @@ pub struct PayoutQueueConfig {
     #[serde(default)]
     pub cpfp_payouts_after_blocks: Option<u32>,
     pub force_min_change_sats: Option<Satoshis>,
+    #[serde(default)]
+    pub min_fee_rate: Option<u64>,
+    #[serde(default)]
+    pub max_fee_rate: Option<u64>,
     pub consolidate_deprecated_keychains: bool,
+    #[serde(default)]
+    pub consolidation: Option<ConsolidationConfig>,
     pub trigger: PayoutQueueTrigger,
 }
 
+/// Merges small utxos of the current keychain of each wallet into its change output
+/// whenever the queue is processed at a fee rate below `fee_rate_threshold` (sats/vbyte).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConsolidationConfig {
+    pub wallet_ids: Vec<WalletId>,
+    pub fee_rate_threshold: u64,
+    pub max_utxos: u32,
+    #[serde(default)]
+    pub max_utxo_sats: Option<Satoshis>,
+}
+
 impl PayoutQueueConfig {
     pub fn cpfp_payouts_detected_before(&self) -> chrono::DateTime<chrono::Utc> {
         let now = chrono::Utc::now();
@@ -32,6 +49,35 @@ impl PayoutQueueConfig {
     pub fn should_cpfp(&self) -> bool {
         self.cpfp_payouts_after_mins.is_some() || self.cpfp_payouts_after_blocks.is_some()
     }
+
+    pub fn has_valid_fee_rate_bounds(&self) -> bool {
+        match (self.min_fee_rate, self.max_fee_rate) {
+            (Some(min), Some(max)) => min <= max,
+            _ => true,
+        }
+    }
+
+    pub fn exceeds_max_fee_rate(&self, fee_rate: FeeRate) -> bool {
+        self.max_fee_rate
+            .map(|max| fee_rate.as_sat_per_vb() > max as f32)
+            .unwrap_or(false)
+    }
+
+    pub fn consolidation_at(&self, fee_rate: FeeRate) -> Option<&ConsolidationConfig> {
+        self.consolidation.as_ref().filter(|consolidation| {
+            consolidation.max_utxos > 1
+                && fee_rate.as_sat_per_vb() < consolidation.fee_rate_threshold as f32
+        })
+    }
+
+    pub fn apply_min_fee_rate(&self, fee_rate: FeeRate) -> FeeRate {
+        match self.min_fee_rate {
+            Some(min) if fee_rate.as_sat_per_vb() < min as f32 => {
+                FeeRate::from_sat_per_vb(min as f32)
+            }
+            _ => fee_rate,
+        }
+    }
 }
 
 #[serde_with::serde_as]
@@ -51,12 +97,15 @@ impl Default for PayoutQueueConfig {
         Self {
             tx_priority: TxPriority::NextBlock,
             consolidate_deprecated_keychains: false,
+            consolidation: None,
             trigger: PayoutQueueTrigger::Interval {
                 seconds: default_interval(),
             },
             cpfp_payouts_after_mins: None,
             cpfp_payouts_after_blocks: None,
             force_min_change_sats: None,
+            min_fee_rate: None,
+            max_fee_rate: None,
         }
     }
 }
@@ -64,3 +113,57 @@ impl Default for PayoutQueueConfig {
 fn default_interval() -> Duration {
     Duration::from_secs(60)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_rate_bounds() {
+        let config = PayoutQueueConfig {
+            min_fee_rate: Some(2),
+            max_fee_rate: Some(50),
+            ..Default::default()
+        };
+        assert!(config.has_valid_fee_rate_bounds());
+        assert_eq!(
+            config.apply_min_fee_rate(FeeRate::from_sat_per_vb(1.0)),
+            FeeRate::from_sat_per_vb(2.0)
+        );
+        assert_eq!(
+            config.apply_min_fee_rate(FeeRate::from_sat_per_vb(10.0)),
+            FeeRate::from_sat_per_vb(10.0)
+        );
+        assert!(!config.exceeds_max_fee_rate(FeeRate::from_sat_per_vb(50.0)));
+        assert!(config.exceeds_max_fee_rate(FeeRate::from_sat_per_vb(50.5)));
+
+        let config = PayoutQueueConfig {
+            min_fee_rate: Some(10),
+            max_fee_rate: Some(5),
+            ..Default::default()
+        };
+        assert!(!config.has_valid_fee_rate_bounds());
+    }
+
+    #[test]
+    fn consolidation_threshold() {
+        let config = PayoutQueueConfig {
+            consolidation: Some(ConsolidationConfig {
+                wallet_ids: vec![WalletId::new()],
+                fee_rate_threshold: 5,
+                max_utxos: 50,
+                max_utxo_sats: None,
+            }),
+            ..Default::default()
+        };
+        assert!(config
+            .consolidation_at(FeeRate::from_sat_per_vb(4.0))
+            .is_some());
+        assert!(config
+            .consolidation_at(FeeRate::from_sat_per_vb(5.0))
+            .is_none());
+        assert!(PayoutQueueConfig::default()
+            .consolidation_at(FeeRate::from_sat_per_vb(1.0))
+            .is_none());
+    }
+}
@@ -25,6 +25,10 @@ pub enum PayoutQueueEvent {
     ConfigUpdated {
         config: PayoutQueueConfig,
     },
+    BatchingDeferred {
+        fee_rate_sat_per_vb: f32,
+    },
+    BatchingResumed {},
 }
 
 #[derive(EsEntity, Builder)]
@@ -68,6 +72,38 @@ impl PayoutQueue {
             self.events.push(PayoutQueueEvent::ConfigUpdated { config });
         }
     }
+
+    pub fn is_batching_deferred(&self) -> bool {
+        let mut deferred = false;
+        for event in self.events.iter_all() {
+            match event {
+                PayoutQueueEvent::BatchingDeferred { .. } => deferred = true,
+                PayoutQueueEvent::BatchingResumed { .. } => deferred = false,
+                _ => (),
+            }
+        }
+        deferred
+    }
+
+    /// Returns true if batching was not already deferred
+    pub fn defer_batching(&mut self, fee_rate: bitcoin::FeeRate) -> bool {
+        if self.is_batching_deferred() {
+            return false;
+        }
+        self.events.push(PayoutQueueEvent::BatchingDeferred {
+            fee_rate_sat_per_vb: fee_rate.as_sat_per_vb(),
+        });
+        true
+    }
+
+    /// Returns true if batching was deferred
+    pub fn resume_batching(&mut self) -> bool {
+        if !self.is_batching_deferred() {
+            return false;
+        }
+        self.events.push(PayoutQueueEvent::BatchingResumed {});
+        true
+    }
 }
 
 impl TryFromEvents<PayoutQueueEvent> for PayoutQueue {
@@ -129,3 +165,33 @@ impl IntoEvents<PayoutQueueEvent> for NewPayoutQueue {
         EntityEvents::init(self.id, events)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue() -> PayoutQueue {
+        let new_queue = NewPayoutQueue::builder()
+            .account_id(AccountId::new())
+            .name("test")
+            .build()
+            .unwrap();
+        PayoutQueue::try_from_events(new_queue.into_events()).unwrap()
+    }
+
+    #[test]
+    fn batching_is_deferred_only_on_transition() {
+        let mut queue = queue();
+        let fee_rate = bitcoin::FeeRate::from_sat_per_vb(100.0);
+        assert!(!queue.is_batching_deferred());
+        assert!(!queue.resume_batching());
+
+        assert!(queue.defer_batching(fee_rate));
+        assert!(!queue.defer_batching(fee_rate));
+        assert!(queue.is_batching_deferred());
+
+        assert!(queue.resume_batching());
+        assert!(!queue.resume_batching());
+        assert!(queue.defer_batching(fee_rate));
+    }
+}
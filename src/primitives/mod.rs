@@ -8,7 +8,7 @@ pub use sqlx_ledger::{
 
 use std::fmt;
 
-es_entity::entity_id! { ProfileId, PayoutQueueId, WalletId, SigningSessionId, PayoutId, AdminApiKeyId, AccountId, ProfileApiKeyId, KeychainId, BatchId, OutboxEventId }
+es_entity::entity_id! { ProfileId, PayoutQueueId, WalletId, SigningSessionId, PayoutId, AdminApiKeyId, AccountId, ProfileApiKeyId, KeychainId, BatchId, OutboxEventId, WebhookEndpointId }
 
 impl From<LedgerJournalId> for AccountId {
     fn from(id: LedgerJournalId) -> Self {
@@ -112,7 +112,7 @@ pub mod bitcoin {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, clap::ValueEnum, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, clap::ValueEnum, PartialEq, Eq, Hash, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum TxPriority {
     NextBlock,
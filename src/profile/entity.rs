@@ -70,6 +70,21 @@ impl Profile {
             .map(|sp| sp.is_amount_allowed(sats))
             .unwrap_or(true)
     }
+
+    /// The approval policy that applies to a payout of `sats`, if any
+    pub fn approval_required(&self, sats: Satoshis) -> Option<&ApprovalPolicy> {
+        self.spending_policy
+            .as_ref()
+            .and_then(|sp| sp.approval.as_ref())
+            .filter(|approval| approval.required_approvals(sats).is_some())
+    }
+
+    pub fn spending_limits(&self) -> &[SpendingLimit] {
+        self.spending_policy
+            .as_ref()
+            .map(|sp| sp.limits.as_slice())
+            .unwrap_or_default()
+    }
 }
 
 impl TryFromEvents<ProfileEvent> for Profile {
@@ -96,6 +111,60 @@ impl TryFromEvents<ProfileEvent> for Profile {
 pub struct SpendingPolicy {
     pub allowed_payout_addresses: Vec<Address>,
     pub max_payout: Option<Satoshis>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub limits: Vec<SpendingLimit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalPolicy>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalPolicy {
+    pub threshold: Satoshis,
+    pub required_approvals: u32,
+    /// Profiles allowed to approve or reject payouts above the threshold
+    #[serde(default)]
+    pub approvers: Vec<ProfileId>,
+}
+
+impl ApprovalPolicy {
+    fn required_approvals(&self, sats: Satoshis) -> Option<u32> {
+        (sats > self.threshold && self.required_approvals > 0).then_some(self.required_approvals)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpendingLimit {
+    pub window_secs: u64,
+    pub max_amount: Option<Satoshis>,
+    pub max_payouts: Option<u32>,
+}
+
+impl SpendingLimit {
+    pub fn window_start(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> chrono::DateTime<chrono::Utc> {
+        i64::try_from(self.window_secs)
+            .ok()
+            .and_then(chrono::Duration::try_seconds)
+            .and_then(|window| now.checked_sub_signed(window))
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC)
+    }
+
+    pub fn is_exceeded_by(
+        &self,
+        n_submitted: usize,
+        submitted_sats: Satoshis,
+        sats: Satoshis,
+    ) -> bool {
+        self.max_payouts
+            .map(|max| n_submitted >= max as usize)
+            .unwrap_or(false)
+            || self
+                .max_amount
+                .map(|max| submitted_sats + sats > max)
+                .unwrap_or(false)
+    }
 }
 
 impl SpendingPolicy {
@@ -163,6 +232,8 @@ mod tests {
         let policy = super::SpendingPolicy {
             allowed_payout_addresses: vec![],
             max_payout: Some(Satoshis::from(1000)),
+            limits: vec![],
+            approval: None,
         };
         assert!(
             policy.is_destination_allowed(&PayoutDestination::OnchainAddress { value: address })
@@ -177,6 +248,8 @@ mod tests {
                 "bcrt1q4gfcga7jfjmm02zpvrh4ttc5k7lmnq2re52z2y",
             )],
             max_payout: Some(Satoshis::from(1000)),
+            limits: vec![],
+            approval: None,
         };
 
         assert!(
@@ -191,10 +264,51 @@ mod tests {
         let policy = super::SpendingPolicy {
             allowed_payout_addresses: vec![address.clone()],
             max_payout: Some(Satoshis::from(1000)),
+            limits: vec![],
+            approval: None,
         };
 
         assert!(
             policy.is_destination_allowed(&PayoutDestination::OnchainAddress { value: address })
         );
     }
+
+    #[test]
+    fn limit_on_amount_in_window() {
+        let limit = SpendingLimit {
+            window_secs: 86_400,
+            max_amount: Some(Satoshis::from(200_000_000)),
+            max_payouts: None,
+        };
+
+        assert!(!limit.is_exceeded_by(10, Satoshis::from(150_000_000), Satoshis::from(50_000_000)));
+        assert!(limit.is_exceeded_by(1, Satoshis::from(150_000_000), Satoshis::from(50_000_001)));
+    }
+
+    #[test]
+    fn limit_on_number_of_payouts_in_window() {
+        let limit = SpendingLimit {
+            window_secs: 3_600,
+            max_amount: None,
+            max_payouts: Some(50),
+        };
+
+        assert!(!limit.is_exceeded_by(49, Satoshis::from(1), Satoshis::from(1)));
+        assert!(limit.is_exceeded_by(50, Satoshis::from(1), Satoshis::from(1)));
+    }
+
+    #[test]
+    fn approval_only_required_above_threshold() {
+        let approval = ApprovalPolicy {
+            threshold: Satoshis::from(100_000),
+            required_approvals: 2,
+            approvers: vec![ProfileId::new(), ProfileId::new()],
+        };
+
+        assert_eq!(approval.required_approvals(Satoshis::from(100_000)), None);
+        assert_eq!(
+            approval.required_approvals(Satoshis::from(100_001)),
+            Some(2)
+        );
+    }
 }
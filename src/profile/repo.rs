@@ -74,6 +74,20 @@ impl Profiles {
         Ok(profile)
     }
 
+    pub async fn lock_for_spending_in_op(
+        &self,
+        op: &mut impl es_entity::AtomicOperation,
+        id: ProfileId,
+    ) -> Result<(), ProfileError> {
+        sqlx::query!(
+            r#"SELECT id FROM bria_profiles WHERE id = $1 FOR UPDATE"#,
+            id as ProfileId,
+        )
+        .fetch_one(op.as_executor())
+        .await?;
+        Ok(())
+    }
+
     pub async fn create_key_for_profile_in_op(
         &self,
         op: &mut impl es_entity::AtomicOperation,
@@ -5,8 +5,10 @@ use thiserror::Error;
 
 use std::collections::HashMap;
 
+use super::error::SigningSessionError;
 use crate::{
     primitives::{bitcoin::psbt, *},
+    wallet::error::WalletError,
     xpub::SigningClientError,
 };
 
@@ -96,6 +98,27 @@ impl SigningSession {
         ret
     }
 
+    /// Every recorded signing outcome in order, failures carrying their reason.
+    pub fn attempts(&self) -> Vec<SigningAttempt> {
+        self.events
+            .iter_persisted()
+            .filter_map(|persisted| match &persisted.event {
+                SigningSessionEvent::SigningAttemptFailed { reason } => Some(SigningAttempt {
+                    recorded_at: persisted.recorded_at,
+                    failure_reason: Some(reason.clone()),
+                }),
+                SigningSessionEvent::RemoteSigningCompleted { .. }
+                | SigningSessionEvent::ExternallySignedPsbtSubmitted { .. } => {
+                    Some(SigningAttempt {
+                        recorded_at: persisted.recorded_at,
+                        failure_reason: None,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn state(&self) -> SigningSessionState {
         let mut ret = SigningSessionState::Initialized;
         for event in self.events.iter_all() {
@@ -112,6 +135,12 @@ impl SigningSession {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct SigningAttempt {
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+    pub failure_reason: Option<SigningFailureReason>,
+}
+
 #[derive(Error, Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum SigningFailureReason {
@@ -119,6 +148,10 @@ pub enum SigningFailureReason {
     SignerConfigMissing,
     #[error("{err}")]
     SigningClientError { err: String },
+    #[error("{err}")]
+    InvalidSignedPsbt { err: String },
+    #[error("{err}")]
+    InvalidUnsignedPsbt { err: String },
 }
 
 impl From<&SigningClientError> for SigningFailureReason {
@@ -129,10 +162,41 @@ impl From<&SigningClientError> for SigningFailureReason {
     }
 }
 
+impl From<&SigningSessionError> for SigningFailureReason {
+    fn from(err: &SigningSessionError) -> Self {
+        Self::InvalidSignedPsbt {
+            err: err.to_string(),
+        }
+    }
+}
+
+impl From<&WalletError> for SigningFailureReason {
+    fn from(err: &WalletError) -> Self {
+        Self::InvalidUnsignedPsbt {
+            err: err.to_string(),
+        }
+    }
+}
+
 pub struct BatchSigningSession {
     pub xpub_sessions: HashMap<XPubFingerprint, SigningSession>,
 }
 
+impl BatchSigningSession {
+    /// Complete once every xpub has signed, Failed if any attempt is currently failing.
+    pub fn state(&self) -> SigningSessionState {
+        let mut ret = SigningSessionState::Complete;
+        for session in self.xpub_sessions.values() {
+            match session.state() {
+                SigningSessionState::Failed => return SigningSessionState::Failed,
+                SigningSessionState::Initialized => ret = SigningSessionState::Initialized,
+                SigningSessionState::Complete => (),
+            }
+        }
+        ret
+    }
+}
+
 #[derive(Builder, Clone, Debug)]
 pub struct NewSigningSession {
     #[builder(private)]
@@ -8,5 +8,20 @@ pub enum SigningSessionError {
     EsEntityError(es_entity::EsEntityError),
     #[error("SigningSessionError - CursorDestructureError: {0}")]
     CursorDestructureError(#[from] es_entity::CursorDestructureError),
+    #[error("SigningSessionError - Signed psbt does not spend the inputs of the batch")]
+    SignedPsbtInputsMismatch,
+    #[error("SigningSessionError - Signed psbt does not pay the outputs of the batch")]
+    SignedPsbtOutputsMismatch,
+    #[error("SigningSessionError - Signed psbt transaction differs from the batch transaction")]
+    SignedPsbtTransactionMismatch,
+    #[error("SigningSessionError - Signed psbt changed the spent utxo of input {0}")]
+    SignedPsbtInputUtxoMismatch(usize),
+    #[error("SigningSessionError - Signed psbt pays a fee of {actual} instead of {expected}")]
+    SignedPsbtFeeMismatch {
+        expected: crate::primitives::Satoshis,
+        actual: crate::primitives::Satoshis,
+    },
+    #[error("SigningSessionError - Signed psbt input {0} is not signed with sighash ALL")]
+    SignedPsbtSighashNotAll(usize),
 }
 es_entity::from_es_entity_error!(SigningSessionError);
@@ -15,6 +15,7 @@ pub struct WalletUtxo {
     pub utxo_detected_ledger_tx_id: LedgerTransactionId,
     pub utxo_settled_ledger_tx_id: Option<LedgerTransactionId>,
     pub spending_batch_id: Option<BatchId>,
+    pub frozen: bool,
 }
 
 #[derive(Debug)]
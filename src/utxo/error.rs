@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::primitives::bitcoin;
+
 #[derive(Debug, Error)]
 pub enum UtxoError {
     #[error("UtxoError - Sqlx: {0}")]
@@ -8,6 +10,16 @@ pub enum UtxoError {
     UtxoAlreadySettledError,
     #[error("UtxoError - Utxo does not exist")]
     UtxoDoesNotExistError,
+    #[error("UtxoError - Utxo {0} is not an unspent output of the wallet")]
+    UtxoNotInWallet(bitcoin::OutPoint),
+    #[error("UtxoError - Utxo {0} is frozen")]
+    UtxoFrozen(bitcoin::OutPoint),
+    #[error("UtxoError - Utxo {0} is already reserved in a batch")]
+    UtxoAlreadyReserved(bitcoin::OutPoint),
+    #[error("UtxoError - Utxo {0} is not settled yet")]
+    UtxoNotSettled(bitcoin::OutPoint),
+    #[error("UtxoError - InvalidCursor: '{0}'")]
+    InvalidCursor(String),
     #[error("UtxoError - EsEntityError: {0}")]
     EsEntityError(es_entity::EsEntityError),
 }
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+
+use std::{fmt, str::FromStr};
+
+use super::error::UtxoError;
+use crate::primitives::{bitcoin::OutPoint, *};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum UtxoStatus {
+    Pending,
+    Settled,
+    Reserved,
+    Spent,
+    Frozen,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct UtxoFilter {
+    pub keychain_id: Option<KeychainId>,
+    pub status: Option<UtxoStatus>,
+    pub min_value: Option<Satoshis>,
+    pub max_value: Option<Satoshis>,
+    pub min_detected_height: Option<u32>,
+    pub max_detected_height: Option<u32>,
+}
+
+/// Position of the last utxo returned in a page, ordered by
+/// (created_at, keychain_id, tx_id, vout) descending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UtxoCursor {
+    pub created_at: DateTime<Utc>,
+    pub keychain_id: KeychainId,
+    pub outpoint: OutPoint,
+}
+
+impl fmt::Display for UtxoCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}_{}_{}_{}",
+            self.created_at.timestamp_micros(),
+            self.keychain_id,
+            self.outpoint.txid,
+            self.outpoint.vout
+        )
+    }
+}
+
+impl FromStr for UtxoCursor {
+    type Err = UtxoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || UtxoError::InvalidCursor(s.to_string());
+        let mut parts = s.splitn(4, '_');
+        let created_at = parts
+            .next()
+            .and_then(|micros| micros.parse::<i64>().ok())
+            .and_then(DateTime::<Utc>::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let keychain_id = parts
+            .next()
+            .and_then(|id| id.parse::<KeychainId>().ok())
+            .ok_or_else(invalid)?;
+        let txid = parts
+            .next()
+            .and_then(|txid| txid.parse().ok())
+            .ok_or_else(invalid)?;
+        let vout = parts
+            .next()
+            .and_then(|vout| vout.parse::<u32>().ok())
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            created_at,
+            keychain_id,
+            outpoint: OutPoint { txid, vout },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_roundtrip() {
+        let cursor = UtxoCursor {
+            created_at: DateTime::<Utc>::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            keychain_id: KeychainId::new(),
+            outpoint: "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:3"
+                .parse()
+                .unwrap(),
+        };
+        let parsed = cursor.to_string().parse::<UtxoCursor>().unwrap();
+        assert_eq!(cursor, parsed);
+        assert!("not-a-cursor".parse::<UtxoCursor>().is_err());
+    }
+}
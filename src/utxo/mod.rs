@@ -2,18 +2,20 @@ mod cpfp;
 mod effective_allocation;
 mod entity;
 pub mod error;
+mod filter;
 mod repo;
 
 use bdk::{wallet::AddressInfo, LocalUtxo};
 use sqlx::{Pool, Postgres, Transaction};
 use tracing::instrument;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::primitives::{bitcoin::OutPoint, *};
 pub use cpfp::*;
 pub use entity::*;
 use error::UtxoError;
+pub use filter::*;
 use repo::*;
 
 #[derive(Clone)]
@@ -182,12 +184,17 @@ impl Utxos {
         Ok(pending_spend_tx_id.map(|id| (id, spend_tx_id, change_spent)))
     }
 
-    #[instrument(name = "utxos.find_keychain_utxos", skip_all, err)]
-    pub async fn find_keychain_utxos(
+    #[instrument(name = "utxos.list_keychain_utxos", skip_all, err)]
+    pub async fn list_keychain_utxos(
         &self,
         keychain_ids: impl Iterator<Item = KeychainId>,
-    ) -> Result<HashMap<KeychainId, KeychainUtxos>, UtxoError> {
-        self.utxos.find_keychain_utxos(keychain_ids).await
+        filter: &UtxoFilter,
+        after: Option<UtxoCursor>,
+        page_size: Option<usize>,
+    ) -> Result<(HashMap<KeychainId, KeychainUtxos>, Option<UtxoCursor>), UtxoError> {
+        self.utxos
+            .list_keychain_utxos(keychain_ids, filter, after, page_size)
+            .await
     }
 
     #[instrument(name = "utxos.find_cpfp_utxos", skip_all, err)]
@@ -211,6 +218,7 @@ impl Utxos {
         &self,
         tx: &mut Transaction<'_, Postgres>,
         ids: impl Iterator<Item = KeychainId>,
+        pinned_by_payouts: &HashSet<OutPoint>,
     ) -> Result<HashMap<KeychainId, Vec<OutPoint>>, UtxoError> {
         // Here we list all Utxos that bdk might want to use and lock them (FOR UPDATE)
         // This ensures that we don't have 2 concurrent psbt constructions get in the way
@@ -219,10 +227,16 @@ impl Utxos {
 
         // We need to tell bdk which utxos not to select.
         // If we have included it in a batch OR
-        // it isn't confirmed / settled yet
+        // it isn't confirmed / settled yet OR
+        // it has been frozen by an operator OR
+        // a payout that is not part of this build has pinned it
         // we need to flag it to bdk
         let filtered_utxos = reservable_utxos.into_iter().filter_map(|utxo| {
-            if utxo.spending_batch_id.is_some() || utxo.utxo_settled_ledger_tx_id.is_none() {
+            if utxo.spending_batch_id.is_some()
+                || utxo.utxo_settled_ledger_tx_id.is_none()
+                || utxo.frozen
+                || pinned_by_payouts.contains(&utxo.outpoint)
+            {
                 Some((utxo.keychain_id, utxo.outpoint))
             } else {
                 None
@@ -240,6 +254,106 @@ impl Utxos {
         Ok(outpoints_map)
     }
 
+    #[instrument(name = "utxos.pinned_outpoints", skip_all, err)]
+    #[allow(clippy::type_complexity)]
+    pub async fn pinned_outpoints(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: impl Iterator<Item = KeychainId>,
+        outpoints: impl IntoIterator<Item = OutPoint>,
+    ) -> Result<(HashMap<KeychainId, Vec<OutPoint>>, HashSet<OutPoint>), UtxoError> {
+        let mut unspendable: HashSet<OutPoint> = outpoints.into_iter().collect();
+        let mut pinned = HashMap::new();
+        if unspendable.is_empty() {
+            return Ok((pinned, unspendable));
+        }
+        // Outpoints that stopped being spendable since the payout was submitted
+        // are returned separately so the payouts pinning them can wait
+        for utxo in self.utxos.find_reservable_utxos(tx, ids).await? {
+            if utxo.check_spendable().is_ok() && unspendable.remove(&utxo.outpoint) {
+                pinned
+                    .entry(utxo.keychain_id)
+                    .or_insert_with(Vec::new)
+                    .push(utxo.outpoint);
+            }
+        }
+        Ok((pinned, unspendable))
+    }
+
+    #[instrument(name = "utxos.find_consolidation_utxos", skip(self, tx), err)]
+    pub async fn find_consolidation_utxos(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_id: KeychainId,
+        max_utxos: u32,
+        max_value: Option<Satoshis>,
+        min_value: Satoshis,
+    ) -> Result<Vec<OutPoint>, UtxoError> {
+        self.utxos
+            .find_consolidation_utxos(tx, keychain_id, max_utxos, max_value, min_value)
+            .await
+    }
+
+    #[instrument(name = "utxos.check_outpoints_spendable", skip_all, err)]
+    pub async fn check_outpoints_spendable(
+        &self,
+        ids: impl Iterator<Item = KeychainId>,
+        outpoints: &[OutPoint],
+    ) -> Result<(), UtxoError> {
+        if outpoints.is_empty() {
+            return Ok(());
+        }
+        let utxos = self.utxos.find_unspent_by_outpoints(ids, outpoints).await?;
+        for outpoint in outpoints {
+            utxos
+                .iter()
+                .find(|utxo| &utxo.outpoint == outpoint)
+                .ok_or(UtxoError::UtxoNotInWallet(*outpoint))?
+                .check_spendable()?;
+        }
+        Ok(())
+    }
+
+    /// Outpoints that have been spent or reserved by a batch since they were pinned
+    #[instrument(name = "utxos.spent_outpoints", skip_all, err)]
+    pub async fn spent_outpoints(
+        &self,
+        ids: impl Iterator<Item = KeychainId>,
+        outpoints: &[OutPoint],
+    ) -> Result<Vec<OutPoint>, UtxoError> {
+        if outpoints.is_empty() {
+            return Ok(Vec::new());
+        }
+        let utxos = self.utxos.find_unspent_by_outpoints(ids, outpoints).await?;
+        Ok(outpoints
+            .iter()
+            .filter(|outpoint| {
+                !utxos
+                    .iter()
+                    .any(|utxo| &utxo.outpoint == *outpoint && utxo.spending_batch_id.is_none())
+            })
+            .copied()
+            .collect())
+    }
+
+    #[instrument(name = "utxos.freeze_utxo", skip(self, keychain_ids), err)]
+    pub async fn freeze_utxo(
+        &self,
+        keychain_ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+    ) -> Result<(), UtxoError> {
+        self.utxos.set_frozen(keychain_ids, outpoint, true).await
+    }
+
+    #[instrument(name = "utxos.unfreeze_utxo", skip(self, keychain_ids), err)]
+    pub async fn unfreeze_utxo(
+        &self,
+        keychain_ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+    ) -> Result<(), UtxoError> {
+        self.utxos.set_frozen(keychain_ids, outpoint, false).await
+    }
+
     #[instrument(name = "utxos.reserve_utxos_in_batch", skip_all, err)]
     pub async fn reserve_utxos_in_batch(
         &self,
@@ -280,6 +394,28 @@ impl Utxos {
             .await
     }
 
+    #[instrument(name = "utxos.list_batch_inputs", skip(self, tx), err)]
+    pub async fn list_batch_inputs(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: BatchId,
+    ) -> Result<(HashMap<KeychainId, Vec<OutPoint>>, bool), UtxoError> {
+        self.utxos.list_batch_inputs(tx, batch_id).await
+    }
+
+    #[instrument(name = "utxos.revert_spend", skip(self, tx), err)]
+    pub async fn revert_spend(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        spend_detected_ledger_tx_id: LedgerTransactionId,
+    ) -> Result<(), UtxoError> {
+        // The change utxos of a batch were persisted with the same ledger tx id
+        // as the one marking the inputs spent (batch broadcast)
+        self.utxos
+            .revert_spend(tx, spend_detected_ledger_tx_id)
+            .await
+    }
+
     #[instrument(name = "utxos.list_utxos_by_outpoint", skip(self), err)]
     pub async fn list_utxos_by_outpoint(
         &self,
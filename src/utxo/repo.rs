@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use std::collections::{HashMap, HashSet};
 
-use super::{cpfp::CpfpCandidate, entity::*, error::UtxoError};
+use super::{cpfp::CpfpCandidate, entity::*, error::UtxoError, filter::*};
 use crate::primitives::{bitcoin::*, *};
 
 pub struct ReservableUtxo {
@@ -13,6 +13,22 @@ pub struct ReservableUtxo {
     pub outpoint: OutPoint,
     pub spending_batch_id: Option<BatchId>,
     pub utxo_settled_ledger_tx_id: Option<LedgerTransactionId>,
+    pub frozen: bool,
+}
+
+impl ReservableUtxo {
+    pub fn check_spendable(&self) -> Result<(), UtxoError> {
+        if self.frozen {
+            return Err(UtxoError::UtxoFrozen(self.outpoint));
+        }
+        if self.spending_batch_id.is_some() {
+            return Err(UtxoError::UtxoAlreadyReserved(self.outpoint));
+        }
+        if self.utxo_settled_ledger_tx_id.is_none() {
+            return Err(UtxoError::UtxoNotSettled(self.outpoint));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -195,54 +211,131 @@ impl UtxoRepo {
         })
     }
 
-    pub async fn find_keychain_utxos(
+    pub async fn list_keychain_utxos(
         &self,
         keychain_ids: impl Iterator<Item = KeychainId>,
-    ) -> Result<HashMap<KeychainId, KeychainUtxos>, UtxoError> {
-        let keychain_ids: Vec<Uuid> = keychain_ids.map(Uuid::from).collect();
-        let rows = sqlx::query!(
-            r#"SELECT wallet_id, keychain_id, tx_id, vout, kind as "kind: pg::PgKeychainKind", address_idx, value, address, bdk_spent,
+        filter: &UtxoFilter,
+        after: Option<UtxoCursor>,
+        page_size: Option<usize>,
+    ) -> Result<(HashMap<KeychainId, KeychainUtxos>, Option<UtxoCursor>), UtxoError> {
+        let keychain_ids: Vec<Uuid> = keychain_ids
+            .filter(|id| filter.keychain_id.map(|f| f == *id).unwrap_or(true))
+            .map(Uuid::from)
+            .collect();
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            r#"SELECT wallet_id, keychain_id, tx_id, vout, kind, address_idx, value, address, bdk_spent,
                   CASE
                       WHEN kind = 'external' THEN address
                       ELSE NULL
                   END as optional_address,
-                  block_height, income_detected_ledger_tx_id, income_settled_ledger_tx_id, spending_batch_id
-           FROM bria_utxos
-           WHERE keychain_id = ANY($1) AND bdk_spent = false
-           ORDER BY created_at DESC"#,
-           &keychain_ids
-        )
-            .fetch_all(&self.pool)
-            .await?;
+                  block_height, income_detected_ledger_tx_id, income_settled_ledger_tx_id, spending_batch_id,
+                  frozen, created_at
+            FROM bria_utxos
+            WHERE keychain_id = ANY("#,
+        );
+        query_builder.push_bind(&keychain_ids).push(")");
+        match filter.status {
+            None => query_builder.push(" AND bdk_spent = false"),
+            Some(UtxoStatus::Pending) => {
+                query_builder.push(" AND bdk_spent = false AND income_settled_ledger_tx_id IS NULL")
+            }
+            Some(UtxoStatus::Settled) => query_builder
+                .push(" AND bdk_spent = false AND income_settled_ledger_tx_id IS NOT NULL"),
+            Some(UtxoStatus::Reserved) => {
+                query_builder.push(" AND bdk_spent = false AND spending_batch_id IS NOT NULL")
+            }
+            Some(UtxoStatus::Spent) => query_builder.push(" AND bdk_spent = true"),
+            Some(UtxoStatus::Frozen) => {
+                query_builder.push(" AND bdk_spent = false AND frozen = true")
+            }
+        };
+        if let Some(min_value) = filter.min_value {
+            query_builder
+                .push(" AND value >= ")
+                .push_bind(min_value.into_inner());
+        }
+        if let Some(max_value) = filter.max_value {
+            query_builder
+                .push(" AND value <= ")
+                .push_bind(max_value.into_inner());
+        }
+        if let Some(min_height) = filter.min_detected_height {
+            query_builder
+                .push(" AND detected_block_height >= ")
+                .push_bind(min_height as i32);
+        }
+        if let Some(max_height) = filter.max_detected_height {
+            query_builder
+                .push(" AND detected_block_height <= ")
+                .push_bind(max_height as i32);
+        }
+        if let Some(cursor) = after {
+            query_builder
+                .push(" AND (created_at, keychain_id, tx_id, vout) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(Uuid::from(cursor.keychain_id))
+                .push(", ")
+                .push_bind(cursor.outpoint.txid.to_string())
+                .push(", ")
+                .push_bind(cursor.outpoint.vout as i32)
+                .push(")");
+        }
+        query_builder.push(" ORDER BY created_at DESC, keychain_id DESC, tx_id DESC, vout DESC");
+        if let Some(page_size) = page_size {
+            query_builder
+                .push(" LIMIT ")
+                .push_bind((page_size + 1) as i64);
+        }
+        let mut rows = query_builder.build().fetch_all(&self.pool).await?;
 
-        let mut utxos = HashMap::new();
+        let has_next_page = page_size.is_some_and(|page_size| rows.len() > page_size);
+        if let Some(page_size) = page_size {
+            rows.truncate(page_size);
+        }
+        let next_cursor = if has_next_page {
+            rows.last().map(|row| UtxoCursor {
+                created_at: row.get("created_at"),
+                keychain_id: KeychainId::from(row.get::<Uuid, _>("keychain_id")),
+                outpoint: OutPoint {
+                    txid: row.get::<String, _>("tx_id").parse().unwrap(),
+                    vout: row.get::<i32, _>("vout") as u32,
+                },
+            })
+        } else {
+            None
+        };
 
+        let mut utxos = HashMap::new();
         for row in rows {
             let utxo = WalletUtxo {
-                wallet_id: row.wallet_id.into(),
-                keychain_id: row.keychain_id.into(),
-                outpoint: OutPoint {
-                    txid: row.tx_id.parse().unwrap(),
-                    vout: row.vout as u32,
-                },
-                kind: KeychainKind::from(row.kind),
-                address_idx: row.address_idx as u32,
-                value: Satoshis::from(row.value),
+                wallet_id: WalletId::from(row.get::<Uuid, _>("wallet_id")),
+                keychain_id: KeychainId::from(row.get::<Uuid, _>("keychain_id")),
                 address: row
-                    .optional_address
+                    .get::<Option<String>, _>("optional_address")
                     .map(|addr| Address::parse_from_trusted_source(&addr)),
-                bdk_spent: row.bdk_spent,
-                block_height: row.block_height.map(|v| v as u32),
+                address_idx: row.get::<i32, _>("address_idx") as u32,
+                outpoint: OutPoint {
+                    txid: row.get::<String, _>("tx_id").parse().unwrap(),
+                    vout: row.get::<i32, _>("vout") as u32,
+                },
+                kind: KeychainKind::from(row.get::<bitcoin::pg::PgKeychainKind, _>("kind")),
+                bdk_spent: row.get("bdk_spent"),
+                value: Satoshis::from(row.get::<rust_decimal::Decimal, _>("value")),
                 utxo_detected_ledger_tx_id: LedgerTransactionId::from(
-                    row.income_detected_ledger_tx_id,
+                    row.get::<Uuid, _>("income_detected_ledger_tx_id"),
                 ),
                 utxo_settled_ledger_tx_id: row
-                    .income_settled_ledger_tx_id
+                    .get::<Option<Uuid>, _>("income_settled_ledger_tx_id")
                     .map(LedgerTransactionId::from),
-                spending_batch_id: row.spending_batch_id.map(BatchId::from),
+                spending_batch_id: row
+                    .get::<Option<Uuid>, _>("spending_batch_id")
+                    .map(BatchId::from),
+                frozen: row.get("frozen"),
+                block_height: row.get::<Option<i32>, _>("block_height").map(|h| h as u32),
             };
 
-            let keychain_id = KeychainId::from(row.keychain_id);
+            let keychain_id = utxo.keychain_id;
             utxos
                 .entry(keychain_id)
                 .or_insert_with(|| KeychainUtxos {
@@ -253,7 +346,7 @@ impl UtxoRepo {
                 .push(utxo);
         }
 
-        Ok(utxos)
+        Ok((utxos, next_cursor))
     }
 
     pub async fn find_reservable_utxos(
@@ -265,7 +358,7 @@ impl UtxoRepo {
         let rows = sqlx::query!(
             r#"SELECT keychain_id,
                CASE WHEN kind = 'external' THEN true ELSE false END as income_address,
-               tx_id, vout, spending_batch_id, income_settled_ledger_tx_id
+               tx_id, vout, spending_batch_id, income_settled_ledger_tx_id, frozen
                FROM bria_utxos
                WHERE keychain_id = ANY($1) AND bdk_spent = false
                FOR UPDATE"#,
@@ -287,12 +380,90 @@ impl UtxoRepo {
                 utxo_settled_ledger_tx_id: row
                     .income_settled_ledger_tx_id
                     .map(LedgerTransactionId::from),
+                frozen: row.frozen,
             })
             .collect();
 
         Ok(reservable_utxos)
     }
 
+    pub async fn find_consolidation_utxos(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_id: KeychainId,
+        max_utxos: u32,
+        max_value: Option<Satoshis>,
+        min_value: Satoshis,
+    ) -> Result<Vec<OutPoint>, UtxoError> {
+        let rows = sqlx::query!(
+            r#"SELECT tx_id, vout
+               FROM bria_utxos
+               WHERE keychain_id = $1 AND bdk_spent = false AND frozen = false
+               AND spending_batch_id IS NULL AND income_settled_ledger_tx_id IS NOT NULL
+               AND ($2::NUMERIC IS NULL OR value <= $2)
+               AND value > $4
+               ORDER BY value ASC
+               LIMIT $3
+               FOR UPDATE"#,
+            Uuid::from(keychain_id),
+            max_value.map(Satoshis::into_inner) as Option<rust_decimal::Decimal>,
+            max_utxos as i64,
+            min_value.into_inner(),
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OutPoint {
+                txid: row.tx_id.parse().unwrap(),
+                vout: row.vout as u32,
+            })
+            .collect())
+    }
+
+    pub async fn find_unspent_by_outpoints(
+        &self,
+        ids: impl Iterator<Item = KeychainId>,
+        outpoints: &[OutPoint],
+    ) -> Result<Vec<ReservableUtxo>, UtxoError> {
+        let uuids = ids.map(Uuid::from).collect::<Vec<_>>();
+        let (tx_ids, vouts): (Vec<String>, Vec<i32>) = outpoints
+            .iter()
+            .map(|out| (out.txid.to_string(), out.vout as i32))
+            .unzip();
+        let rows = sqlx::query!(
+            r#"SELECT keychain_id,
+               CASE WHEN kind = 'external' THEN true ELSE false END as income_address,
+               tx_id, vout, spending_batch_id, income_settled_ledger_tx_id, frozen
+               FROM bria_utxos
+               WHERE keychain_id = ANY($1) AND bdk_spent = false
+               AND (tx_id, vout) IN (SELECT * FROM UNNEST($2::text[], $3::integer[]))"#,
+            &uuids[..],
+            &tx_ids[..],
+            &vouts[..]
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ReservableUtxo {
+                keychain_id: KeychainId::from(row.keychain_id),
+                income_address: row.income_address.unwrap_or_default(),
+                outpoint: OutPoint {
+                    txid: row.tx_id.parse().unwrap(),
+                    vout: row.vout as u32,
+                },
+                spending_batch_id: row.spending_batch_id.map(BatchId::from),
+                utxo_settled_ledger_tx_id: row
+                    .income_settled_ledger_tx_id
+                    .map(LedgerTransactionId::from),
+                frozen: row.frozen,
+            })
+            .collect())
+    }
+
     pub async fn reserve_utxos_in_batch(
         &self,
         tx: &mut Transaction<'_, Postgres>,
@@ -368,6 +539,59 @@ impl UtxoRepo {
         Ok((map, sum))
     }
 
+    pub async fn list_batch_inputs(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: BatchId,
+    ) -> Result<(HashMap<KeychainId, Vec<OutPoint>>, bool), UtxoError> {
+        let rows = sqlx::query!(
+            r#"SELECT keychain_id, tx_id, vout, spend_settled_ledger_tx_id
+               FROM bria_utxos
+               WHERE spending_batch_id = $1
+               FOR UPDATE"#,
+            batch_id as BatchId,
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        let mut spend_settled = false;
+        let mut inputs = HashMap::new();
+        for row in rows {
+            spend_settled |= row.spend_settled_ledger_tx_id.is_some();
+            inputs
+                .entry(KeychainId::from(row.keychain_id))
+                .or_insert_with(Vec::new)
+                .push(OutPoint {
+                    txid: row.tx_id.parse().unwrap(),
+                    vout: row.vout as u32,
+                });
+        }
+        Ok((inputs, spend_settled))
+    }
+
+    pub async fn revert_spend(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        spend_detected_ledger_tx_id: LedgerTransactionId,
+    ) -> Result<(), UtxoError> {
+        sqlx::query!(
+            r#"DELETE FROM bria_utxos
+               WHERE income_detected_ledger_tx_id = $1 AND income_settled_ledger_tx_id IS NULL"#,
+            spend_detected_ledger_tx_id as LedgerTransactionId,
+        )
+        .execute(&mut **tx)
+        .await?;
+        sqlx::query!(
+            r#"UPDATE bria_utxos
+               SET bdk_spent = false, spend_detected_ledger_tx_id = NULL, modified_at = NOW()
+               WHERE spend_detected_ledger_tx_id = $1 AND spend_settled_ledger_tx_id IS NULL"#,
+            spend_detected_ledger_tx_id as LedgerTransactionId,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
     pub async fn list_utxos_by_outpoint(
         &self,
         utxos: &HashMap<KeychainId, Vec<OutPoint>>,
@@ -378,7 +602,8 @@ impl UtxoRepo {
                       WHEN kind = 'external' THEN address
                       ELSE NULL
                   END as optional_address,
-                  block_height, income_detected_ledger_tx_id, income_settled_ledger_tx_id, spending_batch_id
+                  block_height, income_detected_ledger_tx_id, income_settled_ledger_tx_id, spending_batch_id,
+                  frozen
             FROM bria_utxos
             WHERE (keychain_id, tx_id, vout) IN"#,
         );
@@ -429,6 +654,7 @@ impl UtxoRepo {
                 spending_batch_id: row
                     .get::<Option<Uuid>, _>("spending_batch_id")
                     .map(BatchId::from),
+                frozen: row.get("frozen"),
                 block_height: row.get::<Option<i32>, _>("block_height").map(|h| h as u32),
             })
             .collect())
@@ -453,6 +679,44 @@ impl UtxoRepo {
         Ok(row.and_then(|res| res.avg_value.map(Satoshis::from)))
     }
 
+    pub async fn set_frozen(
+        &self,
+        keychain_ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+        frozen: bool,
+    ) -> Result<(), UtxoError> {
+        let keychain_ids: Vec<Uuid> = keychain_ids.map(Uuid::from).collect();
+        let result = sqlx::query!(
+            r#"UPDATE bria_utxos
+            SET frozen = $4, modified_at = NOW()
+            WHERE keychain_id = ANY($1) AND tx_id = $2 AND vout = $3 AND bdk_spent = false
+              AND (spending_batch_id IS NULL OR NOT $4)"#,
+            &keychain_ids,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+            frozen,
+        )
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() > 0 {
+            return Ok(());
+        }
+        let reserved = sqlx::query!(
+            r#"SELECT spending_batch_id IS NOT NULL AS "reserved!"
+            FROM bria_utxos
+            WHERE keychain_id = ANY($1) AND tx_id = $2 AND vout = $3 AND bdk_spent = false"#,
+            &keychain_ids,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        match reserved {
+            Some(row) if row.reserved => Err(UtxoError::UtxoAlreadyReserved(outpoint)),
+            _ => Err(UtxoError::UtxoNotInWallet(outpoint)),
+        }
+    }
+
     pub async fn delete_utxo(
         &self,
         tx: &mut Transaction<'_, Postgres>,
@@ -510,6 +774,7 @@ impl UtxoRepo {
                       AND u1.created_at < $3
                       AND u1.detected_block_height < $4
                       AND bdk_spent IS FALSE
+                      AND frozen IS FALSE
                       AND spend_detected_ledger_tx_id IS NULL
                       AND income_settled_ledger_tx_id IS NULL
                       AND u1.trusted_origin_tx_input_tx_ids IS NOT NULL
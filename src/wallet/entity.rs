@@ -5,7 +5,7 @@ use sqlx_ledger::{AccountId as LedgerAccountId, JournalId};
 
 use std::collections::HashMap;
 
-use super::{config::*, keychain::*};
+use super::{config::*, error::WalletError, keychain::*};
 use crate::{ledger::WalletLedgerAccountIds, primitives::*, xpub::XPub};
 
 #[derive(EsEvent, Serialize, Deserialize)]
@@ -85,6 +85,10 @@ impl Wallet {
         std::iter::once(current).chain(self.deprecated_keychain_wallets(pool))
     }
 
+    pub fn current_keychain_id(&self) -> KeychainId {
+        self.keychain_ids().next().expect("No current keychain")
+    }
+
     pub fn current_keychain_wallet(&self, pool: &sqlx::PgPool) -> KeychainWallet {
         let (id, cfg) = self.iter_keychains().next().expect("No current keychain");
         KeychainWallet::new(pool.clone(), self.network, *id, cfg.clone())
@@ -99,6 +103,55 @@ impl Wallet {
             .map(move |(id, cfg)| KeychainWallet::new(pool.clone(), self.network, *id, cfg.clone()))
     }
 
+    pub fn created_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.events
+            .entity_first_persisted_at()
+            .expect("wallet not persisted")
+    }
+
+    pub fn update_name(&mut self, name: String) {
+        if self.name != name {
+            self.name = name.clone();
+            self.events.push(WalletEvent::NameUpdated { name });
+        }
+    }
+
+    pub fn update_config(&mut self, config: WalletConfig) {
+        if self.config != config {
+            self.config = config.clone();
+            self.events.push(WalletEvent::ConfigUpdated {
+                wallet_config: config,
+            });
+        }
+    }
+
+    pub fn rotate_keychain(
+        &mut self,
+        keychain_config: KeychainConfig,
+    ) -> Result<KeychainId, WalletError> {
+        if !keychain_config.matches_network(self.network) {
+            return Err(WalletError::KeychainNetworkMismatch(self.network));
+        }
+        if self
+            .iter_keychains()
+            .next()
+            .map(|(_, cfg)| cfg == &keychain_config)
+            .unwrap_or(false)
+        {
+            return Err(WalletError::KeychainAlreadyActive);
+        }
+        let keychain_id = KeychainId::new();
+        let idx = self.iter_keychains().count();
+        self.events.push(WalletEvent::KeychainAdded {
+            keychain_id,
+            idx,
+            keychain_config,
+        });
+        self.events
+            .push(WalletEvent::KeychainActivated { keychain_id });
+        Ok(keychain_id)
+    }
+
     pub fn xpubs_for_keychains<'a>(
         &self,
         keychain_ids: impl IntoIterator<Item = &'a KeychainId>,
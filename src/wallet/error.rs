@@ -3,7 +3,9 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum WalletError {
     #[error("WalletError - Sqlx: {0}")]
-    Sqlx(#[from] sqlx::Error),
+    Sqlx(sqlx::Error),
+    #[error("WalletError - A wallet with this name already exists")]
+    NameAlreadyExists,
     #[error("WalletError - EsEntityError: {0}")]
     EsEntityError(es_entity::EsEntityError),
     #[error("WalletError - CursorDestructureError: {0}")]
@@ -16,6 +18,27 @@ pub enum WalletError {
     PsbtDoesNotHaveValidSignatures,
     #[error("WalletError - Unsigned txn in signed and unsigned psbt don't match")]
     UnsignedTxnMismatch,
+    #[error("WalletError - Psbt input {0} is missing its bip32 key origins")]
+    PsbtInputMissingKeyOrigins(usize),
+    #[error("WalletError - Psbt is missing the global xpub for key origin '{0}'")]
+    PsbtMissingGlobalXPub(crate::primitives::bitcoin::Fingerprint),
+    #[error("WalletError - Keychain does not match wallet network '{0}'")]
+    KeychainNetworkMismatch(crate::primitives::bitcoin::Network),
+    #[error("WalletError - Keychain is already the current keychain of the wallet")]
+    KeychainAlreadyActive,
 }
 
 es_entity::from_es_entity_error!(WalletError);
+
+impl From<sqlx::Error> for WalletError {
+    fn from(error: sqlx::Error) -> Self {
+        if let Some(err) = error.as_database_error() {
+            if let Some(constraint) = err.constraint() {
+                if constraint.contains("name") {
+                    return Self::NameAlreadyExists;
+                }
+            }
+        }
+        Self::Sqlx(error)
+    }
+}
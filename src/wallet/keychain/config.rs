@@ -2,7 +2,10 @@ use bdk::{descriptor::DescriptorPublicKey, miniscript::ForEachKey};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, str::FromStr};
 
-use crate::{primitives::bitcoin::ExtendedDescriptor, xpub::*};
+use crate::{
+    primitives::bitcoin::{self, ExtendedDescriptor},
+    xpub::*,
+};
 
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -11,6 +14,9 @@ pub enum KeychainConfig {
     Wpkh {
         xpub: XPub,
     },
+    Tr {
+        xpub: XPub,
+    },
     Descriptors {
         internal: ExtendedDescriptor,
         external: ExtendedDescriptor,
@@ -26,13 +32,17 @@ impl KeychainConfig {
         Self::Wpkh { xpub }
     }
 
+    pub fn tr(xpub: XPub) -> Self {
+        Self::Tr { xpub }
+    }
+
     pub fn sorted_multisig(xpub: Vec<XPub>, threshold: u32) -> Self {
         Self::SortedMultisig { xpub, threshold }
     }
 
     pub fn xpubs(&self) -> Vec<XPub> {
         match self {
-            Self::Wpkh { xpub } => vec![xpub.clone()],
+            Self::Wpkh { xpub } | Self::Tr { xpub } => vec![xpub.clone()],
             Self::Descriptors { internal, external } => {
                 let mut ret = HashMap::new();
                 internal.for_each_key(|key| {
@@ -51,11 +61,25 @@ impl KeychainConfig {
         }
     }
 
+    pub fn matches_network(&self, network: bitcoin::Network) -> bool {
+        self.xpubs().iter().all(|xpub| {
+            let xpub_network = xpub.inner().network;
+            if network == bitcoin::Network::Bitcoin {
+                xpub_network == bitcoin::Network::Bitcoin
+            } else {
+                xpub_network != bitcoin::Network::Bitcoin
+            }
+        })
+    }
+
     pub fn external_descriptor(&self) -> ExtendedDescriptor {
         match self {
             Self::Wpkh { xpub } => format!("wpkh({}/0/*)", xpub)
                 .parse()
                 .expect("Couldn't create internal wpkh descriptor"),
+            Self::Tr { xpub } => format!("tr({}/0/*)", xpub)
+                .parse()
+                .expect("Couldn't create external tr descriptor"),
             Self::Descriptors { external, .. } => external.clone(),
             Self::SortedMultisig { xpub, threshold } => {
                 let keys = xpub
@@ -76,6 +100,9 @@ impl KeychainConfig {
             Self::Wpkh { xpub } => format!("wpkh({}/1/*)", xpub)
                 .parse()
                 .expect("Couldn't create internal wpkh descriptor"),
+            Self::Tr { xpub } => format!("tr({}/1/*)", xpub)
+                .parse()
+                .expect("Couldn't create internal tr descriptor"),
             Self::Descriptors { internal, .. } => internal.clone(),
             Self::SortedMultisig { xpub, threshold } => {
                 let keys = xpub
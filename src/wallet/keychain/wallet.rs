@@ -1,5 +1,4 @@
 use bdk::{
-    blockchain::{GetHeight, WalletSync},
     database::BatchDatabase,
     wallet::{signer::SignOptions, AddressIndex},
     Wallet,
@@ -7,9 +6,11 @@ use bdk::{
 use sqlx::PgPool;
 use tracing::instrument;
 
+use std::sync::Arc;
+
 use super::config::*;
 use crate::{
-    bdk::{error::BdkError, pg::SqlxWalletDb},
+    bdk::{blockchain::ChainBackend, error::BdkError, pg::SqlxWalletDb},
     primitives::{bitcoin::*, *},
 };
 
@@ -102,12 +103,16 @@ impl KeychainWallet {
     }
 
     #[instrument(name = "keychain_wallet.sync", skip_all)]
-    pub async fn sync<B: WalletSync + GetHeight + Send + Sync + 'static>(
+    pub async fn sync(
         &self,
-        blockchain: B,
+        blockchain: Arc<dyn ChainBackend>,
+        wallet_created_at: chrono::DateTime<chrono::Utc>,
     ) -> Result<(), BdkError> {
-        self.with_wallet(move |wallet| wallet.sync(&blockchain, Default::default()))
-            .await??;
+        let keychain_id = self.keychain_id;
+        self.with_wallet(move |wallet| {
+            blockchain.sync_wallet(keychain_id, wallet_created_at, &wallet)
+        })
+        .await??;
         Ok(())
     }
 
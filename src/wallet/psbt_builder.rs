@@ -1,5 +1,7 @@
 use bdk::{
     database::BatchDatabase,
+    descriptor::DescriptorPublicKey,
+    miniscript::{psbt::PsbtExt, DefiniteDescriptorKey, Descriptor, ForEachKey},
     wallet::{tx_builder::TxOrdering, AddressIndex, AddressInfo},
     FeeRate, Wallet,
 };
@@ -73,6 +75,10 @@ pub struct PsbtBuilderConfig {
     #[builder(default)]
     cpfp_utxos: HashMap<KeychainId, Vec<CpfpUtxo>>,
     #[builder(default)]
+    pinned_utxos: HashMap<KeychainId, Vec<OutPoint>>,
+    #[builder(default)]
+    payout_pins: HashMap<uuid::Uuid, Vec<OutPoint>>,
+    #[builder(default)]
     for_estimation: bool,
     #[builder(default)]
     force_min_change_output: Option<Satoshis>,
@@ -107,6 +113,24 @@ impl PsbtBuilderConfig {
         missing_cpfp_fees
     }
 
+    /// Outpoints pinned by payouts that are not part of `payouts`.
+    /// They may only be spent together with the payout that pinned them.
+    fn pins_of_excluded_payouts(&self, payouts: &[TxPayout]) -> HashSet<OutPoint> {
+        let included: HashSet<_> = payouts.iter().map(|(id, _, _)| *id).collect();
+        let included_pins: HashSet<_> = self
+            .payout_pins
+            .iter()
+            .filter(|(id, _)| included.contains(id))
+            .flat_map(|(_, outpoints)| outpoints.iter().copied())
+            .collect();
+        self.payout_pins
+            .iter()
+            .filter(|(id, _)| !included.contains(id))
+            .flat_map(|(_, outpoints)| outpoints.iter().copied())
+            .filter(|outpoint| !included_pins.contains(outpoint))
+            .collect()
+    }
+
     fn force_min_change_output_with_jitter(&self) -> Option<Satoshis> {
         use rand::Rng;
         self.force_min_change_output.map(|sats| {
@@ -117,6 +141,78 @@ impl PsbtBuilderConfig {
     }
 }
 
+/// Key origin data hardware signers need but that gets lost while combining
+/// the per wallet psbts into one batch.
+#[derive(Default)]
+struct KeyOrigins {
+    xpubs: HashMap<KeychainId, Vec<(ExtendedPubKey, bip32::KeySource)>>,
+    change_descriptors: HashMap<ScriptBuf, Descriptor<DefiniteDescriptorKey>>,
+}
+
+impl KeyOrigins {
+    fn record_keychain<D: BatchDatabase>(&mut self, keychain_id: KeychainId, wallet: &Wallet<D>) {
+        let xpubs = self.xpubs.entry(keychain_id).or_default();
+        if !xpubs.is_empty() {
+            return;
+        }
+        for kind in [KeychainKind::External, KeychainKind::Internal] {
+            wallet
+                .get_descriptor_for_keychain(kind)
+                .for_each_key(|key| {
+                    if let DescriptorPublicKey::XPub(xkey) = key {
+                        let path = xkey
+                            .origin
+                            .as_ref()
+                            .map(|(_, path)| path.clone())
+                            .unwrap_or_else(DerivationPath::master);
+                        let source = (key.master_fingerprint(), path);
+                        if !xpubs.contains(&(xkey.xkey, source.clone())) {
+                            xpubs.push((xkey.xkey, source));
+                        }
+                    }
+                    true
+                });
+        }
+    }
+
+    fn record_change<D: BatchDatabase>(
+        &mut self,
+        wallet: &Wallet<D>,
+        change_address: &AddressInfo,
+    ) {
+        if let Ok(descriptor) = wallet
+            .get_descriptor_for_keychain(KeychainKind::Internal)
+            .at_derivation_index(change_address.index)
+        {
+            self.change_descriptors
+                .insert(change_address.script_pubkey(), descriptor);
+        }
+    }
+
+    fn enrich(
+        self,
+        psbt: &mut psbt::PartiallySignedTransaction,
+        signing_keychains: &HashSet<KeychainId>,
+    ) {
+        for (keychain_id, xpubs) in self.xpubs {
+            if signing_keychains.contains(&keychain_id) {
+                psbt.xpub.extend(xpubs);
+            }
+        }
+        for idx in 0..psbt.outputs.len() {
+            if let Some(descriptor) = self
+                .change_descriptors
+                .get(&psbt.unsigned_tx.output[idx].script_pubkey)
+            {
+                // Signers can still sign without the change key origins so the psbt stays usable
+                if let Err(err) = psbt.update_output_with_descriptor(idx, descriptor) {
+                    tracing::warn!(output = idx, "could not add change key origins: {}", err);
+                }
+            }
+        }
+    }
+}
+
 pub struct PsbtBuilder<T> {
     cfg: PsbtBuilderConfig,
     missing_cpfp_fees: HashMap<Txid, (Option<BatchId>, Satoshis)>,
@@ -127,6 +223,7 @@ pub struct PsbtBuilder<T> {
     result: FinishedPsbtBuild,
     input_weights: HashMap<OutPoint, usize>,
     all_included_utxos: HashSet<OutPoint>,
+    key_origins: KeyOrigins,
     _phantom: PhantomData<T>,
 }
 
@@ -192,6 +289,21 @@ impl<T> PsbtBuilder<T> {
                 .extend(keychain_utxos.keys().copied());
         }
 
+        if let Some(psbt) = ret.psbt.as_mut() {
+            let signing_keychains = ret
+                .wallet_totals
+                .values()
+                .flat_map(|total| {
+                    total
+                        .keychains_with_inputs
+                        .iter()
+                        .copied()
+                        .chain(std::iter::once(total.change_keychain_id))
+                })
+                .collect();
+            self.key_origins.enrich(psbt, &signing_keychains);
+        }
+
         ret
     }
 }
@@ -233,6 +345,7 @@ impl PsbtBuilder<InitialPsbtBuilderState> {
             current_wallet_cpfp_allocations: HashMap::new(),
             all_included_utxos: HashSet::new(),
             input_weights: HashMap::new(),
+            key_origins: KeyOrigins::default(),
             result: FinishedPsbtBuild {
                 included_payouts: HashMap::new(),
                 included_utxos: HashMap::new(),
@@ -264,6 +377,7 @@ impl PsbtBuilder<AcceptingWalletState> {
             current_wallet_cpfp_allocations: self.current_wallet_cpfp_allocations,
             all_included_utxos: self.all_included_utxos,
             input_weights: self.input_weights,
+            key_origins: self.key_origins,
             result: self.result,
             _phantom: PhantomData,
         }
@@ -280,7 +394,8 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingDeprecatedKeychainState> {
         keychain_id: KeychainId,
         wallet: &Wallet<D>,
     ) -> Result<Self, BdkError> {
-        if !self.cfg.consolidate_deprecated_keychains {
+        let pinned_utxos = self.cfg.pinned_utxos.get(&keychain_id);
+        if !self.cfg.consolidate_deprecated_keychains && pinned_utxos.is_none() {
             return Ok(self);
         }
 
@@ -301,25 +416,27 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingDeprecatedKeychainState> {
                 builder.add_unspendable(*out);
             }
         }
+        if let Some(pinned_utxos) = pinned_utxos {
+            builder.add_utxos(pinned_utxos)?;
+        }
+        if self.cfg.consolidate_deprecated_keychains {
+            builder.drain_wallet();
+        } else {
+            builder.manually_selected_only();
+        }
         builder
             .fee_rate(self.cfg.fee_rate)
             .sighash(DEFAULT_SIGHASH_TYPE.into())
-            .drain_wallet()
+            .enable_rbf()
             .drain_to(drain_address.script_pubkey());
-        match builder.finish() {
-            Ok((psbt, _details)) => {
-                for input in psbt.unsigned_tx.input.iter() {
-                    self.input_weights
-                        .insert(input.previous_output, keychain_satisfaction_weight);
-                }
-                self.current_wallet_psbts.push((keychain_id, psbt));
-                Ok(self)
-            }
-            Err(e) => {
-                dbg!(e);
-                unimplemented!()
-            }
+        let (psbt, _details) = builder.finish()?;
+        for input in psbt.unsigned_tx.input.iter() {
+            self.input_weights
+                .insert(input.previous_output, keychain_satisfaction_weight);
         }
+        self.key_origins.record_keychain(keychain_id, wallet);
+        self.current_wallet_psbts.push((keychain_id, psbt));
+        Ok(self)
     }
 }
 
@@ -334,6 +451,7 @@ impl PsbtBuilder<AcceptingDeprecatedKeychainState> {
             current_wallet_cpfp_allocations: self.current_wallet_cpfp_allocations,
             all_included_utxos: self.all_included_utxos,
             input_weights: self.input_weights,
+            key_origins: self.key_origins,
             result: self.result,
             _phantom: PhantomData,
         }
@@ -372,7 +490,23 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
             max_payout += 1;
             inputs = ins;
         }
-        if max_payout == 0 {
+        // A wallet without payouts only gets included when utxos have been pinned
+        // for its current keychain (ie. a consolidation into the change output)
+        if self.current_payouts.is_empty()
+            && self.cfg.pinned_utxos.contains_key(&current_keychain_id)
+        {
+            let (fee, ins, success) = self.try_build_current_wallet_psbt(
+                current_keychain_id,
+                &[],
+                wallet,
+                &change_address,
+            )?;
+            if !success {
+                return Ok(self);
+            }
+            absolute_fee = fee;
+            inputs = ins;
+        } else if max_payout == 0 {
             return Ok(self);
         }
 
@@ -385,6 +519,7 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
 
         builder.drain_to(change_address.script_pubkey());
         builder.sighash(DEFAULT_SIGHASH_TYPE.into());
+        builder.enable_rbf();
         builder.manually_selected_only();
         builder.add_utxos(&inputs)?;
 
@@ -401,6 +536,9 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
             }
         }
 
+        let excluded_pins = self
+            .cfg
+            .pins_of_excluded_payouts(&self.current_payouts[..max_payout]);
         let mut total_output_satoshis = Satoshis::from(0);
         for (payout_id, destination, satoshis) in self.current_payouts.drain(..max_payout) {
             total_output_satoshis += satoshis;
@@ -414,6 +552,9 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
 
         for (keychain_id, psbt) in self.current_wallet_psbts.drain(..) {
             for (input, psbt_input) in psbt.unsigned_tx.input.into_iter().zip(psbt.inputs) {
+                if excluded_pins.contains(&input.previous_output) {
+                    continue;
+                }
                 builder.add_foreign_utxo(
                     input.previous_output,
                     psbt_input,
@@ -460,75 +601,69 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
         }
 
         builder.ordering(TxOrdering::Bip69Lexicographic);
-        match builder.finish() {
-            Ok((psbt, details)) => {
-                let fee_satoshis = Satoshis::from(details.fee.expect("fee must be present"));
-                let current_wallet_fee = fee_satoshis - self.result.fee_satoshis;
-                let wallet_id = self.current_wallet.expect("current wallet must be set");
-                let change_satoshis = Satoshis::from(
-                    psbt.unsigned_tx
-                        .output
-                        .iter()
-                        .find(|out| out.script_pubkey == change_address.script_pubkey())
-                        .map(|out| out.value)
-                        .unwrap_or(0),
-                );
-                let mut cpfp_allocations = HashMap::new();
-                std::mem::swap(
-                    &mut cpfp_allocations,
-                    &mut self.current_wallet_cpfp_allocations,
-                );
-                let cpfp_fee_satoshis = cpfp_allocations
-                    .values()
-                    .flat_map(|tx_allocations| tx_allocations.values())
-                    .map(|(_, fee)| fee)
-                    .sum::<Satoshis>();
-                self.result.wallet_totals.insert(
-                    wallet_id,
-                    WalletTotals {
-                        wallet_id,
-                        keychains_with_inputs: Vec::new(),
-                        input_satoshis: total_output_satoshis
-                            + current_wallet_fee
-                            + change_satoshis,
-                        output_satoshis: total_output_satoshis,
-                        total_fee_satoshis: current_wallet_fee,
-                        cpfp_fee_satoshis,
-                        cpfp_allocations,
-                        change_satoshis,
-                        change_address,
-                        change_keychain_id: current_keychain_id,
-                        change_outpoint: None,
-                    },
+        let (psbt, details) = builder.finish()?;
+        let fee_satoshis = Satoshis::from(details.fee.expect("fee must be present"));
+        let current_wallet_fee = fee_satoshis - self.result.fee_satoshis;
+        let wallet_id = self.current_wallet.expect("current wallet must be set");
+        let change_satoshis = Satoshis::from(
+            psbt.unsigned_tx
+                .output
+                .iter()
+                .find(|out| out.script_pubkey == change_address.script_pubkey())
+                .map(|out| out.value)
+                .unwrap_or(0),
+        );
+        let mut cpfp_allocations = HashMap::new();
+        std::mem::swap(
+            &mut cpfp_allocations,
+            &mut self.current_wallet_cpfp_allocations,
+        );
+        let cpfp_fee_satoshis = cpfp_allocations
+            .values()
+            .flat_map(|tx_allocations| tx_allocations.values())
+            .map(|(_, fee)| fee)
+            .sum::<Satoshis>();
+        self.key_origins
+            .record_keychain(current_keychain_id, wallet);
+        self.key_origins.record_change(wallet, &change_address);
+        self.result.wallet_totals.insert(
+            wallet_id,
+            WalletTotals {
+                wallet_id,
+                keychains_with_inputs: Vec::new(),
+                input_satoshis: total_output_satoshis + current_wallet_fee + change_satoshis,
+                output_satoshis: total_output_satoshis,
+                total_fee_satoshis: current_wallet_fee,
+                cpfp_fee_satoshis,
+                cpfp_allocations,
+                change_satoshis,
+                change_address,
+                change_keychain_id: current_keychain_id,
+                change_outpoint: None,
+            },
+        );
+        self.result.fee_satoshis = fee_satoshis;
+
+        for input in psbt.unsigned_tx.input.iter() {
+            self.input_weights
+                .insert(input.previous_output, keychain_satisfaction_weight);
+            if self.all_included_utxos.insert(input.previous_output) {
+                self.result
+                    .included_utxos
+                    .entry(wallet_id)
+                    .or_default()
+                    .entry(current_keychain_id)
+                    .or_default()
+                    .push(input.previous_output);
+                self.result.included_wallet_keychains.insert(
+                    current_keychain_id,
+                    self.current_wallet.expect("current wallet shouyld be set"),
                 );
-                self.result.fee_satoshis = fee_satoshis;
-
-                for input in psbt.unsigned_tx.input.iter() {
-                    self.input_weights
-                        .insert(input.previous_output, keychain_satisfaction_weight);
-                    if self.all_included_utxos.insert(input.previous_output) {
-                        self.result
-                            .included_utxos
-                            .entry(wallet_id)
-                            .or_default()
-                            .entry(current_keychain_id)
-                            .or_default()
-                            .push(input.previous_output);
-                        self.result.included_wallet_keychains.insert(
-                            current_keychain_id,
-                            self.current_wallet.expect("current wallet shouyld be set"),
-                        );
-                    }
-                }
-                self.result.psbt = Some(psbt);
-                self.result.tx_id = Some(details.txid);
-                Ok(self)
-            }
-            Err(e) => {
-                dbg!(e);
-                unimplemented!()
             }
         }
+        self.result.psbt = Some(psbt);
+        self.result.tx_id = Some(details.txid);
+        Ok(self)
     }
 }
 
@@ -543,6 +678,7 @@ impl PsbtBuilder<AcceptingCurrentKeychainState> {
             current_wallet_cpfp_allocations: self.current_wallet_cpfp_allocations,
             all_included_utxos: self.all_included_utxos,
             input_weights: self.input_weights,
+            key_origins: self.key_origins,
             result: self.result,
             _phantom: PhantomData,
         }
@@ -561,6 +697,7 @@ impl PsbtBuilder<AcceptingCurrentKeychainState> {
     ) -> Result<(u64, Vec<OutPoint>, bool), BdkError> {
         let mut builder = wallet.build_tx();
         builder.fee_rate(self.cfg.fee_rate);
+        builder.enable_rbf();
         builder.drain_to(change_address.script_pubkey());
         if let Some(sats_with_jitter) = self.cfg.force_min_change_output_with_jitter() {
             builder.add_recipient(change_address.script_pubkey(), u64::from(sats_with_jitter));
@@ -575,6 +712,21 @@ impl PsbtBuilder<AcceptingCurrentKeychainState> {
                 builder.add_unspendable(*out);
             }
         }
+        let excluded_pins = self.cfg.pins_of_excluded_payouts(payouts);
+        for out in excluded_pins.iter() {
+            builder.add_unspendable(*out);
+        }
+
+        if let Some(pinned_utxos) = self.cfg.pinned_utxos.get(&keychain_id) {
+            let pinned_utxos: Vec<_> = pinned_utxos
+                .iter()
+                .filter(|out| !excluded_pins.contains(out))
+                .copied()
+                .collect();
+            if !pinned_utxos.is_empty() {
+                builder.add_utxos(&pinned_utxos)?;
+            }
+        }
 
         let mut cpfp_fees = 0;
         if let Some(cpfp) = self.cfg.cpfp_utxos.get(&keychain_id) {
@@ -596,6 +748,9 @@ impl PsbtBuilder<AcceptingCurrentKeychainState> {
         let mut foreign_utxos = HashSet::new();
         for (_, psbt) in self.current_wallet_psbts.iter() {
             for (input, psbt_input) in psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()) {
+                if excluded_pins.contains(&input.previous_output) {
+                    continue;
+                }
                 foreign_utxos.insert(input.previous_output);
                 builder.add_foreign_utxo(
                     input.previous_output,
@@ -1,8 +1,13 @@
-use bdk::bitcoin::psbt;
+use bdk::bitcoin::{
+    blockdata::script::Instruction,
+    psbt, secp256k1,
+    sighash::{EcdsaSighashType, TapSighashType},
+    ScriptBuf, TxIn,
+};
 
 use std::collections::HashSet;
 
-use crate::xpub::XPub;
+use crate::{primitives::Satoshis, signing_session::error::SigningSessionError, xpub::XPub};
 
 use super::error::WalletError;
 
@@ -19,6 +24,14 @@ pub fn validate_psbt(
             (fingerprint == &xpub.inner().parent_fingerprint).then_some(pk)
         })
         .collect();
+    let tap_set: HashSet<_> = signed_psbt
+        .inputs
+        .iter()
+        .flat_map(|inp| &inp.tap_key_origins)
+        .filter_map(|(pk, (_, (fingerprint, _)))| {
+            (fingerprint == &xpub.inner().parent_fingerprint).then_some(pk)
+        })
+        .collect();
 
     if unsigned_psbt.unsigned_tx != signed_psbt.unsigned_tx {
         return Err(WalletError::UnsignedTxnMismatch);
@@ -32,13 +45,210 @@ pub fn validate_psbt(
             .inputs
             .iter()
             .flat_map(|inp| &inp.partial_sigs)
-            .any(|(pk, _)| set.contains(&pk.inner)))
+            .any(|(pk, _)| set.contains(&pk.inner))
+        || signed_psbt.inputs.iter().any(|inp| {
+            inp.tap_key_sig.is_some()
+                && inp
+                    .tap_internal_key
+                    .map(|key| tap_set.contains(&key))
+                    .unwrap_or(false)
+        }))
     {
         return Err(WalletError::PsbtDoesNotHaveValidSignatures);
     }
     Ok(())
 }
 
+/// Strictly checks that a signed psbt still describes the batch it was created for:
+/// same inputs and outputs, untouched spent utxos, the expected fee and sighash ALL only.
+pub fn validate_signed_psbt_matches_batch(
+    signed_psbt: &psbt::PartiallySignedTransaction,
+    unsigned_psbt: &psbt::PartiallySignedTransaction,
+    expected_fee: Satoshis,
+) -> Result<(), SigningSessionError> {
+    let signed_tx = &signed_psbt.unsigned_tx;
+    let unsigned_tx = &unsigned_psbt.unsigned_tx;
+    if signed_tx.input.len() != unsigned_tx.input.len()
+        || signed_psbt.inputs.len() != unsigned_psbt.inputs.len()
+        || signed_tx
+            .input
+            .iter()
+            .zip(unsigned_tx.input.iter())
+            .any(|(signed, unsigned)| {
+                signed.previous_output != unsigned.previous_output
+                    || signed.sequence != unsigned.sequence
+            })
+    {
+        return Err(SigningSessionError::SignedPsbtInputsMismatch);
+    }
+    if signed_tx.output != unsigned_tx.output {
+        return Err(SigningSessionError::SignedPsbtOutputsMismatch);
+    }
+    if signed_tx != unsigned_tx {
+        return Err(SigningSessionError::SignedPsbtTransactionMismatch);
+    }
+
+    let mut total_in = 0;
+    for (idx, ((signed, unsigned), txin)) in signed_psbt
+        .inputs
+        .iter()
+        .zip(unsigned_psbt.inputs.iter())
+        .zip(unsigned_tx.input.iter())
+        .enumerate()
+    {
+        let expected_value = spent_value(unsigned, txin);
+        let signed_value = spent_value(signed, txin);
+        if signed_value.is_some() && signed_value != expected_value {
+            return Err(SigningSessionError::SignedPsbtInputUtxoMismatch(idx));
+        }
+        total_in += expected_value.unwrap_or_default();
+
+        if signed
+            .sighash_type
+            .map(|ty| !ty.taproot_hash_ty().map(is_sighash_all).unwrap_or(false))
+            .unwrap_or(false)
+            || signed
+                .partial_sigs
+                .values()
+                .any(|sig| sig.hash_ty != EcdsaSighashType::All)
+            || signed
+                .tap_key_sig
+                .as_ref()
+                .map(|sig| !is_sighash_all(sig.hash_ty))
+                .unwrap_or(false)
+            || signed
+                .tap_script_sigs
+                .values()
+                .any(|sig| !is_sighash_all(sig.hash_ty))
+            || !finalized_sighash_all(signed, txin)
+        {
+            return Err(SigningSessionError::SignedPsbtSighashNotAll(idx));
+        }
+    }
+
+    let total_out: u64 = unsigned_tx.output.iter().map(|out| out.value).sum();
+    let actual = Satoshis::from(total_in.saturating_sub(total_out));
+    if actual != expected_fee {
+        return Err(SigningSessionError::SignedPsbtFeeMismatch {
+            expected: expected_fee,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+fn is_sighash_all(ty: TapSighashType) -> bool {
+    matches!(ty, TapSighashType::Default | TapSighashType::All)
+}
+
+/// Finalized inputs only carry their signatures inside the witness or script sig,
+/// where the sighash type is the trailing byte of each signature.
+fn finalized_sighash_all(input: &psbt::Input, txin: &TxIn) -> bool {
+    if let Some(witness) = &input.final_script_witness {
+        let mut elements: Vec<_> = witness.iter().collect();
+        let is_taproot = spent_script(input, txin)
+            .map(|script| script.is_v1_p2tr())
+            .unwrap_or(false);
+        if is_taproot {
+            if elements.len() > 1 && elements.last().and_then(|e| e.first()) == Some(&0x50) {
+                elements.pop();
+            }
+            // A key path spend is a lone signature, a script path spend ends with
+            // the script and control block
+            let n_stack = if elements.len() == 1 {
+                1
+            } else {
+                elements.len().saturating_sub(2)
+            };
+            if elements[..n_stack]
+                .iter()
+                .any(|e| e.len() == 65 && e[64] != TapSighashType::All as u8)
+            {
+                return false;
+            }
+        } else if !elements.into_iter().all(ecdsa_sighash_all) {
+            return false;
+        }
+    }
+    if let Some(script_sig) = &input.final_script_sig {
+        return script_sig
+            .instructions()
+            .all(|instruction| match instruction {
+                Ok(Instruction::PushBytes(bytes)) => ecdsa_sighash_all(bytes.as_bytes()),
+                _ => true,
+            });
+    }
+    true
+}
+
+fn ecdsa_sighash_all(element: &[u8]) -> bool {
+    match element.split_last() {
+        Some((hash_ty, sig)) if secp256k1::ecdsa::Signature::from_der(sig).is_ok() => {
+            *hash_ty == EcdsaSighashType::All as u8
+        }
+        _ => true,
+    }
+}
+
+fn spent_script(input: &psbt::Input, txin: &TxIn) -> Option<ScriptBuf> {
+    input
+        .witness_utxo
+        .as_ref()
+        .map(|utxo| utxo.script_pubkey.clone())
+        .or_else(|| {
+            input
+                .non_witness_utxo
+                .as_ref()
+                .and_then(|tx| tx.output.get(txin.previous_output.vout as usize))
+                .map(|out| out.script_pubkey.clone())
+        })
+}
+
+fn spent_value(input: &psbt::Input, txin: &TxIn) -> Option<u64> {
+    input
+        .witness_utxo
+        .as_ref()
+        .map(|utxo| utxo.value)
+        .or_else(|| {
+            input
+                .non_witness_utxo
+                .as_ref()
+                .and_then(|tx| tx.output.get(txin.previous_output.vout as usize))
+                .map(|out| out.value)
+        })
+}
+
+/// Checks that an unsigned psbt carries what external signers need to verify it:
+/// key origins on every input and a global xpub for every origin referenced.
+pub fn validate_key_origins(psbt: &psbt::PartiallySignedTransaction) -> Result<(), WalletError> {
+    let global_fingerprints: HashSet<_> = psbt
+        .xpub
+        .values()
+        .map(|(fingerprint, _)| fingerprint)
+        .collect();
+    for (idx, input) in psbt.inputs.iter().enumerate() {
+        if input.bip32_derivation.is_empty() && input.tap_key_origins.is_empty() {
+            return Err(WalletError::PsbtInputMissingKeyOrigins(idx));
+        }
+        let fingerprints = input
+            .bip32_derivation
+            .values()
+            .map(|(fingerprint, _)| fingerprint)
+            .chain(
+                input
+                    .tap_key_origins
+                    .values()
+                    .map(|(_, (fingerprint, _))| fingerprint),
+            );
+        for fingerprint in fingerprints {
+            if !global_fingerprints.contains(fingerprint) {
+                return Err(WalletError::PsbtMissingGlobalXPub(*fingerprint));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +282,86 @@ mod tests {
             Err(WalletError::PsbtDoesNotHaveValidSignatures)
         ))
     }
+
+    #[test]
+    fn key_origins_require_global_xpubs() {
+        let mut psbt = "cHNidP8BAH0BAAAAASNihqnLFfz7pHt1zDeB/iB7ku75Ah6EFaFhQZnbErt9AAAAAAD+////Ap13fQEAAAAAIgAgO37beKyitaViJwyjZ3oTIwdBU0JTbBRa32V1zvdifQzAaHgEAAAAABYAFFPOvhKDbGzCHM0LNEHgSPJjuf7RzQAAAAABAPYCAAAAAAEBTEYh+JWYBjbSBgwY+QxYOE25/vFk5zdS61jKtc1HJjYAAAAAAP3///8CAOH1BQAAAAAiACCOipWPCjso1EpZQctqUeF6N4QjTNQ3c+15axzGinwSVhwEECQBAAAAIlEgzmi+Ha7O7p08hrHEzLrq68MJlSDW40V39kbqS+ArmTMCRzBEAiB5fcQ8lx7fp+Calgy7o9jQEsHEPho0zfP13TQsCC2/GgIgSL/zyp0nz5PzdMXxhgBJ59O2t7tUhAfKxBYtVjMYXR0BIQN39pz1kuRtgfVu5SMba1rXL5HXDIKq4/rq7I/342+/GsgAAAABASsA4fUFAAAAACIAII6KlY8KOyjUSllBy2pR4Xo3hCNM1Ddz7XlrHMaKfBJWAQMEAQAAAAEFR1EhAlBn4VwHril4Da/2rGzF/FZnM0gnAi5M7A7iHMxMA4tIIQKXjwOvzjfb1Y0HMvH2Bc2Eqtukx+dxd4V8qOus23qGhlKuIgYCUGfhXAeuKXgNr/asbMX8VmczSCcCLkzsDuIczEwDi0gcmFPdqTAAAIABAACAAAAAgAIAAIAAAAAAAAAAACIGApePA6/ON9vVjQcy8fYFzYSq26TH53F3hXyo66zbeoaGHB3opBwwAACAAQAAgAAAAIACAACAAAAAAAAAAAAAAQFHUSECXDnAvMuAqtaBxRvWWRK4cOeJCmnxrHmzX7Ys+TOgLkMhAsl2+NBf0WNXB5Dyu/j0+luIVYCV+21GR7hPI2AUvisiUq4iAgJcOcC8y4Cq1oHFG9ZZErhw54kKafGsebNftiz5M6AuQxyYU92pMAAAgAEAAIAAAACAAgAAgAEAAAAAAAAAIgICyXb40F/RY1cHkPK7+PT6W4hVgJX7bUZHuE8jYBS+KyIcHeikHDAAAIABAACAAAAAgAIAAIABAAAAAAAAAAAA".parse::<psbt::PartiallySignedTransaction>().unwrap();
+        assert!(matches!(
+            validate_key_origins(&psbt),
+            Err(WalletError::PsbtMissingGlobalXPub(_))
+        ));
+
+        let xpubs = [
+            XPub::try_from(("tpubDE8HT914zGpxhJhgoMX35xgNyjHy5d1neGXHjTLAtuUssTA7tNWNs177JsFPbJwD5FBXCHJYbwUC9AzSEpYHC4hKgaCvZyZTuCbWfNUWXoM", Some("m/48h/1h/0h/2h"))).unwrap(),
+            XPub::try_from(("tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4", Some("m/84'/0'/0'"))).unwrap(),
+        ];
+        let origins: Vec<_> = psbt.inputs[0].bip32_derivation.values().cloned().collect();
+        for (xpub, origin) in xpubs.iter().zip(origins) {
+            psbt.xpub.insert(*xpub.inner(), origin);
+        }
+        assert!(validate_key_origins(&psbt).is_ok());
+
+        psbt.inputs[0].bip32_derivation.clear();
+        assert!(matches!(
+            validate_key_origins(&psbt),
+            Err(WalletError::PsbtInputMissingKeyOrigins(0))
+        ));
+    }
+
+    #[test]
+    fn signed_psbt_must_match_batch() {
+        let unsigned_psbt = "cHNidP8BAH0BAAAAASNihqnLFfz7pHt1zDeB/iB7ku75Ah6EFaFhQZnbErt9AAAAAAD+////Ap13fQEAAAAAIgAgO37beKyitaViJwyjZ3oTIwdBU0JTbBRa32V1zvdifQzAaHgEAAAAABYAFFPOvhKDbGzCHM0LNEHgSPJjuf7RzQAAAAABAPYCAAAAAAEBTEYh+JWYBjbSBgwY+QxYOE25/vFk5zdS61jKtc1HJjYAAAAAAP3///8CAOH1BQAAAAAiACCOipWPCjso1EpZQctqUeF6N4QjTNQ3c+15axzGinwSVhwEECQBAAAAIlEgzmi+Ha7O7p08hrHEzLrq68MJlSDW40V39kbqS+ArmTMCRzBEAiB5fcQ8lx7fp+Calgy7o9jQEsHEPho0zfP13TQsCC2/GgIgSL/zyp0nz5PzdMXxhgBJ59O2t7tUhAfKxBYtVjMYXR0BIQN39pz1kuRtgfVu5SMba1rXL5HXDIKq4/rq7I/342+/GsgAAAABASsA4fUFAAAAACIAII6KlY8KOyjUSllBy2pR4Xo3hCNM1Ddz7XlrHMaKfBJWAQMEAQAAAAEFR1EhAlBn4VwHril4Da/2rGzF/FZnM0gnAi5M7A7iHMxMA4tIIQKXjwOvzjfb1Y0HMvH2Bc2Eqtukx+dxd4V8qOus23qGhlKuIgYCUGfhXAeuKXgNr/asbMX8VmczSCcCLkzsDuIczEwDi0gcmFPdqTAAAIABAACAAAAAgAIAAIAAAAAAAAAAACIGApePA6/ON9vVjQcy8fYFzYSq26TH53F3hXyo66zbeoaGHB3opBwwAACAAQAAgAAAAIACAACAAAAAAAAAAAAAAQFHUSECXDnAvMuAqtaBxRvWWRK4cOeJCmnxrHmzX7Ys+TOgLkMhAsl2+NBf0WNXB5Dyu/j0+luIVYCV+21GR7hPI2AUvisiUq4iAgJcOcC8y4Cq1oHFG9ZZErhw54kKafGsebNftiz5M6AuQxyYU92pMAAAgAEAAIAAAACAAgAAgAEAAAAAAAAAIgICyXb40F/RY1cHkPK7+PT6W4hVgJX7bUZHuE8jYBS+KyIcHeikHDAAAIABAACAAAAAgAIAAIABAAAAAAAAAAAA".parse::<psbt::PartiallySignedTransaction>().unwrap();
+        let signed_psbt = "cHNidP8BAH0BAAAAASNihqnLFfz7pHt1zDeB/iB7ku75Ah6EFaFhQZnbErt9AAAAAAD+////Ap13fQEAAAAAIgAgO37beKyitaViJwyjZ3oTIwdBU0JTbBRa32V1zvdifQzAaHgEAAAAABYAFFPOvhKDbGzCHM0LNEHgSPJjuf7RzQAAAAABAIkCAAAAAUxGIfiVmAY20gYMGPkMWDhNuf7xZOc3UutYyrXNRyY2AAAAAAD9////AgDh9QUAAAAAIgAgjoqVjwo7KNRKWUHLalHhejeEI0zUN3PteWscxop8ElYcBBAkAQAAACJRIM5ovh2uzu6dPIaxxMy66uvDCZUg1uNFd/ZG6kvgK5kzyAAAAAEBKwDh9QUAAAAAIgAgjoqVjwo7KNRKWUHLalHhejeEI0zUN3PteWscxop8ElYBCJIDAEcwRAIgGZdgjGq/M/51nE9WtP69BZBhQtho22JcoIQHSWEXI00CIA7/Mj5A906MFjd+sm+EawhjTALyR5jsPyT6Qa7TEJQDAUdRIQJQZ+FcB64peA2v9qxsxfxWZzNIJwIuTOwO4hzMTAOLSCECl48Dr84329WNBzLx9gXNhKrbpMfncXeFfKjrrNt6hoZSrgABAUdRIQJcOcC8y4Cq1oHFG9ZZErhw54kKafGsebNftiz5M6AuQyECyXb40F/RY1cHkPK7+PT6W4hVgJX7bUZHuE8jYBS+KyJSriICAlw5wLzLgKrWgcUb1lkSuHDniQpp8ax5s1+2LPkzoC5DHJhT3akwAACAAQAAgAAAAIACAACAAQAAAAAAAAAiAgLJdvjQX9FjVweQ8rv49PpbiFWAlfttRke4TyNgFL4rIhwd6KQcMAAAgAEAAIAAAACAAgAAgAEAAAAAAAAAAAA=".parse::<psbt::PartiallySignedTransaction>().unwrap();
+        let fee = Satoshis::from(163u64);
+        assert!(validate_signed_psbt_matches_batch(&signed_psbt, &unsigned_psbt, fee).is_ok());
+        assert!(matches!(
+            validate_signed_psbt_matches_batch(
+                &signed_psbt,
+                &unsigned_psbt,
+                Satoshis::from(164u64)
+            ),
+            Err(SigningSessionError::SignedPsbtFeeMismatch { .. })
+        ));
+
+        let mut altered = signed_psbt.clone();
+        altered.unsigned_tx.output[0].value -= 1;
+        assert!(matches!(
+            validate_signed_psbt_matches_batch(&altered, &unsigned_psbt, fee),
+            Err(SigningSessionError::SignedPsbtOutputsMismatch)
+        ));
+
+        let mut altered = signed_psbt.clone();
+        altered.inputs[0].witness_utxo.as_mut().unwrap().value += 1;
+        assert!(matches!(
+            validate_signed_psbt_matches_batch(&altered, &unsigned_psbt, fee),
+            Err(SigningSessionError::SignedPsbtInputUtxoMismatch(0))
+        ));
+
+        let mut altered = signed_psbt.clone();
+        altered.inputs[0].sighash_type = Some(EcdsaSighashType::SinglePlusAnyoneCanPay.into());
+        assert!(matches!(
+            validate_signed_psbt_matches_batch(&altered, &unsigned_psbt, fee),
+            Err(SigningSessionError::SignedPsbtSighashNotAll(0))
+        ));
+
+        // The signature in the finalized witness commits to SINGLE|ANYONECANPAY
+        let mut altered = signed_psbt;
+        let mut witness = altered.inputs[0]
+            .final_script_witness
+            .as_ref()
+            .unwrap()
+            .to_vec();
+        let signature = witness
+            .iter_mut()
+            .find(|e| {
+                secp256k1::ecdsa::Signature::from_der(&e[..e.len().saturating_sub(1)]).is_ok()
+            })
+            .unwrap();
+        *signature.last_mut().unwrap() = 0x83;
+        altered.inputs[0].final_script_witness = Some(bdk::bitcoin::Witness::from_slice(&witness));
+        assert!(matches!(
+            validate_signed_psbt_matches_batch(&altered, &unsigned_psbt, fee),
+            Err(SigningSessionError::SignedPsbtSighashNotAll(0))
+        ));
+    }
 }
@@ -73,6 +73,30 @@ impl Wallets {
         Ok(wallet)
     }
 
+    pub async fn record_synced(&self, wallet_id: WalletId) -> Result<(), WalletError> {
+        sqlx::query!(
+            r#"INSERT INTO bria_wallet_sync_times (wallet_id, last_synced_at)
+               VALUES ($1, NOW())
+               ON CONFLICT (wallet_id) DO UPDATE SET last_synced_at = NOW()"#,
+            wallet_id as WalletId,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn last_synced_at(
+        &self,
+    ) -> Result<HashMap<WalletId, chrono::DateTime<chrono::Utc>>, WalletError> {
+        let rows = sqlx::query!(r#"SELECT wallet_id, last_synced_at FROM bria_wallet_sync_times"#)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (WalletId::from(row.wallet_id), row.last_synced_at))
+            .collect())
+    }
+
     pub async fn all_ids(
         &self,
     ) -> Result<impl Iterator<Item = (AccountId, WalletId)>, WalletError> {
@@ -0,0 +1,139 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::instrument;
+
+use std::{sync::Arc, time::Duration};
+
+use super::{
+    delivery::WebhookDelivery,
+    entity::{resolve_public, validate_destination, WebhookEndpoint},
+    error::WebhookError,
+};
+
+pub const SIGNATURE_HEADER: &str = "x-bria-signature";
+pub const TIMESTAMP_HEADER: &str = "x-bria-timestamp";
+pub const SEQUENCE_HEADER: &str = "x-bria-event-sequence";
+pub const ENDPOINT_ID_HEADER: &str = "x-bria-webhook-endpoint-id";
+
+#[derive(Clone)]
+pub struct WebhookClient {
+    client: reqwest::Client,
+    allowed_private_hosts: Vec<String>,
+}
+
+impl WebhookClient {
+    pub fn new(timeout: Duration, allowed_private_hosts: Vec<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .redirect(reqwest::redirect::Policy::none())
+                .dns_resolver(Arc::new(PublicOnlyResolver {
+                    allowed_private_hosts: allowed_private_hosts.clone(),
+                }))
+                .build()
+                .expect("Couldn't build webhook http client"),
+            allowed_private_hosts,
+        }
+    }
+
+    #[instrument(name = "webhook_client.deliver", skip_all, fields(url = %endpoint.url, sequence = %delivery.sequence, status), err)]
+    pub async fn deliver(
+        &self,
+        endpoint: &WebhookEndpoint,
+        secret: &str,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), WebhookError> {
+        validate_destination(&endpoint.url, &self.allowed_private_hosts).await?;
+        let body = serde_json::to_string(&delivery.event)?;
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = sign_payload(secret, timestamp, &body);
+        let response = self
+            .client
+            .post(&endpoint.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, format!("sha256={signature}"))
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SEQUENCE_HEADER, delivery.sequence.to_string())
+            .header(ENDPOINT_ID_HEADER, endpoint.id.to_string())
+            .body(body)
+            .send()
+            .await?;
+        let status = response.status();
+        tracing::Span::current().record("status", status.as_u16());
+        if !status.is_success() {
+            return Err(WebhookError::UnexpectedResponseStatus(status.as_u16()));
+        }
+        Ok(())
+    }
+}
+
+/// Connects only to the addresses that passed the public address check, so a host can't
+/// resolve to a public address for `validate_destination` and a private one for the request.
+struct PublicOnlyResolver {
+    allowed_private_hosts: Vec<String>,
+}
+
+impl reqwest::dns::Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_owned();
+        let allow_private = self.allowed_private_hosts.contains(&host);
+        Box::pin(async move {
+            // reqwest replaces the port with the one from the url
+            let addrs = if allow_private {
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect()
+            } else {
+                resolve_public(&host, 0).await?
+            };
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Hex encoded HMAC-SHA256 over `<timestamp>.<body>` keyed with the endpoint secret.
+/// Receivers should recompute it and reject stale timestamps to prevent replays.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature() {
+        assert_eq!(
+            sign_payload("whsec_test", 1700000000, r#"{"sequence":1}"#),
+            "506b0af5c21bf33a1429cd4e619e008b729a2a89f256c29a1a834a3dfac6ae79"
+        );
+        assert_ne!(
+            sign_payload("whsec_test", 1700000001, r#"{"sequence":1}"#),
+            sign_payload("whsec_test", 1700000000, r#"{"sequence":1}"#),
+        );
+    }
+
+    #[tokio::test]
+    async fn resolver_rejects_private_addresses() {
+        use reqwest::dns::Resolve;
+
+        let resolver = PublicOnlyResolver {
+            allowed_private_hosts: vec![],
+        };
+        assert!(resolver
+            .resolve("localhost".parse().unwrap())
+            .await
+            .is_err());
+        let resolver = PublicOnlyResolver {
+            allowed_private_hosts: vec!["localhost".to_string()],
+        };
+        assert!(resolver
+            .resolve("localhost".parse().unwrap())
+            .await
+            .unwrap()
+            .all(|addr| addr.ip().is_loopback()));
+    }
+}
@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::error::WebhookError;
+use crate::{outbox::*, primitives::*};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, clap::ValueEnum,
+)]
+#[sqlx(type_name = "WebhookDeliveryStatus", rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    DeadLetter,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub webhook_endpoint_id: WebhookEndpointId,
+    pub account_id: AccountId,
+    pub sequence: EventSequence,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub event: serde_json::Value,
+    pub delivered_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub modified_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Tracks, per endpoint, which outbox events have been picked up for delivery
+/// and the delivery status of each of them keyed by `EventSequence`.
+#[derive(Clone)]
+pub struct WebhookDeliveries {
+    pool: Pool<Postgres>,
+}
+
+impl WebhookDeliveries {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn init_cursor(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        account_id: AccountId,
+        webhook_endpoint_id: WebhookEndpointId,
+        start_after: EventSequence,
+    ) -> Result<(), WebhookError> {
+        sqlx::query!(
+            r#"INSERT INTO bria_webhook_cursors (webhook_endpoint_id, account_id, sequence)
+               VALUES ($1, $2, $3)"#,
+            Uuid::from(webhook_endpoint_id),
+            Uuid::from(account_id),
+            start_after as EventSequence,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns `None` if another dispatcher is currently holding the cursor.
+    pub async fn lock_cursor(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        webhook_endpoint_id: WebhookEndpointId,
+    ) -> Result<Option<EventSequence>, WebhookError> {
+        let row = sqlx::query!(
+            r#"SELECT sequence AS "sequence: EventSequence"
+               FROM bria_webhook_cursors
+               WHERE webhook_endpoint_id = $1
+               FOR UPDATE SKIP LOCKED"#,
+            Uuid::from(webhook_endpoint_id),
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+        Ok(row.map(|row| row.sequence))
+    }
+
+    pub async fn enqueue(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        webhook_endpoint_id: WebhookEndpointId,
+        events: &[OutboxEvent<WithoutAugmentation>],
+    ) -> Result<Vec<EventSequence>, WebhookError> {
+        let last = match events.last() {
+            Some(event) => event.sequence,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            r#"INSERT INTO bria_webhook_deliveries
+            (webhook_endpoint_id, account_id, sequence, event)"#,
+        );
+        let mut values = Vec::with_capacity(events.len());
+        for event in events {
+            values.push((event, serde_json::to_value(event)?));
+        }
+        query_builder.push_values(values, |mut builder, (event, json)| {
+            builder.push_bind(Uuid::from(webhook_endpoint_id));
+            builder.push_bind(event.account_id);
+            builder.push_bind(event.sequence);
+            builder.push_bind(json);
+        });
+        query_builder.push(" ON CONFLICT DO NOTHING");
+        query_builder.build().execute(&mut **tx).await?;
+
+        sqlx::query!(
+            r#"UPDATE bria_webhook_cursors
+               SET sequence = $2, modified_at = NOW()
+               WHERE webhook_endpoint_id = $1"#,
+            Uuid::from(webhook_endpoint_id),
+            last as EventSequence,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(events.iter().map(|event| event.sequence).collect())
+    }
+
+    /// Bumps the attempt counter of a pending delivery.
+    /// Returns `None` if the delivery is no longer pending.
+    pub async fn record_attempt(
+        &self,
+        webhook_endpoint_id: WebhookEndpointId,
+        sequence: EventSequence,
+    ) -> Result<Option<WebhookDelivery>, WebhookError> {
+        let row = sqlx::query!(
+            r#"UPDATE bria_webhook_deliveries
+               SET attempts = attempts + 1, modified_at = NOW()
+               WHERE webhook_endpoint_id = $1 AND sequence = $2 AND status = 'pending'
+               RETURNING webhook_endpoint_id, account_id, sequence AS "sequence: EventSequence",
+                 status AS "status: WebhookDeliveryStatus", attempts, last_error, event,
+                 delivered_at, created_at, modified_at"#,
+            Uuid::from(webhook_endpoint_id),
+            sequence as EventSequence,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|row| WebhookDelivery {
+            webhook_endpoint_id: WebhookEndpointId::from(row.webhook_endpoint_id),
+            account_id: AccountId::from(row.account_id),
+            sequence: row.sequence,
+            status: row.status,
+            attempts: row.attempts as u32,
+            last_error: row.last_error,
+            event: row.event,
+            delivered_at: row.delivered_at,
+            created_at: row.created_at,
+            modified_at: row.modified_at,
+        }))
+    }
+
+    pub async fn mark_delivered(
+        &self,
+        webhook_endpoint_id: WebhookEndpointId,
+        sequence: EventSequence,
+    ) -> Result<(), WebhookError> {
+        sqlx::query!(
+            r#"UPDATE bria_webhook_deliveries
+               SET status = 'delivered', last_error = NULL, delivered_at = NOW(), modified_at = NOW()
+               WHERE webhook_endpoint_id = $1 AND sequence = $2"#,
+            Uuid::from(webhook_endpoint_id),
+            sequence as EventSequence,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_failure(
+        &self,
+        webhook_endpoint_id: WebhookEndpointId,
+        sequence: EventSequence,
+        error: String,
+        dead_letter: bool,
+    ) -> Result<(), WebhookError> {
+        let status = if dead_letter {
+            WebhookDeliveryStatus::DeadLetter
+        } else {
+            WebhookDeliveryStatus::Pending
+        };
+        sqlx::query!(
+            r#"UPDATE bria_webhook_deliveries
+               SET status = $3, last_error = $4, modified_at = NOW()
+               WHERE webhook_endpoint_id = $1 AND sequence = $2"#,
+            Uuid::from(webhook_endpoint_id),
+            sequence as EventSequence,
+            status as WebhookDeliveryStatus,
+            error,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Puts a delivered or dead lettered delivery back into the pending state
+    /// so that a fresh delivery job can pick it up.
+    pub async fn reset_for_redelivery(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        webhook_endpoint_id: WebhookEndpointId,
+        sequence: EventSequence,
+    ) -> Result<(), WebhookError> {
+        let row = sqlx::query!(
+            r#"SELECT status AS "status: WebhookDeliveryStatus"
+               FROM bria_webhook_deliveries
+               WHERE webhook_endpoint_id = $1 AND sequence = $2
+               FOR UPDATE"#,
+            Uuid::from(webhook_endpoint_id),
+            sequence as EventSequence,
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+        match row.map(|row| row.status) {
+            None => {
+                return Err(WebhookError::DeliveryNotFound(
+                    webhook_endpoint_id,
+                    u64::from(sequence),
+                ))
+            }
+            Some(WebhookDeliveryStatus::Pending) => {
+                return Err(WebhookError::DeliveryPending(
+                    webhook_endpoint_id,
+                    u64::from(sequence),
+                ))
+            }
+            Some(_) => (),
+        }
+        sqlx::query!(
+            r#"UPDATE bria_webhook_deliveries
+               SET status = 'pending', attempts = 0, last_error = NULL, delivered_at = NULL, modified_at = NOW()
+               WHERE webhook_endpoint_id = $1 AND sequence = $2"#,
+            Uuid::from(webhook_endpoint_id),
+            sequence as EventSequence,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_for_endpoint(
+        &self,
+        webhook_endpoint_id: WebhookEndpointId,
+        status: Option<WebhookDeliveryStatus>,
+        limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        let rows = sqlx::query!(
+            r#"SELECT webhook_endpoint_id, account_id, sequence AS "sequence: EventSequence",
+                 status AS "status: WebhookDeliveryStatus", attempts, last_error, event,
+                 delivered_at, created_at, modified_at
+               FROM bria_webhook_deliveries
+               WHERE webhook_endpoint_id = $1
+                 AND ($2::WebhookDeliveryStatus IS NULL OR status = $2)
+               ORDER BY sequence DESC
+               LIMIT $3"#,
+            Uuid::from(webhook_endpoint_id),
+            status as Option<WebhookDeliveryStatus>,
+            limit as i64,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| WebhookDelivery {
+                webhook_endpoint_id: WebhookEndpointId::from(row.webhook_endpoint_id),
+                account_id: AccountId::from(row.account_id),
+                sequence: row.sequence,
+                status: row.status,
+                attempts: row.attempts as u32,
+                last_error: row.last_error,
+                event: row.event,
+                delivered_at: row.delivered_at,
+                created_at: row.created_at,
+                modified_at: row.modified_at,
+            })
+            .collect())
+    }
+}
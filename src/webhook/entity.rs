@@ -0,0 +1,342 @@
+use derive_builder::Builder;
+use es_entity::*;
+use rand::distributions::{Alphanumeric, DistString};
+use serde::{Deserialize, Serialize};
+
+use crate::{primitives::*, xpub::EncryptionKey};
+
+use super::{error::WebhookError, secret::EncryptedWebhookSecret};
+
+const SECRET_PREFIX: &str = "whsec_";
+
+#[derive(EsEvent, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[es_event(id = "WebhookEndpointId")]
+pub enum WebhookEndpointEvent {
+    Initialized {
+        id: WebhookEndpointId,
+        account_id: AccountId,
+    },
+    UrlUpdated {
+        url: String,
+    },
+    SecretRotated {
+        encrypted_secret: EncryptedWebhookSecret,
+    },
+    Enabled,
+    Disabled,
+    Deleted,
+}
+
+#[derive(EsEntity, Builder)]
+#[builder(pattern = "owned", build_fn(error = "EsEntityError"))]
+pub struct WebhookEndpoint {
+    pub id: WebhookEndpointId,
+    pub account_id: AccountId,
+    pub url: String,
+    pub(super) encrypted_secret: EncryptedWebhookSecret,
+    #[builder(default = "true")]
+    pub enabled: bool,
+    #[builder(default)]
+    pub deleted: bool,
+    pub(super) events: EntityEvents<WebhookEndpointEvent>,
+}
+
+impl WebhookEndpoint {
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.deleted
+    }
+
+    pub fn secret(&self, key: &EncryptionKey) -> Result<String, WebhookError> {
+        self.encrypted_secret.decrypt(key)
+    }
+
+    pub fn update_url(&mut self, url: String) -> Result<(), WebhookError> {
+        validate_url(&url)?;
+        if self.url != url {
+            self.url = url.clone();
+            self.events.push(WebhookEndpointEvent::UrlUpdated { url });
+        }
+        Ok(())
+    }
+
+    pub fn rotate_secret(&mut self, key: &EncryptionKey) -> String {
+        let secret = generate_secret();
+        self.set_secret(&secret, key);
+        secret
+    }
+
+    /// Re-encrypts the secret under `new_key`, returns false if it wasn't encrypted with `old_key`.
+    pub fn reencrypt_secret(&mut self, old_key: &EncryptionKey, new_key: &EncryptionKey) -> bool {
+        match self.encrypted_secret.decrypt(old_key) {
+            Ok(secret) => {
+                self.set_secret(&secret, new_key);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn set_secret(&mut self, secret: &str, key: &EncryptionKey) {
+        let encrypted_secret = EncryptedWebhookSecret::encrypt(secret, key);
+        self.encrypted_secret = encrypted_secret.clone();
+        self.events
+            .push(WebhookEndpointEvent::SecretRotated { encrypted_secret });
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled == enabled {
+            return;
+        }
+        self.enabled = enabled;
+        if enabled {
+            self.events.push(WebhookEndpointEvent::Enabled);
+        } else {
+            self.events.push(WebhookEndpointEvent::Disabled);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if !self.deleted {
+            self.deleted = true;
+            self.events.push(WebhookEndpointEvent::Deleted);
+        }
+    }
+}
+
+impl TryFromEvents<WebhookEndpointEvent> for WebhookEndpoint {
+    fn try_from_events(events: EntityEvents<WebhookEndpointEvent>) -> Result<Self, EsEntityError> {
+        let mut builder = WebhookEndpointBuilder::default();
+        for event in events.iter_all() {
+            match event {
+                WebhookEndpointEvent::Initialized { id, account_id } => {
+                    builder = builder.id(*id).account_id(*account_id);
+                }
+                WebhookEndpointEvent::UrlUpdated { url } => {
+                    builder = builder.url(url.clone());
+                }
+                WebhookEndpointEvent::SecretRotated { encrypted_secret } => {
+                    builder = builder.encrypted_secret(encrypted_secret.clone());
+                }
+                WebhookEndpointEvent::Enabled => {
+                    builder = builder.enabled(true);
+                }
+                WebhookEndpointEvent::Disabled => {
+                    builder = builder.enabled(false);
+                }
+                WebhookEndpointEvent::Deleted => {
+                    builder = builder.deleted(true);
+                }
+            }
+        }
+        builder.events(events).build()
+    }
+}
+
+#[derive(Builder)]
+pub struct NewWebhookEndpoint {
+    #[builder(setter(into))]
+    pub(super) id: WebhookEndpointId,
+    pub(super) account_id: AccountId,
+    #[builder(setter(into))]
+    pub(super) url: String,
+    #[builder(default = "generate_secret()")]
+    pub(super) secret: String,
+    pub(super) encryption_key: EncryptionKey,
+}
+
+impl NewWebhookEndpoint {
+    pub fn builder() -> NewWebhookEndpointBuilder {
+        let mut builder = NewWebhookEndpointBuilder::default();
+        builder.id(WebhookEndpointId::new());
+        builder
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+}
+
+impl std::fmt::Debug for NewWebhookEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NewWebhookEndpoint")
+            .field("id", &self.id)
+            .field("account_id", &self.account_id)
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IntoEvents<WebhookEndpointEvent> for NewWebhookEndpoint {
+    fn into_events(self) -> EntityEvents<WebhookEndpointEvent> {
+        EntityEvents::init(
+            self.id,
+            vec![
+                WebhookEndpointEvent::Initialized {
+                    id: self.id,
+                    account_id: self.account_id,
+                },
+                WebhookEndpointEvent::UrlUpdated { url: self.url },
+                WebhookEndpointEvent::SecretRotated {
+                    encrypted_secret: EncryptedWebhookSecret::encrypt(
+                        &self.secret,
+                        &self.encryption_key,
+                    ),
+                },
+            ],
+        )
+    }
+}
+
+pub fn validate_url(url: &str) -> Result<(), WebhookError> {
+    let parsed = url::Url::parse(url).map_err(|e| WebhookError::InvalidUrl(e.to_string()))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(()),
+        scheme => Err(WebhookError::InvalidUrl(format!(
+            "unsupported scheme '{scheme}'"
+        ))),
+    }
+}
+
+/// Resolves the url's host and rejects it unless every address it resolves to is publicly
+/// routable, so endpoints can't be pointed at bria's own network. Hosts in
+/// `allowed_private_hosts` skip the check.
+pub async fn validate_destination(
+    url: &str,
+    allowed_private_hosts: &[String],
+) -> Result<(), WebhookError> {
+    validate_url(url)?;
+    let parsed = url::Url::parse(url).map_err(|e| WebhookError::InvalidUrl(e.to_string()))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| WebhookError::InvalidUrl("missing host".to_string()))?;
+    if allowed_private_hosts.iter().any(|allowed| allowed == host) {
+        return Ok(());
+    }
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    resolve_public(host, port).await?;
+    Ok(())
+}
+
+/// Resolves `host` and returns its addresses only if every one of them is publicly routable.
+pub(super) async fn resolve_public(
+    host: &str,
+    port: u16,
+) -> Result<Vec<std::net::SocketAddr>, WebhookError> {
+    let addrs: Vec<_> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| WebhookError::InvalidUrl(format!("could not resolve '{host}': {e}")))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(WebhookError::InvalidUrl(format!(
+            "'{host}' did not resolve to any address"
+        )));
+    }
+    if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+        return Err(WebhookError::InvalidUrl(format!(
+            "'{host}' resolves to non public address {}",
+            addr.ip()
+        )));
+    }
+    Ok(addrs)
+}
+
+fn is_public(ip: std::net::IpAddr) -> bool {
+    use std::net::IpAddr;
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || octets[0] == 0
+                // 100.64.0.0/10 shared address space
+                || (octets[0] == 100 && (octets[1] & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(ip));
+            }
+            let first_segment = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // fc00::/7 unique local
+                || (first_segment & 0xfe00) == 0xfc00
+                // fe80::/10 link local
+                || (first_segment & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+fn generate_secret() -> String {
+    format!(
+        "{SECRET_PREFIX}{}",
+        Alphanumeric.sample_string(&mut rand::thread_rng(), 32)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_validation() {
+        assert!(validate_url("https://example.com/hooks").is_ok());
+        assert!(validate_url("http://localhost:8080").is_ok());
+        assert!(matches!(
+            validate_url("ftp://example.com/hooks"),
+            Err(WebhookError::InvalidUrl(_))
+        ));
+        assert!(matches!(
+            validate_url("not a url"),
+            Err(WebhookError::InvalidUrl(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn destination_validation() {
+        assert!(validate_destination("https://93.184.216.34/hooks", &[])
+            .await
+            .is_ok());
+        for url in [
+            "http://127.0.0.1:8080",
+            "http://10.0.0.1",
+            "http://172.16.0.1",
+            "http://192.168.1.1",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.64.0.1",
+            "http://0.0.0.0",
+            "http://[::1]:8080",
+            "http://[fd00::1]",
+            "http://[fe80::1]",
+            "http://[::ffff:127.0.0.1]",
+        ] {
+            assert!(
+                matches!(
+                    validate_destination(url, &[]).await,
+                    Err(WebhookError::InvalidUrl(_))
+                ),
+                "{url} should be rejected"
+            );
+        }
+        assert!(
+            validate_destination("http://127.0.0.1:8080", &["127.0.0.1".to_string()])
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn generated_secrets_are_prefixed_and_unique() {
+        let secret = generate_secret();
+        assert!(secret.starts_with(SECRET_PREFIX));
+        assert_eq!(secret.len(), SECRET_PREFIX.len() + 32);
+        assert_ne!(secret, generate_secret());
+    }
+}
@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+use crate::primitives::*;
+
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("WebhookError - Sqlx: {0}")]
+    Sqlx(#[from] sqlx::Error),
+    #[error("WebhookError - EsEntityError: {0}")]
+    EsEntityError(es_entity::EsEntityError),
+    #[error("WebhookError - CursorDestructureError: {0}")]
+    CursorDestructureError(#[from] es_entity::CursorDestructureError),
+    #[error("WebhookError - SerdeJson: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("WebhookError - Reqwest: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("WebhookError - Hex: {0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("WebhookError - CouldNotDecryptSecret: {0}")]
+    CouldNotDecryptSecret(chacha20poly1305::Error),
+    #[error("WebhookError - InvalidUrl: {0}")]
+    InvalidUrl(String),
+    #[error("WebhookError - UnexpectedResponseStatus: {0}")]
+    UnexpectedResponseStatus(u16),
+    #[error("WebhookError - DeliveryNotFound: endpoint {0} sequence {1}")]
+    DeliveryNotFound(WebhookEndpointId, u64),
+    #[error("WebhookError - DeliveryPending: endpoint {0} sequence {1} is still being delivered")]
+    DeliveryPending(WebhookEndpointId, u64),
+}
+
+es_entity::from_es_entity_error!(WebhookError);
@@ -0,0 +1,12 @@
+mod client;
+mod delivery;
+mod entity;
+pub mod error;
+mod repo;
+mod secret;
+
+pub use client::*;
+pub use delivery::*;
+pub use entity::*;
+pub use repo::*;
+pub use secret::*;
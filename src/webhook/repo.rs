@@ -0,0 +1,75 @@
+use es_entity::*;
+use sqlx::{Pool, Postgres};
+
+use super::{entity::*, error::WebhookError};
+
+use crate::primitives::*;
+
+#[derive(EsRepo, Clone, Debug)]
+#[es_repo(
+    entity = "WebhookEndpoint",
+    err = "WebhookError",
+    columns(account_id(ty = "AccountId", list_for)),
+    tbl_prefix = "bria"
+)]
+pub struct WebhookEndpoints {
+    pool: Pool<Postgres>,
+}
+
+impl WebhookEndpoints {
+    pub fn new(pool: &Pool<Postgres>) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn find_by_account_id_and_id(
+        &self,
+        account_id: AccountId,
+        id: WebhookEndpointId,
+    ) -> Result<WebhookEndpoint, WebhookError> {
+        let endpoint = self.find_by_id(id).await?;
+        if endpoint.account_id != account_id || endpoint.deleted {
+            return Err(WebhookError::EsEntityError(EsEntityError::NotFound));
+        }
+        Ok(endpoint)
+    }
+
+    pub async fn list_for_account_id(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<WebhookEndpoint>, WebhookError> {
+        let mut endpoints = Vec::new();
+        let mut next = Some(PaginatedQueryArgs::default());
+
+        while let Some(query) = next.take() {
+            let mut ret = self
+                .list_for_account_id_by_created_at(account_id, query, Default::default())
+                .await?;
+
+            endpoints.append(&mut ret.entities);
+            next = ret.into_next_query();
+        }
+        endpoints.retain(|endpoint| !endpoint.deleted);
+
+        Ok(endpoints)
+    }
+
+    pub async fn list_active(&self) -> Result<Vec<WebhookEndpoint>, WebhookError> {
+        let mut endpoints = self.list_all().await?;
+        endpoints.retain(|endpoint| endpoint.is_active());
+        Ok(endpoints)
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<WebhookEndpoint>, WebhookError> {
+        let mut endpoints = Vec::new();
+        let mut next = Some(PaginatedQueryArgs::default());
+
+        while let Some(query) = next.take() {
+            let mut ret = self.list_by_id(query, Default::default()).await?;
+
+            endpoints.append(&mut ret.entities);
+            next = ret.into_next_query();
+        }
+
+        Ok(endpoints)
+    }
+}
@@ -0,0 +1,66 @@
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305,
+};
+use serde::{Deserialize, Serialize};
+
+use super::error::WebhookError;
+use crate::xpub::EncryptionKey;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EncryptedWebhookSecret {
+    cypher: String,
+    nonce: String,
+}
+
+impl EncryptedWebhookSecret {
+    pub(super) fn encrypt(secret: &str, key: &EncryptionKey) -> Self {
+        let cipher = ChaCha20Poly1305::new(key);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let cypher = cipher
+            .encrypt(&nonce, secret.as_bytes())
+            .expect("should always encrypt");
+        Self {
+            cypher: hex::encode(cypher),
+            nonce: hex::encode(nonce),
+        }
+    }
+
+    pub(super) fn decrypt(&self, key: &EncryptionKey) -> Result<String, WebhookError> {
+        let cipher = ChaCha20Poly1305::new(key);
+        let nonce = hex::decode(&self.nonce)?;
+        let cypher = hex::decode(&self.cypher)?;
+        let secret = cipher
+            .decrypt(
+                chacha20poly1305::Nonce::from_slice(nonce.as_slice()),
+                cypher.as_slice(),
+            )
+            .map_err(WebhookError::CouldNotDecryptSecret)?;
+        Ok(String::from_utf8(secret).expect("secret should be utf8"))
+    }
+}
+
+impl std::fmt::Debug for EncryptedWebhookSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncryptedWebhookSecret {{ *******Redacted******* }}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let encrypted = EncryptedWebhookSecret::encrypt("whsec_test", &key);
+        assert_ne!(encrypted.cypher, hex::encode("whsec_test"));
+        assert_eq!(encrypted.decrypt(&key).unwrap(), "whsec_test");
+
+        let other_key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        assert!(matches!(
+            encrypted.decrypt(&other_key),
+            Err(WebhookError::CouldNotDecryptSecret(_))
+        ));
+    }
+}
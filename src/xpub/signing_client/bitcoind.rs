@@ -40,9 +40,11 @@ impl RemoteSigningClient for BitcoindRemoteSigner {
         let raw_psbt = psbt.serialize();
         let hex_psbt = general_purpose::STANDARD.encode(raw_psbt);
         let sighash_type = Some(DEFAULT_SIGHASH_TYPE.into());
+        // Keep the key origins in the response so taproot key path signatures
+        // can be attributed to the xpub during validation
         let response = self
             .inner
-            .wallet_process_psbt(&hex_psbt, None, sighash_type, None)
+            .wallet_process_psbt(&hex_psbt, None, sighash_type, Some(true))
             .map_err(|e| {
                 SigningClientError::RemoteCallFailure(format!(
                     "Failed to sign psbt via bitcoind: {e}"
@@ -0,0 +1,461 @@
+mod helpers;
+
+use bdk::bitcoin::{
+    absolute::LockTime, psbt::PartiallySignedTransaction, ScriptBuf, Transaction, TxOut,
+};
+use rand::distributions::{Alphanumeric, DistString};
+
+use bria::{
+    app::{error::ApplicationError, *},
+    batch::*,
+    primitives::{bitcoin::Txid, *},
+    signing_session::{NewSigningSession, SigningSessions},
+    utxo::{error::UtxoError, Utxos},
+    wallet::Wallets,
+    xpub::XPub,
+};
+
+const XPUB: &str = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+
+fn wallet_summary(
+    wallet_id: WalletId,
+    keychain_id: KeychainId,
+    cpfp_fee_sats: u64,
+    broadcast: bool,
+) -> WalletSummary {
+    WalletSummary {
+        wallet_id,
+        current_keychain_id: keychain_id,
+        signing_keychains: vec![keychain_id],
+        total_in_sats: Satoshis::from(100_000),
+        total_spent_sats: Satoshis::from(50_000),
+        total_fee_sats: Satoshis::from(1_000 + cpfp_fee_sats),
+        cpfp_fee_sats: Satoshis::from(cpfp_fee_sats),
+        cpfp_details: Default::default(),
+        change_sats: Satoshis::from(49_000 - cpfp_fee_sats),
+        change_address: None,
+        change_outpoint: None,
+        batch_created_ledger_tx_id: None,
+        batch_broadcast_ledger_tx_id: broadcast.then(LedgerTransactionId::new),
+        batch_replaced_ledger_tx_id: None,
+    }
+}
+
+async fn create_batch(
+    pool: &sqlx::PgPool,
+    account_id: AccountId,
+    payout_queue_id: PayoutQueueId,
+    summary: WalletSummary,
+) -> anyhow::Result<(BatchId, Txid)> {
+    let unsigned_tx = Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![],
+        output: vec![TxOut {
+            value: u64::from(summary.total_spent_sats),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    };
+    let tx_id = helpers::random_txid();
+    let batch_id = BatchId::new();
+    let batch = NewBatch::builder()
+        .id(batch_id)
+        .account_id(account_id)
+        .payout_queue_id(payout_queue_id)
+        .tx_id(tx_id)
+        .total_fee_sats(summary.total_fee_sats)
+        .unsigned_psbt(PartiallySignedTransaction::from_unsigned_tx(unsigned_tx)?)
+        .wallet_summaries([(summary.wallet_id, summary)].into_iter().collect())
+        .build()?;
+    let mut tx = pool.begin().await?;
+    Batches::new(pool).create_in_op(&mut tx, batch).await?;
+    tx.commit().await?;
+    Ok((batch_id, tx_id))
+}
+
+#[tokio::test]
+async fn list_batches_with_filters() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let account_id = profile.account_id;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    let wallets = Wallets::new(&pool);
+    let utxos = Utxos::new(&pool);
+    let batches = Batches::new(&pool);
+
+    let mut wallet_ids = Vec::new();
+    let mut keychain_ids = Vec::new();
+    let mut wallet_names = Vec::new();
+    for derivation in ["m/84'/0'/0'", "m/84'/0'/1'"] {
+        let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+        let (wallet_id, _) = app
+            .create_wpkh_wallet(
+                &profile,
+                name.clone(),
+                XPUB.to_owned(),
+                Some(derivation.to_owned()),
+            )
+            .await?;
+        wallet_ids.push(wallet_id);
+        keychain_ids.push(wallets.find_by_id(wallet_id).await?.current_keychain_id());
+        wallet_names.push(name);
+    }
+    let mut queue_ids = Vec::new();
+    let mut queue_names = Vec::new();
+    for _ in 0..2 {
+        let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+        queue_ids.push(
+            app.create_payout_queue(&profile, name.clone(), None, None)
+                .await?,
+        );
+        queue_names.push(name);
+    }
+
+    // Not broadcast, carries a cpfp fee and two payouts
+    let (pending_id, pending_tx_id) = create_batch(
+        &pool,
+        account_id,
+        queue_ids[0],
+        wallet_summary(wallet_ids[0], keychain_ids[0], 500, false),
+    )
+    .await?;
+    for _ in 0..2 {
+        let (payout_id, _) = app
+            .submit_payout_to_address(
+                &profile,
+                wallet_names[0].clone(),
+                queue_names[0].clone(),
+                "bcrt1q208tuy5rd3kvy8xdpv6yrczg7f3mnlk3lql7ej".to_string(),
+                Satoshis::from(10_000),
+                None,
+                None,
+                None,
+                vec![],
+            )
+            .await?;
+        sqlx::query("UPDATE bria_payouts SET batch_id = $1 WHERE id = $2")
+            .bind(pending_id)
+            .bind(payout_id)
+            .execute(&pool)
+            .await?;
+    }
+
+    let (broadcast_id, _) = create_batch(
+        &pool,
+        account_id,
+        queue_ids[1],
+        wallet_summary(wallet_ids[0], keychain_ids[0], 0, true),
+    )
+    .await?;
+
+    // Broadcast and its inputs have been spent by a confirmed transaction
+    let (confirmed_id, _) = create_batch(
+        &pool,
+        account_id,
+        queue_ids[0],
+        wallet_summary(wallet_ids[1], keychain_ids[1], 0, true),
+    )
+    .await?;
+    let input = helpers::detect_utxo(
+        &utxos,
+        account_id,
+        wallet_ids[1],
+        keychain_ids[1],
+        100_000,
+        100,
+        true,
+    )
+    .await?;
+    let mut tx = pool.begin().await?;
+    utxos
+        .reserve_utxos_in_batch(
+            &mut tx,
+            account_id,
+            confirmed_id,
+            queue_ids[0],
+            bitcoin::FeeRate::from_sat_per_vb(1.0),
+            [(keychain_ids[1], input)],
+        )
+        .await?;
+    tx.commit().await?;
+    sqlx::query(
+        "UPDATE bria_utxos SET spend_settled_ledger_tx_id = $1 WHERE keychain_id = $2 AND tx_id = $3",
+    )
+    .bind(LedgerTransactionId::new())
+    .bind(keychain_ids[1])
+    .bind(input.txid.to_string())
+    .execute(&pool)
+    .await?;
+
+    let list = |filter: BatchFilter| {
+        let batches = batches.clone();
+        async move {
+            let listings = batches
+                .list_for_account(account_id, &filter, 1, 100)
+                .await?;
+            Ok::<_, anyhow::Error>(listings)
+        }
+    };
+    let ids = |listings: &[BatchListing]| listings.iter().map(|b| b.id).collect::<Vec<_>>();
+
+    let all = list(BatchFilter::default()).await?;
+    assert_eq!(ids(&all), vec![confirmed_id, broadcast_id, pending_id]);
+    let pending = &all[2];
+    assert_eq!(pending.status, BatchStatus::Pending);
+    assert_eq!(pending.cpfp_fee_sats, Satoshis::from(500));
+    assert_eq!(pending.n_payouts, 2);
+    assert_eq!(pending.bitcoin_tx_id, pending_tx_id);
+    assert_eq!(all[1].status, BatchStatus::Broadcast);
+    assert_eq!(all[1].n_payouts, 0);
+    assert_eq!(all[0].status, BatchStatus::Confirmed);
+
+    let by_queue = list(BatchFilter {
+        payout_queue_id: Some(queue_ids[0]),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(ids(&by_queue), vec![confirmed_id, pending_id]);
+
+    let by_wallet = list(BatchFilter {
+        wallet_id: Some(wallet_ids[0]),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(ids(&by_wallet), vec![broadcast_id, pending_id]);
+
+    let by_tx_id = list(BatchFilter {
+        tx_id: Some(pending_tx_id),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(ids(&by_tx_id), vec![pending_id]);
+
+    let by_time = list(BatchFilter {
+        created_after: Some(all[1].created_at),
+        created_before: Some(all[0].created_at),
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(ids(&by_time), vec![broadcast_id]);
+
+    for (status, expected) in [
+        (BatchStatus::Pending, pending_id),
+        (BatchStatus::Broadcast, broadcast_id),
+        (BatchStatus::Confirmed, confirmed_id),
+    ] {
+        let by_status = list(BatchFilter {
+            status: Some(status),
+            ..Default::default()
+        })
+        .await?;
+        assert_eq!(ids(&by_status), vec![expected]);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_signing_waits_for_an_attempt_in_progress() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    let batches = Batches::new(&pool);
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let (wallet_id, _) = app
+        .create_wpkh_wallet(
+            &profile,
+            name,
+            XPUB.to_owned(),
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await?;
+    let keychain_id = Wallets::new(&pool)
+        .find_by_id(wallet_id)
+        .await?
+        .current_keychain_id();
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let queue_id = app
+        .create_payout_queue(&profile, queue_name, None, None)
+        .await?;
+    let (batch_id, _) = create_batch(
+        &pool,
+        profile.account_id,
+        queue_id,
+        wallet_summary(wallet_id, keychain_id, 0, false),
+    )
+    .await?;
+
+    let mut signing_attempt = pool.begin().await?;
+    assert!(
+        batches
+            .try_lock_for_signing(&mut signing_attempt, batch_id)
+            .await?
+    );
+    let res = app.retry_signing(&profile, batch_id).await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::BatchSigningInProgress(id)) if id == batch_id
+    ));
+
+    signing_attempt.rollback().await?;
+    app.retry_signing(&profile, batch_id).await?;
+
+    // Retrying again reschedules the queued attempt instead of adding another one
+    for _ in 0..10 {
+        match app.retry_signing(&profile, batch_id).await {
+            Err(ApplicationError::BatchSigningInProgress(_)) => {
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await
+            }
+            res => {
+                res?;
+                break;
+            }
+        }
+    }
+    let (queued,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM mq_msgs WHERE channel_name = 'batch_signing' AND channel_args = $1",
+    )
+    .bind(format!("batch_id:{batch_id}"))
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(queued, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_unsigned_psbt_for_xpub() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let (wallet_id, _) = app
+        .create_wpkh_wallet(
+            &profile,
+            name,
+            XPUB.to_owned(),
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await?;
+    let keychain_id = Wallets::new(&pool)
+        .find_by_id(wallet_id)
+        .await?
+        .current_keychain_id();
+    let other_xpub_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let other_fingerprint = app
+        .import_xpub(
+            &profile,
+            other_xpub_name.clone(),
+            "tpubDDDDGYiFda8HfJRc2AHFJDxVzzEtBPrKsbh35EaW2UGd5qfzrF2G87ewAgeeRyHEz4iB3kvhAYW1sH6dpLepTkFUzAktumBN8AXeXWE9nd1".to_owned(),
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await?;
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let queue_id = app
+        .create_payout_queue(&profile, queue_name, None, None)
+        .await?;
+    let (batch_id, _) = create_batch(
+        &pool,
+        profile.account_id,
+        queue_id,
+        wallet_summary(wallet_id, keychain_id, 0, false),
+    )
+    .await?;
+
+    let fingerprint =
+        XPub::try_from((&XPUB.to_owned(), Some("m/84'/0'/0'".to_owned())))?.fingerprint();
+    let unsigned_psbt = Batches::new(&pool)
+        .find_by_id(profile.account_id, batch_id)
+        .await?
+        .unsigned_psbt;
+    let session = NewSigningSession::builder()
+        .account_id(profile.account_id)
+        .batch_id(batch_id)
+        .xpub_fingerprint(fingerprint)
+        .unsigned_psbt(unsigned_psbt.clone())
+        .build()?;
+    SigningSessions::new(&pool)
+        .persist_sessions([(fingerprint, session)].into_iter().collect())
+        .await?;
+
+    let session = app
+        .get_unsigned_psbt(&profile, batch_id, fingerprint.to_string())
+        .await?;
+    assert_eq!(session.xpub_fingerprint, fingerprint);
+    assert_eq!(session.unsigned_psbt, unsigned_psbt);
+
+    let res = app
+        .get_unsigned_psbt(&profile, batch_id, other_xpub_name)
+        .await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::SigningSessionNotFoundForXPubFingerprint(fp)) if fp == other_fingerprint
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reserved_utxo_cannot_be_frozen() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    let utxos = Utxos::new(&pool);
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let (wallet_id, _) = app
+        .create_wpkh_wallet(
+            &profile,
+            name.clone(),
+            XPUB.to_owned(),
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await?;
+    let keychain_id = Wallets::new(&pool)
+        .find_by_id(wallet_id)
+        .await?
+        .current_keychain_id();
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let queue_id = app
+        .create_payout_queue(&profile, queue_name, None, None)
+        .await?;
+    let (batch_id, _) = create_batch(
+        &pool,
+        profile.account_id,
+        queue_id,
+        wallet_summary(wallet_id, keychain_id, 0, false),
+    )
+    .await?;
+    let outpoint = helpers::detect_utxo(
+        &utxos,
+        profile.account_id,
+        wallet_id,
+        keychain_id,
+        100_000,
+        100,
+        true,
+    )
+    .await?;
+    let mut tx = pool.begin().await?;
+    utxos
+        .reserve_utxos_in_batch(
+            &mut tx,
+            profile.account_id,
+            batch_id,
+            queue_id,
+            bitcoin::FeeRate::from_sat_per_vb(1.0),
+            [(keychain_id, outpoint)],
+        )
+        .await?;
+    tx.commit().await?;
+
+    let res = app.freeze_utxo(&profile, name, outpoint).await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::UtxoError(UtxoError::UtxoAlreadyReserved(o))) if o == outpoint
+    ));
+
+    Ok(())
+}
@@ -1,3 +1,7 @@
+mod helpers;
+
+use serial_test::serial;
+
 use bria::{fees::*, primitives::TxPriority};
 
 #[tokio::test]
@@ -17,3 +21,131 @@ async fn blockstream() -> anyhow::Result<()> {
     assert!(fee_rate.as_sat_per_vb() > 0.0);
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn falls_back_to_next_provider() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let fees_config = FeesConfig {
+        providers: vec![FeeProvider::Bitcoind, FeeProvider::MempoolSpace],
+        bitcoind: BitcoindFeeConfig {
+            url: "http://127.0.0.1:1".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let fees_client = FeesClient::new(fees_config, &pool);
+    let fee_rate = fees_client.fee_rate(TxPriority::NextBlock).await?;
+    assert!(fee_rate.as_sat_per_vb() > 0.0);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn fee_rate_override() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let fees_config = FeesConfig {
+        providers: vec![FeeProvider::Bitcoind],
+        bitcoind: BitcoindFeeConfig {
+            url: "http://127.0.0.1:1".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let fees_client = FeesClient::new(fees_config, &pool);
+    let overrides = FeeRateOverrides::new(&pool);
+
+    overrides
+        .set(
+            bdk::FeeRate::from_sat_per_vb(42.0),
+            chrono::Utc::now() + std::time::Duration::from_secs(60),
+        )
+        .await?;
+    let fee_rate = fees_client.fee_rate(TxPriority::NextBlock).await?;
+    assert_eq!(fee_rate.as_sat_per_vb(), 42.0);
+
+    overrides.clear().await?;
+    assert!(fees_client.active_override().await?.is_none());
+    assert!(fees_client.fee_rate(TxPriority::NextBlock).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn fee_rate_quote_is_cached() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let fees_config = FeesConfig {
+        providers: vec![FeeProvider::MempoolSpace],
+        cache_ttl: std::time::Duration::from_secs(60),
+        ..Default::default()
+    };
+    let fees_client = FeesClient::new(fees_config, &pool);
+    let first = fees_client.fee_rate_quote(TxPriority::NextBlock).await?;
+    assert_eq!(first.source, FeeRateSource::MempoolSpace);
+    assert!(!first.stale);
+
+    let second = fees_client.fee_rate_quote(TxPriority::NextBlock).await?;
+    assert_eq!(first.fetched_at, second.fetched_at);
+    assert_eq!(first.fee_rate, second.fee_rate);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn stale_quote_is_served_when_providers_fail() -> anyhow::Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let pool = helpers::init_pool().await?;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    // Answers a single request - afterwards the listener is dropped and the provider fails
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+        }
+        let body = r#"{"fastestFee":21,"halfHourFee":12,"hourFee":7}"#;
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    });
+
+    let fees_config = FeesConfig {
+        providers: vec![FeeProvider::MempoolSpace],
+        cache_ttl: std::time::Duration::ZERO,
+        mempool_space: MempoolSpaceConfig {
+            url,
+            number_of_retries: 0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let fees_client = FeesClient::new(fees_config, &pool);
+    let fresh = fees_client.fee_rate_quote(TxPriority::NextBlock).await?;
+    assert!(!fresh.stale);
+    assert_eq!(fresh.source, FeeRateSource::MempoolSpace);
+    assert_eq!(fresh.fee_rate.as_sat_per_vb(), 21.0);
+    server.join().unwrap();
+
+    let stale = fees_client.fee_rate_quote(TxPriority::NextBlock).await?;
+    assert!(stale.stale);
+    assert_eq!(stale.source, FeeRateSource::MempoolSpace);
+    assert_eq!(stale.fee_rate, fresh.fee_rate);
+    assert_eq!(stale.fetched_at, fresh.fetched_at);
+
+    // Nothing was cached for this priority so there is nothing to fall back to
+    assert!(fees_client
+        .fee_rate_quote(TxPriority::OneHour)
+        .await
+        .is_err());
+    Ok(())
+}
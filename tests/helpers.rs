@@ -14,7 +14,15 @@ use bdk::{
     miniscript::Segwitv0,
 };
 use bitcoincore_rpc::{Client as BitcoindClient, RpcApi};
-use bria::{admin::*, job_svc::JobSvc, primitives::*, profile::*, xpub::*};
+use bria::{
+    admin::*,
+    app::{BitcoindRpcConfig, BlockchainBackend, BlockchainConfig, EsploraConfig},
+    bdk::blockchain::{init_backend, ChainBackend, ElectrumBackend, EsploraBackend},
+    job_svc::JobSvc,
+    primitives::*,
+    profile::*,
+    xpub::*,
+};
 use rand::distributions::{Alphanumeric, DistString};
 
 use bria::{
@@ -24,6 +32,7 @@ use bria::{
     outbox::{Augmenter, Outbox},
     payout::Payouts,
     payout_queue::PayoutQueues,
+    utxo::Utxos,
 };
 pub async fn init_pool() -> anyhow::Result<sqlx::PgPool> {
     let pg_host = std::env::var("PG_HOST").unwrap_or("localhost".to_string());
@@ -162,6 +171,38 @@ pub fn gen_blocks(bitcoind: &BitcoindClient, n: u64) -> anyhow::Result<()> {
     Ok(())
 }
 
+pub fn electrum_backend() -> anyhow::Result<std::sync::Arc<dyn ChainBackend>> {
+    let electrum_host = std::env::var("ELECTRUM_HOST").unwrap_or("localhost".to_string());
+    Ok(std::sync::Arc::new(ElectrumBackend::connect(&format!(
+        "{electrum_host}:50001"
+    ))?))
+}
+
+pub fn esplora_backend() -> anyhow::Result<std::sync::Arc<dyn ChainBackend>> {
+    let esplora_host = std::env::var("ESPLORA_HOST").unwrap_or("localhost".to_string());
+    Ok(std::sync::Arc::new(EsploraBackend::new(&EsploraConfig {
+        url: format!("http://{esplora_host}:3002"),
+        ..Default::default()
+    })?))
+}
+
+pub fn bitcoind_rpc_backend(
+    sync_start_time: Option<u64>,
+) -> anyhow::Result<std::sync::Arc<dyn ChainBackend>> {
+    let bitcoind_host = std::env::var("BITCOIND_HOST").unwrap_or("localhost".to_string());
+    Ok(init_backend(&BlockchainConfig {
+        network: bitcoin::Network::Regtest,
+        backend: BlockchainBackend::BitcoindRpc,
+        bitcoind_rpc: BitcoindRpcConfig {
+            url: format!("http://{bitcoind_host}:18443"),
+            rpc_user: "rpcuser".to_string(),
+            rpc_password: "rpcpassword".to_string(),
+            sync_start_time,
+        },
+        ..Default::default()
+    })?)
+}
+
 pub async fn electrum_blockchain() -> anyhow::Result<ElectrumBlockchain> {
     let electrum_host = std::env::var("ELECTRUM_HOST").unwrap_or("localhost".to_string());
     let electrum_url = format!("{electrum_host}:50001");
@@ -257,3 +298,70 @@ pub async fn bitcoind_signing_client() -> anyhow::Result<BitcoindRemoteSigner> {
 
     Ok(BitcoindRemoteSigner::connect(&cfg).await?)
 }
+
+pub fn random_txid() -> bdk::bitcoin::Txid {
+    (0..32)
+        .map(|_| format!("{:02x}", rand::random::<u8>()))
+        .collect::<String>()
+        .parse()
+        .unwrap()
+}
+
+/// Records an unspent output paying the wallet without going through a chain backend
+pub async fn detect_utxo(
+    utxos: &Utxos,
+    account_id: AccountId,
+    wallet_id: WalletId,
+    keychain_id: KeychainId,
+    value: u64,
+    block_height: u32,
+    settle: bool,
+) -> anyhow::Result<bdk::bitcoin::OutPoint> {
+    let address = "bcrt1qzg4a08kc2xrp08d9k5jadm78ehf7catp735zn0"
+        .parse::<Address<_>>()?
+        .assume_checked();
+    let local_utxo = bdk::LocalUtxo {
+        outpoint: bdk::bitcoin::OutPoint {
+            txid: random_txid(),
+            vout: 0,
+        },
+        txout: bdk::bitcoin::TxOut {
+            value,
+            script_pubkey: address.script_pubkey(),
+        },
+        keychain: bdk::KeychainKind::External,
+        is_spent: false,
+    };
+    let address_info = bdk::wallet::AddressInfo {
+        index: 0,
+        address,
+        keychain: bdk::KeychainKind::External,
+    };
+    let (_, mut tx) = utxos
+        .new_utxo_detected(
+            account_id,
+            wallet_id,
+            keychain_id,
+            &address_info,
+            &local_utxo,
+            Satoshis::from(1_000),
+            110,
+            false,
+            block_height,
+        )
+        .await?
+        .expect("utxo was already detected");
+    if settle {
+        utxos
+            .settle_utxo(
+                &mut tx,
+                keychain_id,
+                local_utxo.outpoint,
+                false,
+                block_height,
+            )
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(local_utxo.outpoint)
+}
@@ -2,6 +2,8 @@ mod helpers;
 
 use bdk::bitcoin::Network;
 
+use bitcoincore_rpc::RpcApi;
+
 use bria::{primitives::*, wallet::*};
 
 #[tokio::test]
@@ -29,3 +31,81 @@ async fn new_address() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn sync_via_esplora() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let bitcoind = helpers::bitcoind_client().await?;
+
+    let external = "wpkh([492ef832/84'/0'/0']tpubDCyf42ghP6mBAETwkz8AbXo97822jdUgHNug91bbX8GXpTP6ee298yGeiM5SvgL8Z85bcFyKioyRQokNh6J4eT3Fy8mgKDAKfynovRu3WzE/0/*)#gf2aklqx";
+    let internal = "wpkh([492ef832/84'/0'/0']tpubDCyf42ghP6mBAETwkz8AbXo97822jdUgHNug91bbX8GXpTP6ee298yGeiM5SvgL8Z85bcFyKioyRQokNh6J4eT3Fy8mgKDAKfynovRu3WzE/1/*)#ea0ut2s7";
+    let keychain_cfg = KeychainConfig::try_from((external, internal)).unwrap();
+    let wallet = KeychainWallet::new(pool, Network::Regtest, KeychainId::new(), keychain_cfg);
+
+    let addr = wallet.new_external_address().await?;
+    let amount = 50_000_000;
+    let tx_id = helpers::fund_addr(&bitcoind, &addr, amount)?;
+    helpers::gen_blocks(&bitcoind, 1)?;
+
+    let esplora = helpers::esplora_backend()?;
+    let mut attempts = 0;
+    loop {
+        wallet.sync(esplora.clone(), chrono::Utc::now()).await?;
+        if wallet.balance().await?.confirmed >= amount {
+            break;
+        }
+        attempts += 1;
+        assert!(attempts < 30, "esplora never reported {tx_id}");
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sync_via_bitcoind_rpc() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let bitcoind = helpers::bitcoind_client().await?;
+
+    let external = "wpkh([492ef832/84'/0'/0']tpubDCyf42ghP6mBAETwkz8AbXo97822jdUgHNug91bbX8GXpTP6ee298yGeiM5SvgL8Z85bcFyKioyRQokNh6J4eT3Fy8mgKDAKfynovRu3WzE/0/*)#gf2aklqx";
+    let internal = "wpkh([492ef832/84'/0'/0']tpubDCyf42ghP6mBAETwkz8AbXo97822jdUgHNug91bbX8GXpTP6ee298yGeiM5SvgL8Z85bcFyKioyRQokNh6J4eT3Fy8mgKDAKfynovRu3WzE/1/*)#ea0ut2s7";
+    let keychain_cfg = KeychainConfig::try_from((external, internal)).unwrap();
+    let keychain_id = KeychainId::new();
+    let wallet = KeychainWallet::new(
+        pool.clone(),
+        Network::Regtest,
+        keychain_id,
+        keychain_cfg.clone(),
+    );
+    let created_at = chrono::Utc::now() - chrono::Duration::hours(1);
+
+    let addr = wallet.new_external_address().await?;
+    let amount = 50_000_000;
+    helpers::fund_addr(&bitcoind, &addr, amount)?;
+    helpers::gen_blocks(&bitcoind, 1)?;
+
+    // Without a configured start time the rescan starts when the wallet was created
+    wallet
+        .sync(helpers::bitcoind_rpc_backend(None)?, created_at)
+        .await?;
+    assert!(bitcoind
+        .list_wallets()?
+        .contains(&format!("bria_{keychain_id}")));
+    assert!(wallet.balance().await?.confirmed >= amount);
+
+    // A configured start time takes precedence over the wallet creation time
+    let keychain_id = KeychainId::new();
+    let wallet = KeychainWallet::new(pool, Network::Regtest, keychain_id, keychain_cfg);
+    wallet
+        .sync(
+            helpers::bitcoind_rpc_backend(Some(0))?,
+            chrono::Utc::now() + chrono::Duration::days(1),
+        )
+        .await?;
+    assert!(bitcoind
+        .list_wallets()?
+        .contains(&format!("bria_{keychain_id}")));
+    assert!(wallet.balance().await?.confirmed >= amount);
+
+    Ok(())
+}
@@ -431,6 +431,249 @@ async fn create_batch() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn replace_batch() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+
+    let ledger = Ledger::init(&pool).await?;
+
+    let account_id = AccountId::new();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let mut tx = pool.begin().await?;
+    let journal_id = ledger
+        .create_journal_for_account(&mut tx, account_id, name.clone())
+        .await?;
+    let wallet_id = WalletId::new();
+    let keychain_id = KeychainId::new();
+    let wallet_ledger_accounts = ledger
+        .create_ledger_accounts_for_wallet(&mut tx, wallet_id)
+        .await?;
+
+    let utxo_sats = Satoshis::from(200_000_000);
+    let utxo_encumbered_fee = Satoshis::from(1_000);
+    let address = Address::parse_from_trusted_source("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+    let utxo_outpoint = OutPoint {
+        txid: "3010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d"
+            .parse()
+            .unwrap(),
+        vout: 0,
+    };
+    let utxo_detected_id = LedgerTransactionId::new();
+    ledger
+        .utxo_detected(
+            tx,
+            utxo_detected_id,
+            UtxoDetectedParams {
+                journal_id,
+                onchain_incoming_account_id: wallet_ledger_accounts.onchain_incoming_id,
+                onchain_fee_account_id: wallet_ledger_accounts.fee_id,
+                effective_incoming_account_id: wallet_ledger_accounts.effective_incoming_id,
+                meta: UtxoDetectedMeta {
+                    account_id,
+                    wallet_id,
+                    keychain_id,
+                    outpoint: utxo_outpoint,
+                    satoshis: utxo_sats,
+                    address: address.clone(),
+                    encumbered_spending_fees: std::iter::once((utxo_outpoint, utxo_encumbered_fee))
+                        .collect(),
+                    confirmation_time: None,
+                },
+            },
+        )
+        .await?;
+    let tx = pool.begin().await?;
+    ledger
+        .utxo_settled(
+            tx,
+            LedgerTransactionId::new(),
+            UtxoSettledParams {
+                journal_id,
+                ledger_account_ids: wallet_ledger_accounts,
+                pending_id: utxo_detected_id,
+                meta: UtxoSettledMeta {
+                    account_id,
+                    wallet_id,
+                    keychain_id,
+                    outpoint: utxo_outpoint,
+                    satoshis: utxo_sats,
+                    address: address.clone(),
+                    confirmation_time: BlockTime {
+                        height: 1,
+                        timestamp: 123409,
+                    },
+                    already_spent_tx_id: None,
+                },
+            },
+        )
+        .await?;
+
+    let payout_queue_id = PayoutQueueId::new();
+    let profile_id = ProfileId::new();
+    let payout_id = PayoutId::new();
+    let payout_sats = Satoshis::from(100_000_000);
+    let tx = pool.begin().await?;
+    ledger
+        .payout_submitted(
+            tx,
+            LedgerTransactionId::new(),
+            PayoutSubmittedParams {
+                journal_id,
+                effective_outgoing_account_id: wallet_ledger_accounts.effective_outgoing_id,
+                external_id: payout_id.to_string(),
+                meta: PayoutSubmittedMeta {
+                    account_id,
+                    payout_id,
+                    wallet_id,
+                    payout_queue_id,
+                    profile_id,
+                    satoshis: payout_sats,
+                    destination: PayoutDestination::OnchainAddress {
+                        value: address.clone(),
+                    },
+                },
+            },
+        )
+        .await?;
+
+    let summary = WalletBalanceSummary::from(
+        ledger
+            .get_wallet_ledger_account_balances(journal_id, wallet_ledger_accounts)
+            .await?,
+    );
+    assert_queued_only(&summary, utxo_sats, payout_sats, utxo_encumbered_fee);
+
+    let batch_id = BatchId::new();
+    let fee_sats = Satoshis::from(2_346);
+    let change_sats = utxo_sats - payout_sats - fee_sats;
+    let bitcoin_tx_id = "4010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d"
+        .parse()
+        .unwrap();
+    let change_outpoint = OutPoint {
+        txid: bitcoin_tx_id,
+        vout: 1,
+    };
+    let encumbered_fees = Satoshis::from(12_346);
+
+    let batch_created_tx_id = LedgerTransactionId::new();
+    let tx = pool.begin().await?;
+    ledger
+        .batch_created(
+            tx,
+            batch_created_tx_id,
+            BatchCreatedParams {
+                journal_id,
+                ledger_account_ids: wallet_ledger_accounts,
+                encumbered_fees,
+                meta: BatchCreatedMeta {
+                    batch_info: BatchWalletInfo {
+                        account_id,
+                        wallet_id,
+                        batch_id,
+                        payout_queue_id,
+                        included_payouts: vec![PayoutInfo {
+                            id: payout_id,
+                            profile_id,
+                            satoshis: payout_sats,
+                            destination: PayoutDestination::OnchainAddress {
+                                value: address.clone(),
+                            },
+                            vout_in_tx: 0,
+                        }],
+                    },
+                    tx_summary: WalletTransactionSummary {
+                        account_id,
+                        wallet_id,
+                        bitcoin_tx_id,
+                        total_utxo_settled_in_sats: utxo_sats,
+                        total_utxo_in_sats: utxo_sats,
+                        fee_sats,
+                        change_utxos: std::iter::once(ChangeOutput {
+                            outpoint: change_outpoint,
+                            satoshis: change_sats,
+                            address,
+                        })
+                        .collect(),
+                        current_keychain_id: keychain_id,
+                        cpfp_details: None,
+                        cpfp_fee_sats: None,
+                    },
+                },
+            },
+        )
+        .await?;
+
+    let batch_broadcast_tx_id = LedgerTransactionId::new();
+    let change_spending_fee = Satoshis::from(500);
+    let tx = pool.begin().await?;
+    ledger
+        .batch_broadcast(
+            tx,
+            batch_created_tx_id,
+            batch_broadcast_tx_id,
+            change_spending_fee,
+            wallet_ledger_accounts,
+        )
+        .await?;
+
+    let summary = WalletBalanceSummary::from(
+        ledger
+            .get_wallet_ledger_account_balances(journal_id, wallet_ledger_accounts)
+            .await?,
+    );
+    assert_eq!(summary.effective_encumbered_outgoing, Satoshis::ZERO);
+    assert_eq!(summary.effective_pending_outgoing, payout_sats);
+    assert_eq!(summary.fees_pending, fee_sats);
+    assert_eq!(summary.utxo_pending_outgoing, utxo_sats - fee_sats);
+    assert_eq!(summary.utxo_pending_incoming, change_sats);
+
+    let tx = pool.begin().await?;
+    ledger
+        .batch_replaced(
+            tx,
+            LedgerTransactionId::new(),
+            batch_created_tx_id,
+            Some(batch_broadcast_tx_id),
+            BatchId::new(),
+            wallet_ledger_accounts,
+        )
+        .await?;
+
+    let summary = WalletBalanceSummary::from(
+        ledger
+            .get_wallet_ledger_account_balances(journal_id, wallet_ledger_accounts)
+            .await?,
+    );
+    assert_queued_only(&summary, utxo_sats, payout_sats, utxo_encumbered_fee);
+
+    let account_balances = ledger
+        .get_account_ledger_account_balances(journal_id)
+        .await?;
+    let account_summary = AccountBalanceSummary::from(account_balances);
+    assert_summaries_match(summary, account_summary);
+
+    Ok(())
+}
+
+/// Balances of a wallet holding one settled utxo and one payout waiting to be batched
+fn assert_queued_only(
+    summary: &WalletBalanceSummary,
+    utxo_sats: Satoshis,
+    payout_sats: Satoshis,
+    utxo_encumbered_fee: Satoshis,
+) {
+    assert_eq!(summary.utxo_settled, utxo_sats);
+    assert_eq!(summary.utxo_pending_incoming, Satoshis::ZERO);
+    assert_eq!(summary.utxo_pending_outgoing, Satoshis::ZERO);
+    assert_eq!(summary.utxo_encumbered_incoming, Satoshis::ZERO);
+    assert_eq!(summary.effective_settled, utxo_sats);
+    assert_eq!(summary.effective_pending_income, Satoshis::ZERO);
+    assert_eq!(summary.effective_pending_outgoing, Satoshis::ZERO);
+    assert_eq!(summary.effective_encumbered_outgoing, payout_sats);
+    assert_eq!(summary.fees_pending, Satoshis::ZERO);
+    assert_eq!(summary.fees_encumbered, utxo_encumbered_fee);
+}
+
 #[tokio::test]
 async fn spend_detected() -> anyhow::Result<()> {
     let pool = helpers::init_pool().await?;
@@ -0,0 +1,63 @@
+mod helpers;
+
+use bria::{
+    address::Addresses, batch_inclusion::BatchInclusion, outbox::*, payout::Payouts,
+    payout_queue::PayoutQueues, primitives::*,
+};
+
+#[tokio::test]
+async fn publish_in_op_is_transactional() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let addresses = Addresses::new(&pool);
+    let payouts = Payouts::new(&pool);
+    let batch_inclusion = BatchInclusion::new(pool.clone(), PayoutQueues::new(&pool));
+    let outbox = Outbox::init(
+        &pool,
+        Augmenter::new(&addresses, &payouts, &batch_inclusion),
+    )
+    .await?;
+    let payload = |id| OutboxEventPayload::PayoutCancelled {
+        id,
+        profile_id: profile.id,
+        wallet_id: WalletId::new(),
+        payout_queue_id: PayoutQueueId::new(),
+        satoshis: Satoshis::from(10_000),
+        destination: PayoutDestination::OnchainAddress {
+            value: Address::parse_from_trusted_source(
+                "bcrt1q6q79yce8vutqzpnwkxr5x8p5kxw5rc0hqqzwym",
+            ),
+        },
+    };
+    let start = EventSequence::from(0);
+
+    let mut tx = pool.begin().await?;
+    outbox
+        .publish_in_op(&mut tx, profile.account_id, payload(PayoutId::new()))
+        .await?;
+    tx.rollback().await?;
+    assert!(outbox
+        .load_events_after(profile.account_id, start, 10)
+        .await?
+        .is_empty());
+
+    for _ in 0..2 {
+        let mut tx = pool.begin().await?;
+        outbox
+            .publish_in_op(&mut tx, profile.account_id, payload(PayoutId::new()))
+            .await?;
+        tx.commit().await?;
+    }
+    let events = outbox
+        .load_events_after(profile.account_id, start, 10)
+        .await?;
+    assert_eq!(
+        events
+            .iter()
+            .map(|e| u64::from(e.sequence))
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    Ok(())
+}
@@ -4,8 +4,11 @@ use rand::distributions::{Alphanumeric, DistString};
 
 use bria::{
     app::{error::ApplicationError, *},
+    payout::{PayoutFilter, PayoutStatus, Payouts},
     primitives::*,
-    profile::SpendingPolicy,
+    profile::{SpendingLimit, SpendingPolicy},
+    utxo::{error::UtxoError, Utxos},
+    wallet::Wallets,
     xpub::*,
 };
 
@@ -51,8 +54,167 @@ async fn payout() -> anyhow::Result<()> {
             Satoshis::from(10000),
             None,
             None,
+            None,
+            vec![],
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn scheduled_payout() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let xpub = XPub::try_from((original, Some("m/84'/0'/0'"))).unwrap();
+    let wallet_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let repo = XPubs::new(&pool);
+
+    let id = repo
+        .create(
+            NewAccountXPub::builder()
+                .account_id(profile.account_id)
+                .original(original.to_owned())
+                .key_name(wallet_name.clone())
+                .value(xpub)
+                .build()
+                .unwrap(),
+        )
+        .await?
+        .fingerprint();
+
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None)
+        .await?;
+
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let queue_id = app
+        .create_payout_queue(&profile, queue_name.clone(), None, None)
+        .await?;
+
+    let not_before = chrono::Utc::now() + chrono::Duration::try_hours(1).unwrap();
+    let (_, estimate) = app
+        .submit_payout_to_address(
+            &profile,
+            wallet_name,
+            queue_name,
+            "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".to_string(),
+            Satoshis::from(10000),
+            None,
+            None,
+            Some(not_before),
+            vec![],
+        )
+        .await?;
+    assert!(estimate.expect("interval queue has an estimate") >= not_before);
+
+    let payouts = bria::payout::Payouts::new(&pool);
+    let mut op = payouts.begin_op().await?;
+    let unbatched = payouts
+        .list_unbatched(&mut op, profile.account_id, queue_id)
+        .await?;
+    assert_eq!(unbatched.n_payouts(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_payouts_with_filters() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let xpub = XPub::try_from((original, Some("m/84'/0'/0'"))).unwrap();
+    let wallet_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let repo = XPubs::new(&pool);
+
+    let id = repo
+        .create(
+            NewAccountXPub::builder()
+                .account_id(profile.account_id)
+                .original(original.to_owned())
+                .key_name(wallet_name.clone())
+                .value(xpub)
+                .build()
+                .unwrap(),
+        )
+        .await?
+        .fingerprint();
+
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None)
+        .await?;
+
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    app.create_payout_queue(&profile, queue_name.clone(), None, None)
+        .await?;
+
+    let external_id = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let (kept_id, _) = app
+        .submit_payout_to_address(
+            &profile,
+            wallet_name.clone(),
+            queue_name.clone(),
+            "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".to_string(),
+            Satoshis::from(10000),
+            Some(external_id.clone()),
+            None,
+            None,
+            vec![],
         )
         .await?;
+    let (cancelled_id, _) = app
+        .submit_payout_to_address(
+            &profile,
+            wallet_name,
+            queue_name.clone(),
+            "n4VQ5YdHf7hLQ2gWQYYrcxoE5B7nWuDFNF".to_string(),
+            Satoshis::from(20000),
+            None,
+            None,
+            None,
+            vec![],
+        )
+        .await?;
+    app.cancel_payout(&profile, cancelled_id).await?;
+
+    let payouts = app
+        .list_payouts(&profile, None, None, PayoutFilter::default(), 1, 100)
+        .await?;
+    assert_eq!(payouts.len(), 2);
+
+    let filter = PayoutFilter {
+        status: Some(PayoutStatus::Queued),
+        ..Default::default()
+    };
+    let payouts = app
+        .list_payouts(&profile, None, Some(queue_name.clone()), filter, 1, 100)
+        .await?;
+    assert_eq!(payouts.len(), 1);
+    assert_eq!(payouts[0].payout.id, kept_id);
+
+    let filter = PayoutFilter {
+        status: Some(PayoutStatus::Cancelled),
+        ..Default::default()
+    };
+    let payouts = app
+        .list_payouts(&profile, None, None, filter, 1, 100)
+        .await?;
+    assert_eq!(payouts.len(), 1);
+    assert_eq!(payouts[0].payout.id, cancelled_id);
+
+    let filter = PayoutFilter {
+        external_id: Some(external_id),
+        destination_address: Some("mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".to_string()),
+        ..Default::default()
+    };
+    let payouts = app
+        .list_payouts(&profile, None, None, filter, 1, 100)
+        .await?;
+    assert_eq!(payouts.len(), 1);
+    assert_eq!(payouts[0].payout.id, kept_id);
 
     Ok(())
 }
@@ -96,6 +258,8 @@ async fn spending_policy() -> anyhow::Result<()> {
             Some(SpendingPolicy {
                 allowed_payout_addresses: vec![address.clone()],
                 max_payout: Some(Satoshis::from(10000)),
+                limits: vec![],
+                approval: None,
             }),
         )
         .await?;
@@ -109,6 +273,8 @@ async fn spending_policy() -> anyhow::Result<()> {
             Satoshis::from(10000),
             None,
             None,
+            None,
+            vec![],
         )
         .await?;
 
@@ -121,6 +287,8 @@ async fn spending_policy() -> anyhow::Result<()> {
             Satoshis::from(10001),
             None,
             None,
+            None,
+            vec![],
         )
         .await;
     assert!(matches!(
@@ -139,6 +307,8 @@ async fn spending_policy() -> anyhow::Result<()> {
             Satoshis::from(10000),
             None,
             None,
+            None,
+            vec![],
         )
         .await;
     assert!(matches!(
@@ -148,3 +318,289 @@ async fn spending_policy() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn spending_limits() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let xpub = XPub::try_from((original, Some("m/84'/0'/0'"))).unwrap();
+    let wallet_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let repo = XPubs::new(&pool);
+
+    let id = repo
+        .create(
+            NewAccountXPub::builder()
+                .account_id(profile.account_id)
+                .original(original.to_owned())
+                .key_name(wallet_name.clone())
+                .value(xpub)
+                .build()
+                .unwrap(),
+        )
+        .await?
+        .fingerprint();
+
+    let app = App::run(pool, AppConfig::default()).await?;
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None)
+        .await?;
+
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let _ = app
+        .create_payout_queue(&profile, queue_name.clone(), None, None)
+        .await?;
+    let address = Address::parse_from_trusted_source("mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU");
+    let spending_profile = app
+        .create_profile(
+            &profile,
+            wallet_name.clone(),
+            Some(SpendingPolicy {
+                allowed_payout_addresses: vec![],
+                max_payout: None,
+                limits: vec![
+                    SpendingLimit {
+                        window_secs: 86_400,
+                        max_amount: Some(Satoshis::from(15000)),
+                        max_payouts: None,
+                    },
+                    SpendingLimit {
+                        window_secs: 3_600,
+                        max_amount: None,
+                        max_payouts: Some(2),
+                    },
+                ],
+                approval: None,
+            }),
+        )
+        .await?;
+
+    let (payout_id, _) = app
+        .submit_payout_to_address(
+            &spending_profile,
+            wallet_name.clone(),
+            queue_name.clone(),
+            address.to_string(),
+            Satoshis::from(10000),
+            None,
+            None,
+            None,
+            vec![],
+        )
+        .await?;
+
+    let res = app
+        .submit_payout_to_address(
+            &spending_profile,
+            wallet_name.clone(),
+            queue_name.clone(),
+            address.to_string(),
+            Satoshis::from(5001),
+            None,
+            None,
+            None,
+            vec![],
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::SpendingLimitExceeded(86_400))
+    ));
+
+    app.cancel_payout(&spending_profile, payout_id).await?;
+
+    for _ in 0..2 {
+        let _ = app
+            .submit_payout_to_address(
+                &spending_profile,
+                wallet_name.clone(),
+                queue_name.clone(),
+                address.to_string(),
+                Satoshis::from(1000),
+                None,
+                None,
+                None,
+                vec![],
+            )
+            .await?;
+    }
+
+    let res = app
+        .submit_payout_to_address(
+            &spending_profile,
+            wallet_name,
+            queue_name,
+            address.to_string(),
+            Satoshis::from(1000),
+            None,
+            None,
+            None,
+            vec![],
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::SpendingLimitExceeded(3_600))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn payout_pinned_to_unknown_outpoint() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let xpub = XPub::try_from((original, Some("m/84'/0'/0'"))).unwrap();
+    let wallet_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let repo = XPubs::new(&pool);
+
+    let id = repo
+        .create(
+            NewAccountXPub::builder()
+                .account_id(profile.account_id)
+                .original(original.to_owned())
+                .key_name(wallet_name.clone())
+                .value(xpub)
+                .build()
+                .unwrap(),
+        )
+        .await?
+        .fingerprint();
+
+    let app = App::run(pool, AppConfig::default()).await?;
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None)
+        .await?;
+
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    app.create_payout_queue(&profile, queue_name.clone(), None, None)
+        .await?;
+
+    let outpoint: bitcoin::OutPoint =
+        "4010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d:0".parse()?;
+    let res = app
+        .freeze_utxo(&profile, wallet_name.clone(), outpoint)
+        .await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::UtxoError(UtxoError::UtxoNotInWallet(_)))
+    ));
+
+    let res = app
+        .submit_payout_to_address(
+            &profile,
+            wallet_name,
+            queue_name,
+            "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".to_string(),
+            Satoshis::from(10000),
+            None,
+            None,
+            None,
+            vec![outpoint],
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::UtxoError(UtxoError::UtxoNotInWallet(_)))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pinned_outpoint_reserved_for_its_payout() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let xpub = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4".to_owned();
+    let wallet_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    let (wallet_id, _) = app
+        .create_wpkh_wallet(
+            &profile,
+            wallet_name.clone(),
+            xpub,
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await?;
+    let keychain_id = Wallets::new(&pool)
+        .find_by_id(wallet_id)
+        .await?
+        .current_keychain_id();
+
+    let pinning_queue = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    app.create_payout_queue(&profile, pinning_queue.clone(), None, None)
+        .await?;
+    let other_queue = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let other_queue_id = app
+        .create_payout_queue(&profile, other_queue.clone(), None, None)
+        .await?;
+
+    let utxos = Utxos::new(&pool);
+    let outpoint = helpers::detect_utxo(
+        &utxos,
+        profile.account_id,
+        wallet_id,
+        keychain_id,
+        100_000,
+        100,
+        true,
+    )
+    .await?;
+    let (payout_id, _) = app
+        .submit_payout_to_address(
+            &profile,
+            wallet_name,
+            pinning_queue.clone(),
+            "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".to_string(),
+            Satoshis::from(10_000),
+            None,
+            None,
+            None,
+            vec![outpoint],
+        )
+        .await?;
+
+    // Batches of the other queue must leave the pinned outpoint to the payout
+    let payouts = Payouts::new(&pool);
+    let mut tx = pool.begin().await?;
+    let unbatched = payouts
+        .list_unbatched(&mut tx, profile.account_id, other_queue_id)
+        .await?;
+    assert!(unbatched.pinned_by_other_queues().contains(&outpoint));
+    let reserved = utxos
+        .outpoints_bdk_should_not_select(
+            &mut tx,
+            std::iter::once(keychain_id),
+            unbatched.pinned_by_other_queues(),
+        )
+        .await?;
+    assert!(reserved[&keychain_id].contains(&outpoint));
+    tx.rollback().await?;
+
+    // Once the outpoint is spent elsewhere the payout can't be batched anymore
+    sqlx::query("UPDATE bria_utxos SET bdk_spent = true WHERE keychain_id = $1 AND tx_id = $2")
+        .bind(keychain_id)
+        .bind(outpoint.txid.to_string())
+        .execute(&pool)
+        .await?;
+    app.trigger_payout_queue(&profile, pinning_queue).await?;
+
+    let mut attempts = 0;
+    loop {
+        let payout = payouts.find_by_id(payout_id).await?;
+        if payout.is_cancelled() {
+            break;
+        }
+        attempts += 1;
+        assert!(attempts < 100, "payout with a spent pin was not cancelled");
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let res = payouts
+        .list_unbatched_pins(&mut pool.begin().await?, profile.account_id)
+        .await?;
+    assert!(res.iter().all(|pins| pins.payout_id != payout_id));
+
+    Ok(())
+}
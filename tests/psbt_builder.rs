@@ -80,8 +80,10 @@ async fn build_psbt() -> anyhow::Result<()> {
         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
     }
     while !find_tx_id(&pool, domain_current_keychain_id, tx_id).await? {
-        let blockchain = helpers::electrum_blockchain().await?;
-        domain_current_keychain.sync(blockchain).await?;
+        let blockchain = helpers::electrum_backend()?;
+        domain_current_keychain
+            .sync(blockchain, chrono::Utc::now())
+            .await?;
     }
 
     let fee = FeeRate::from_sat_per_vb(1.0);
@@ -210,6 +212,11 @@ async fn build_psbt() -> anyhow::Result<()> {
     assert_eq!(total_summary_fees, fee_satoshis);
     assert!(unsigned_psbt.inputs.len() >= 3);
     assert_eq!(unsigned_psbt.outputs.len(), 4);
+    psbt_validator::validate_key_origins(&unsigned_psbt)?;
+    for total in wallet_totals.values() {
+        let vout = total.change_outpoint.expect("no change output").vout as usize;
+        assert!(!unsigned_psbt.outputs[vout].bip32_derivation.is_empty());
+    }
 
     other_wallet_current_keychain.sign(&mut unsigned_psbt, SignOptions::default())?;
     other_wallet_deprecated_keychain.sign(&mut unsigned_psbt, SignOptions::default())?;
@@ -292,8 +299,10 @@ async fn build_psbt_with_cpfp() -> anyhow::Result<()> {
         .wallet_payouts(domain_wallet_id, payouts_one)
         .accept_current_keychain();
     while !find_tx_id(&pool, domain_current_keychain_id, tx_id).await? {
-        let blockchain = helpers::electrum_blockchain().await?;
-        domain_current_keychain.sync(blockchain).await?;
+        let blockchain = helpers::electrum_backend()?;
+        domain_current_keychain
+            .sync(blockchain, chrono::Utc::now())
+            .await?;
     }
     let builder = domain_current_keychain
         .dispatch_bdk_wallet(builder)
@@ -416,8 +425,10 @@ async fn build_psbt_with_min_change_output() -> anyhow::Result<()> {
         .wallet_payouts(domain_wallet_id, payouts_one)
         .accept_current_keychain();
     while !find_tx_id(&pool, domain_current_keychain_id, tx_id).await? {
-        let blockchain = helpers::electrum_blockchain().await?;
-        domain_current_keychain.sync(blockchain).await?;
+        let blockchain = helpers::electrum_backend()?;
+        domain_current_keychain
+            .sync(blockchain, chrono::Utc::now())
+            .await?;
     }
     let builder = domain_current_keychain
         .dispatch_bdk_wallet(builder)
@@ -0,0 +1,216 @@
+mod helpers;
+
+use rand::distributions::{Alphanumeric, DistString};
+
+use bria::{
+    app::*,
+    primitives::{bitcoin::*, *},
+    profile::Profile,
+    utxo::*,
+    wallet::Wallets,
+};
+
+struct TestWallet {
+    pool: sqlx::PgPool,
+    app: App,
+    profile: Profile,
+    name: String,
+    wallet_id: WalletId,
+    keychain_id: KeychainId,
+    utxos: Utxos,
+}
+
+async fn test_wallet() -> anyhow::Result<TestWallet> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let xpub = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4".to_owned();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    let (wallet_id, _) = app
+        .create_wpkh_wallet(&profile, name.clone(), xpub, Some("m/84'/0'/0'".to_owned()))
+        .await?;
+    let keychain_id = Wallets::new(&pool)
+        .find_by_id(wallet_id)
+        .await?
+        .current_keychain_id();
+    Ok(TestWallet {
+        pool: pool.clone(),
+        app,
+        profile,
+        name,
+        wallet_id,
+        keychain_id,
+        utxos: Utxos::new(&pool),
+    })
+}
+
+impl TestWallet {
+    async fn detect_utxo(
+        &self,
+        value: u64,
+        block_height: u32,
+        settle: bool,
+    ) -> anyhow::Result<OutPoint> {
+        helpers::detect_utxo(
+            &self.utxos,
+            self.profile.account_id,
+            self.wallet_id,
+            self.keychain_id,
+            value,
+            block_height,
+            settle,
+        )
+        .await
+    }
+
+    async fn list(
+        &self,
+        filter: UtxoFilter,
+        after: Option<UtxoCursor>,
+        page_size: Option<usize>,
+    ) -> anyhow::Result<(Vec<OutPoint>, Option<UtxoCursor>)> {
+        let (_, keychain_utxos, next_cursor) = self
+            .app
+            .list_utxos(&self.profile, self.name.clone(), filter, after, page_size)
+            .await?;
+        let outpoints = keychain_utxos
+            .into_iter()
+            .flat_map(|keychain| keychain.utxos.into_iter().map(|utxo| utxo.outpoint))
+            .collect();
+        Ok((outpoints, next_cursor))
+    }
+}
+
+#[tokio::test]
+async fn list_utxos_with_filters() -> anyhow::Result<()> {
+    let wallet = test_wallet().await?;
+    let small = wallet.detect_utxo(10_000, 100, true).await?;
+    let medium = wallet.detect_utxo(20_000, 101, false).await?;
+    let large = wallet.detect_utxo(30_000, 102, false).await?;
+
+    let (all, next_cursor) = wallet.list(UtxoFilter::default(), None, None).await?;
+    assert_eq!(all, vec![large, medium, small]);
+    assert!(next_cursor.is_none());
+
+    let settled = UtxoFilter {
+        status: Some(UtxoStatus::Settled),
+        ..Default::default()
+    };
+    assert_eq!(wallet.list(settled, None, None).await?.0, vec![small]);
+
+    let pending = UtxoFilter {
+        status: Some(UtxoStatus::Pending),
+        ..Default::default()
+    };
+    assert_eq!(
+        wallet.list(pending, None, None).await?.0,
+        vec![large, medium]
+    );
+
+    let by_value = UtxoFilter {
+        min_value: Some(Satoshis::from(15_000)),
+        max_value: Some(Satoshis::from(25_000)),
+        ..Default::default()
+    };
+    assert_eq!(wallet.list(by_value, None, None).await?.0, vec![medium]);
+
+    let by_height = UtxoFilter {
+        min_detected_height: Some(101),
+        max_detected_height: Some(102),
+        ..Default::default()
+    };
+    assert_eq!(
+        wallet.list(by_height, None, None).await?.0,
+        vec![large, medium]
+    );
+
+    let other_keychain = UtxoFilter {
+        keychain_id: Some(KeychainId::new()),
+        ..Default::default()
+    };
+    assert!(wallet.list(other_keychain, None, None).await?.0.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_utxos_pages_by_cursor() -> anyhow::Result<()> {
+    let wallet = test_wallet().await?;
+    let first = wallet.detect_utxo(10_000, 100, false).await?;
+    let second = wallet.detect_utxo(20_000, 100, false).await?;
+    let third = wallet.detect_utxo(30_000, 100, false).await?;
+
+    let (page, after) = wallet.list(UtxoFilter::default(), None, Some(2)).await?;
+    assert_eq!(page, vec![third, second]);
+    let after = after.expect("there should be a next page");
+    assert_eq!(after.keychain_id, wallet.keychain_id);
+    assert_eq!(after.outpoint, second);
+
+    // The cursor survives the round trip through its string representation
+    let after = after.to_string().parse::<UtxoCursor>()?;
+    let (page, after) = wallet
+        .list(UtxoFilter::default(), Some(after), Some(2))
+        .await?;
+    assert_eq!(page, vec![first]);
+    assert!(after.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn frozen_utxo_is_not_selected() -> anyhow::Result<()> {
+    let wallet = test_wallet().await?;
+    let frozen = wallet.detect_utxo(10_000, 100, true).await?;
+    let spendable = wallet.detect_utxo(20_000, 100, true).await?;
+
+    wallet
+        .app
+        .freeze_utxo(&wallet.profile, wallet.name.clone(), frozen)
+        .await?;
+
+    let (_, keychain_utxos, _) = wallet
+        .app
+        .list_utxos(
+            &wallet.profile,
+            wallet.name.clone(),
+            UtxoFilter::default(),
+            None,
+            None,
+        )
+        .await?;
+    let listed: Vec<_> = keychain_utxos
+        .into_iter()
+        .flat_map(|keychain| keychain.utxos)
+        .map(|utxo| (utxo.outpoint, utxo.frozen))
+        .collect();
+    assert_eq!(listed, vec![(spendable, false), (frozen, true)]);
+
+    let mut tx = wallet.pool.begin().await?;
+    let not_selectable = wallet
+        .utxos
+        .outpoints_bdk_should_not_select(
+            &mut tx,
+            std::iter::once(wallet.keychain_id),
+            &Default::default(),
+        )
+        .await?;
+    assert_eq!(not_selectable.get(&wallet.keychain_id), Some(&vec![frozen]));
+    tx.rollback().await?;
+
+    wallet
+        .app
+        .unfreeze_utxo(&wallet.profile, wallet.name.clone(), frozen)
+        .await?;
+    let mut tx = wallet.pool.begin().await?;
+    let not_selectable = wallet
+        .utxos
+        .outpoints_bdk_should_not_select(
+            &mut tx,
+            std::iter::once(wallet.keychain_id),
+            &Default::default(),
+        )
+        .await?;
+    assert!(not_selectable.is_empty());
+
+    Ok(())
+}
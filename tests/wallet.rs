@@ -3,7 +3,12 @@ mod helpers;
 use rand::distributions::{Alphanumeric, DistString};
 use serde_json::json;
 
-use bria::{app::*, xpub::*};
+use bria::{
+    address::AddressFilter,
+    app::{error::ApplicationError, *},
+    wallet::{error::WalletError, Wallets},
+    xpub::*,
+};
 
 #[tokio::test]
 async fn create_wpkh_wallet() -> anyhow::Result<()> {
@@ -49,6 +54,46 @@ async fn create_wpkh_wallet() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn create_tr_wallet() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let xpub = XPub::try_from((original, Some("m/86'/0'/0'"))).unwrap();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let repo = XPubs::new(&pool);
+
+    let id = repo
+        .create(
+            NewAccountXPub::builder()
+                .account_id(profile.account_id)
+                .original(original.to_owned())
+                .key_name(name.clone())
+                .value(xpub)
+                .build()
+                .unwrap(),
+        )
+        .await?
+        .fingerprint();
+    let app = App::run(pool, AppConfig::default()).await?;
+    app.create_tr_wallet(&profile, name.clone(), id.to_string(), None)
+        .await?;
+
+    let (_, addr) = app.new_address(&profile, name, None, None).await?;
+    let expected = bdk::Wallet::new(
+        &format!("tr({original}/0/*)"),
+        None,
+        bdk::bitcoin::Network::Regtest,
+        bdk::database::MemoryDatabase::default(),
+    )?
+    .get_address(bdk::wallet::AddressIndex::Peek(0))?;
+    assert!(addr.to_string().starts_with("bcrt1p"));
+    assert_eq!(addr.to_string(), expected.address.to_string());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn create_descriptors_wallet() -> anyhow::Result<()> {
     let pool = helpers::init_pool().await?;
@@ -65,3 +110,208 @@ async fn create_descriptors_wallet() -> anyhow::Result<()> {
     assert_eq!(xpub_fingerprints[0].to_string(), "2f18f2f7");
     Ok(())
 }
+
+#[tokio::test]
+async fn rotate_wpkh_wallet_keychain() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4".to_owned();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let app = App::run(pool, AppConfig::default()).await?;
+    app.create_wpkh_wallet(
+        &profile,
+        name.clone(),
+        original,
+        Some("m/84'/0'/0'".to_owned()),
+    )
+    .await?;
+
+    let mainnet = "xpub6DGNFeJF5Movi9XdiTCJhnCy88UTiPPc3CtfburwNSDDrfoRxxEwChqV1QgzUgH8F2MjWghLWLeKdRRD2it1ccy256sk2ZqmVsL3fMbiSWg".to_owned();
+    let err = app
+        .rotate_wpkh_wallet_keychain(
+            &profile,
+            name.clone(),
+            mainnet,
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await;
+    assert!(matches!(
+        err,
+        Err(ApplicationError::WalletError(
+            WalletError::KeychainNetworkMismatch(_)
+        ))
+    ));
+
+    let rotated = "tpubDDdzmt7vndmNywiVAeBPuhYLTFa7hmtfaqUxxTv5iLy7bxU93B62M9WKFSmn1BEN2vte8GDD3SUNKbupRajFW4RK8hd3i6W15pvTRQfo1fK".to_owned();
+    let (_, xpub_fingerprints) = app
+        .rotate_wpkh_wallet_keychain(
+            &profile,
+            name.clone(),
+            rotated,
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await?;
+    assert_eq!(xpub_fingerprints.len(), 1);
+    assert_eq!(xpub_fingerprints[0].to_string(), "2f18f2f7");
+
+    let (_, addr) = app.new_address(&profile, name, None, None).await?;
+    assert_ne!(
+        addr.to_string(),
+        "bcrt1qzg4a08kc2xrp08d9k5jadm78ehf7catp735zn0"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn update_wallet() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4".to_owned();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let new_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    app.create_wpkh_wallet(
+        &profile,
+        name.clone(),
+        original.clone(),
+        Some("m/84'/0'/0'".to_owned()),
+    )
+    .await?;
+
+    let wallet = app
+        .update_wallet(
+            &profile,
+            name.clone(),
+            Some(new_name.clone()),
+            Some(6),
+            None,
+        )
+        .await?;
+    assert_eq!(wallet.name, new_name);
+    assert_eq!(wallet.config.settle_income_after_n_confs, 6);
+    assert_eq!(wallet.config.settle_change_after_n_confs, 1);
+
+    let wallets = app.list_wallets(&profile).await?;
+    let wallet = wallets.into_iter().find(|w| w.name == new_name).unwrap();
+    assert_eq!(wallet.config.settle_income_after_n_confs, 6);
+
+    let wallets = Wallets::new(&pool);
+    let renamed = wallets
+        .find_by_account_id_and_name(profile.account_id, new_name.clone())
+        .await?;
+    assert_eq!(renamed.id, wallet.id);
+    assert!(wallets
+        .find_by_account_id_and_name(profile.account_id, name.clone())
+        .await
+        .is_err());
+
+    let taken_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    app.create_wpkh_wallet(
+        &profile,
+        taken_name.clone(),
+        original,
+        Some("m/84'/0'/1'".to_owned()),
+    )
+    .await?;
+    let res = app
+        .update_wallet(&profile, new_name, Some(taken_name), None, None)
+        .await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::WalletError(
+            WalletError::NameAlreadyExists
+        ))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_addresses_with_filters() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4".to_owned();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let prefix = Alphanumeric.sample_string(&mut rand::thread_rng(), 16);
+    let app = App::run(pool, AppConfig::default()).await?;
+    app.create_wpkh_wallet(
+        &profile,
+        name.clone(),
+        original,
+        Some("m/84'/0'/0'".to_owned()),
+    )
+    .await?;
+    for (idx, tier) in ["gold", "silver", "gold"].iter().enumerate() {
+        app.new_address(
+            &profile,
+            name.clone(),
+            Some(format!("{prefix}-{idx}")),
+            Some(json!({ "customer": { "tier": tier } })),
+        )
+        .await?;
+    }
+    app.new_address(&profile, name.clone(), None, None).await?;
+
+    let filter = AddressFilter {
+        external_id_prefix: Some(prefix.clone()),
+        ..Default::default()
+    };
+    let (_, addresses, next) = app
+        .list_addresses(&profile, name.clone(), filter.clone(), None, Some(2))
+        .await?;
+    assert_eq!(addresses.len(), 2);
+    let (_, addresses, next) = app
+        .list_addresses(&profile, name.clone(), filter.clone(), next, Some(2))
+        .await?;
+    assert_eq!(addresses.len(), 1);
+    assert!(next.is_none());
+    let (_, addresses, next) = app
+        .list_addresses(&profile, name.clone(), filter, None, None)
+        .await?;
+    assert_eq!(addresses.len(), 3);
+    assert!(next.is_none());
+
+    let filter = AddressFilter {
+        metadata: Some((
+            vec!["customer".to_owned(), "tier".to_owned()],
+            json!("gold"),
+        )),
+        ..Default::default()
+    };
+    let (_, addresses, _) = app
+        .list_addresses(&profile, name, filter, None, Some(100))
+        .await?;
+    assert_eq!(addresses.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn record_wallet_synced() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+    let xpub = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let (wallet_id, _) = app
+        .create_wpkh_wallet(
+            &profile,
+            name,
+            xpub.to_owned(),
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await?;
+    let wallets = Wallets::new(&pool);
+    assert!(!wallets.last_synced_at().await?.contains_key(&wallet_id));
+
+    wallets.record_synced(wallet_id).await?;
+    let first = wallets.last_synced_at().await?[&wallet_id];
+    wallets.record_synced(wallet_id).await?;
+    assert!(wallets.last_synced_at().await?[&wallet_id] > first);
+
+    Ok(())
+}
@@ -0,0 +1,327 @@
+mod helpers;
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use bria::{
+    address::Addresses,
+    app::{error::ApplicationError, *},
+    batch_inclusion::BatchInclusion,
+    outbox::*,
+    payout::Payouts,
+    payout_queue::PayoutQueues,
+    primitives::*,
+    webhook::{error::WebhookError, *},
+};
+
+/// Endpoints on the local receiver need to be allowed explicitly. All tests in this
+/// file share the config as any of their job runners may pick up a delivery.
+fn app_config() -> AppConfig {
+    let mut config = AppConfig::default();
+    config.jobs.webhook_delivery.max_attempts = 2;
+    config.jobs.webhook_delivery.allowed_private_hosts = vec!["127.0.0.1".to_string()];
+    config
+}
+
+struct ReceivedRequest {
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+#[derive(Clone)]
+struct Receiver {
+    url: String,
+    status: Arc<AtomicU16>,
+    requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+}
+
+impl Receiver {
+    async fn start() -> anyhow::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let receiver = Self {
+            url: format!("http://{}/hooks", listener.local_addr()?),
+            status: Arc::new(AtomicU16::new(200)),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        };
+        let inner = receiver.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let receiver = inner.clone();
+                tokio::spawn(async move {
+                    if let Some(request) = read_request(&mut socket).await {
+                        receiver.requests.lock().unwrap().push(request);
+                    }
+                    let status = receiver.status.load(Ordering::SeqCst);
+                    let response =
+                        format!("HTTP/1.1 {status} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        Ok(receiver)
+    }
+
+    fn respond_with(&self, status: u16) {
+        self.status.store(status, Ordering::SeqCst);
+    }
+
+    fn requests_for(&self, sequence: u64) -> Vec<(HashMap<String, String>, String)> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.headers.get(SEQUENCE_HEADER) == Some(&sequence.to_string()))
+            .map(|r| (r.headers.clone(), r.body.clone()))
+            .collect()
+    }
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> Option<ReceivedRequest> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 4096];
+    let header_end = loop {
+        let n = socket.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+    let headers: HashMap<_, _> = String::from_utf8_lossy(&buf[..header_end])
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+        .collect();
+    let content_length: usize = headers.get("content-length")?.parse().ok()?;
+    while buf.len() < header_end + content_length {
+        let n = socket.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let body = String::from_utf8_lossy(&buf[header_end..header_end + content_length]).to_string();
+    Some(ReceivedRequest { headers, body })
+}
+
+async fn wait_for_status(
+    app: &App,
+    profile: &bria::profile::Profile,
+    id: WebhookEndpointId,
+    sequence: u64,
+    status: WebhookDeliveryStatus,
+) -> anyhow::Result<WebhookDelivery> {
+    for _ in 0..60 {
+        let delivery = app
+            .list_webhook_deliveries(profile, id, None, None)
+            .await?
+            .into_iter()
+            .find(|d| u64::from(d.sequence) == sequence);
+        if let Some(delivery) = delivery {
+            if delivery.status == status {
+                return Ok(delivery);
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    anyhow::bail!("delivery {sequence} never reached {status:?}")
+}
+
+#[tokio::test]
+async fn webhook_endpoint_lifecycle() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), app_config()).await?;
+
+    let (id, secret) = app
+        .create_webhook_endpoint(&profile, "https://93.184.216.34/hooks".to_string())
+        .await?;
+    assert!(secret.starts_with("whsec_"));
+
+    let endpoints = app.list_webhook_endpoints(&profile).await?;
+    assert_eq!(endpoints.len(), 1);
+    assert_eq!(endpoints[0].id, id);
+    assert!(endpoints[0].enabled);
+
+    let rotated = app
+        .update_webhook_endpoint(&profile, id, None, Some(false), true)
+        .await?;
+    assert!(rotated.is_some());
+    assert_ne!(rotated, Some(secret));
+    let endpoints = app.list_webhook_endpoints(&profile).await?;
+    assert!(!endpoints[0].enabled);
+
+    let deliveries = app
+        .list_webhook_deliveries(&profile, id, None, None)
+        .await?;
+    assert!(deliveries.is_empty());
+
+    let err = app
+        .redeliver_webhook(&profile, id, 1)
+        .await
+        .expect_err("nothing was delivered yet");
+    assert!(matches!(
+        err,
+        ApplicationError::WebhookError(WebhookError::DeliveryNotFound(_, 1))
+    ));
+
+    app.delete_webhook_endpoint(&profile, id).await?;
+    assert!(app.list_webhook_endpoints(&profile).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn webhook_endpoint_rejects_invalid_url() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), app_config()).await?;
+
+    let err = app
+        .create_webhook_endpoint(&profile, "ftp://example.com".to_string())
+        .await
+        .expect_err("only http(s) urls are accepted");
+    assert!(matches!(
+        err,
+        ApplicationError::WebhookError(WebhookError::InvalidUrl(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn webhook_endpoint_rejects_private_destination() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), app_config()).await?;
+
+    for url in ["http://169.254.169.254/latest", "http://10.0.0.1/hooks"] {
+        let err = app
+            .create_webhook_endpoint(&profile, url.to_string())
+            .await
+            .expect_err("private destinations are rejected");
+        assert!(matches!(
+            err,
+            ApplicationError::WebhookError(WebhookError::InvalidUrl(_))
+        ));
+    }
+
+    let (id, _) = app
+        .create_webhook_endpoint(&profile, "https://93.184.216.34/hooks".to_string())
+        .await?;
+    let err = app
+        .update_webhook_endpoint(
+            &profile,
+            id,
+            Some("http://[::1]/hooks".to_string()),
+            None,
+            false,
+        )
+        .await
+        .expect_err("private destinations are rejected");
+    assert!(matches!(
+        err,
+        ApplicationError::WebhookError(WebhookError::InvalidUrl(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn webhook_delivery_retries_dead_letters_and_redelivers() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), app_config()).await?;
+    let addresses = Addresses::new(&pool);
+    let payouts = Payouts::new(&pool);
+    let batch_inclusion = BatchInclusion::new(pool.clone(), PayoutQueues::new(&pool));
+    let outbox = Outbox::init(
+        &pool,
+        Augmenter::new(&addresses, &payouts, &batch_inclusion),
+    )
+    .await?;
+    let publish = || async {
+        let mut tx = pool.begin().await?;
+        outbox
+            .publish_in_op(
+                &mut tx,
+                profile.account_id,
+                OutboxEventPayload::PayoutCancelled {
+                    id: PayoutId::new(),
+                    profile_id: profile.id,
+                    wallet_id: WalletId::new(),
+                    payout_queue_id: PayoutQueueId::new(),
+                    satoshis: Satoshis::from(10_000),
+                    destination: PayoutDestination::OnchainAddress {
+                        value: Address::parse_from_trusted_source(
+                            "bcrt1q6q79yce8vutqzpnwkxr5x8p5kxw5rc0hqqzwym",
+                        ),
+                    },
+                },
+            )
+            .await?;
+        tx.commit().await?;
+        Ok::<_, anyhow::Error>(())
+    };
+
+    let receiver = Receiver::start().await?;
+    let (id, secret) = app
+        .create_webhook_endpoint(&profile, receiver.url.clone())
+        .await?;
+
+    receiver.respond_with(500);
+    publish().await?;
+    let delivery =
+        wait_for_status(&app, &profile, id, 1, WebhookDeliveryStatus::DeadLetter).await?;
+    assert_eq!(delivery.attempts, 2);
+    assert!(delivery.last_error.is_some());
+    let requests = receiver.requests_for(1);
+    assert_eq!(requests.len(), 2);
+    for (headers, body) in &requests {
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].parse()?;
+        assert_eq!(
+            headers[SIGNATURE_HEADER],
+            format!("sha256={}", sign_payload(&secret, timestamp, body))
+        );
+        assert_eq!(headers[ENDPOINT_ID_HEADER], id.to_string());
+        let event: serde_json::Value = serde_json::from_str(body)?;
+        assert_eq!(event, delivery.event);
+    }
+
+    receiver.respond_with(200);
+    publish().await?;
+    let delivery = wait_for_status(&app, &profile, id, 2, WebhookDeliveryStatus::Delivered).await?;
+    assert_eq!(delivery.attempts, 1);
+    assert!(delivery.delivered_at.is_some());
+    let dead_letters = app
+        .list_webhook_deliveries(&profile, id, Some(WebhookDeliveryStatus::DeadLetter), None)
+        .await?;
+    assert_eq!(
+        dead_letters
+            .iter()
+            .map(|d| u64::from(d.sequence))
+            .collect::<Vec<_>>(),
+        vec![1]
+    );
+
+    app.redeliver_webhook(&profile, id, 1).await?;
+    wait_for_status(&app, &profile, id, 1, WebhookDeliveryStatus::Delivered).await?;
+    assert_eq!(receiver.requests_for(1).len(), 3);
+    assert_eq!(receiver.requests_for(2).len(), 1);
+
+    Ok(())
+}